@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*},
+    chunk_compression::*,
+    file_system::*,
+};
+
+
+
+/*
+how big a saved chunk is in each format, and how long it takes to save and read it
+the sizes are printed before the benchmarks run since criterion only measures time
+*/
+fn bench_chunk_compression(c: &mut Criterion) {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+
+    let mut group = c.benchmark_group("chunk_compression");
+
+    for (name, generate) in [
+        ("flat", (|temp_chunk_vector: &mut Vec<Vec<Vec<Block>>>, chunk_sizes| generate_flat_chunk(temp_chunk_vector, chunk_sizes)) as fn(&mut _, _)),
+        ("generated", (|temp_chunk_vector: &mut Vec<Vec<Vec<Block>>>, chunk_sizes| generate_chunk(temp_chunk_vector, (0, 0), chunk_sizes, 5)) as fn(&mut _, _)),
+    ].iter() {
+        let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+        generate(&mut temp_chunk_vector, chunk_sizes);
+        let block_types: Vec<BlockType> = get_temp_chunk_block_types(&temp_chunk_vector, chunk_sizes);
+
+        let text_size: usize = encode_chunk_blocks_text(&block_types, chunk_sizes).len();
+        let raw_size: usize = encode_chunk_blocks_raw(&block_types).len();
+        let compressed_data: Vec<u8> = encode_chunk_blocks(&block_types, chunk_sizes);
+        println!(
+            "{} chunk: text {} bytes, raw {} bytes, palette and runs {} bytes ({:.1}x smaller than text, {:.1}x smaller than raw)",
+            name,
+            text_size,
+            raw_size,
+            compressed_data.len(),
+            text_size as f64 / compressed_data.len() as f64,
+            raw_size as f64 / compressed_data.len() as f64,
+        );
+
+        group.bench_function(BenchmarkId::new("encode_text", name), |b| {
+            b.iter(|| encode_chunk_blocks_text(&block_types, chunk_sizes));
+        });
+        group.bench_function(BenchmarkId::new("encode_raw", name), |b| {
+            b.iter(|| encode_chunk_blocks_raw(&block_types));
+        });
+        group.bench_function(BenchmarkId::new("encode_compressed", name), |b| {
+            b.iter(|| encode_chunk_blocks(&block_types, chunk_sizes));
+        });
+        group.bench_function(BenchmarkId::new("decode_compressed", name), |b| {
+            b.iter(|| decode_chunk_blocks(&compressed_data, chunk_sizes).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!{
+    name = benches;
+    config = Criterion::default().sample_size(50);
+    targets = bench_chunk_compression
+}
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
+use std::collections::HashMap;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*},
+    greedy_mesh::*,
+    types::*,
+};
+
+
+
+// set the visible faces of every block in the chunk to the faces next to air (the walls of the chunk count as air)
+fn set_air_faces(temp_chunk_vector: &mut [Vec<Vec<Block>>], chunk_sizes: (usize, usize, usize)) {
+    for x in 0..chunk_sizes.0 {
+        for y in 0..chunk_sizes.1 {
+            for z in 0..chunk_sizes.2 {
+                if temp_chunk_vector[x][y][z].block_type == BlockType::Air {
+                    continue;
+                }
+
+                let mut visible_faces: u8 = 0;
+                for face in BlockFace::ALL {
+                    let offset: (i32, i16, i32) = face.offset();
+                    let neighbour: (i32, i32, i32) = (x as i32 + offset.0, y as i32 + offset.1 as i32, z as i32 + offset.2);
+                    let is_air: bool = neighbour.0 < 0 || neighbour.1 < 0 || neighbour.2 < 0
+                        || neighbour.0 >= chunk_sizes.0 as i32 || neighbour.1 >= chunk_sizes.1 as i32 || neighbour.2 >= chunk_sizes.2 as i32
+                        || temp_chunk_vector[neighbour.0 as usize][neighbour.1 as usize][neighbour.2 as usize].block_type == BlockType::Air;
+                    if is_air {
+                        visible_faces |= face.bit();
+                    }
+                }
+                temp_chunk_vector[x][y][z].set_visible_faces(visible_faces);
+            }
+        }
+    }
+}
+
+fn bench_greedy_mesh(c: &mut Criterion) {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+
+    let mut group = c.benchmark_group("greedy_mesh");
+
+    for (name, generate) in [
+        ("flat", (|temp_chunk_vector: &mut Vec<Vec<Vec<Block>>>, chunk_sizes| generate_flat_chunk(temp_chunk_vector, chunk_sizes)) as fn(&mut _, _)),
+        ("generated", (|temp_chunk_vector: &mut Vec<Vec<Vec<Block>>>, chunk_sizes| generate_chunk(temp_chunk_vector, (0, 0), chunk_sizes, 5)) as fn(&mut _, _)),
+    ].iter() {
+        let mut temp_chunk_vector_global: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+        generate(&mut temp_chunk_vector_global, chunk_sizes);
+        set_air_faces(&mut temp_chunk_vector_global, chunk_sizes);
+
+        // the chunk blocks the same way the chunk stores them
+        let mut chunk_blocks_global: HashMap<(i32, i16, i32), Block> = HashMap::new();
+        let mut instances_to_render: HashMap<(i32, i16, i32), InstanceData> = HashMap::new();
+        fill_chunk_hashmap(&mut chunk_blocks_global, &mut instances_to_render, temp_chunk_vector_global.clone(), chunk_sizes);
+
+        // the instancing path, every block touching air gets an instance
+        group.bench_function(BenchmarkId::new("instanced", name), |b| {
+            b.iter(|| {
+                let mut chunk_blocks: HashMap<(i32, i16, i32), Block> = HashMap::new();
+                let mut instances_to_render: HashMap<(i32, i16, i32), InstanceData> = HashMap::new();
+
+                fill_chunk_hashmap(&mut chunk_blocks, &mut instances_to_render, temp_chunk_vector_global.clone(), chunk_sizes);
+            });
+        });
+
+        // the greedy path, one mesh for the chunk
+        group.bench_function(BenchmarkId::new("greedy", name), |b| {
+            b.iter(|| {
+                build_greedy_mesh(&chunk_blocks_global, (0, 0), chunk_sizes, |block: &Block| block.block_type.block_colour());
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!{
+    name = benches;
+    config = Criterion::default().sample_size(100);
+    targets = bench_greedy_mesh
+}
+criterion_main!(benches);
@@ -0,0 +1,177 @@
+/*
+The one block border around a chunk that the touching air compute shader uses
+the sides of the border are filled with the edge layers of the chunks next to it, so blocks on the walls of a chunk
+only have a face showing if the block in the next chunk is transparent
+if the chunk next to it isnt loaded (and above and below the world) the border is void, so that wall isnt drawn
+once the next chunk is loaded the edge is checked again
+*/
+
+use crate::{block::*, block_type::*, chunk::Chunk};
+
+// the 4 sides of a chunk, named the same as the faces in the compute shader
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkSide {
+    Right, // +x
+    Left,  // -x
+    Back,  // +z
+    Front, // -z
+}
+
+impl ChunkSide {
+    pub fn all() -> [ChunkSide; 4] {
+        [ChunkSide::Right, ChunkSide::Left, ChunkSide::Back, ChunkSide::Front]
+    }
+
+    // the id of the chunk touching this side of a chunk
+    pub fn get_neighbour_id(&self, chunk_id: (i32, i32)) -> (i32, i32) {
+        match self {
+            ChunkSide::Right => (chunk_id.0 + 1, chunk_id.1),
+            ChunkSide::Left => (chunk_id.0 - 1, chunk_id.1),
+            ChunkSide::Back => (chunk_id.0, chunk_id.1 + 1),
+            ChunkSide::Front => (chunk_id.0, chunk_id.1 - 1),
+        }
+    }
+
+    // the side of the neighbouring chunk that touches this side
+    pub fn opposite(&self) -> ChunkSide {
+        match self {
+            ChunkSide::Right => ChunkSide::Left,
+            ChunkSide::Left => ChunkSide::Right,
+            ChunkSide::Back => ChunkSide::Front,
+            ChunkSide::Front => ChunkSide::Back,
+        }
+    }
+
+    // how many blocks wide this side is
+    pub fn get_width(&self, chunk_sizes: (usize, usize, usize)) -> usize {
+        match self {
+            ChunkSide::Right | ChunkSide::Left => chunk_sizes.2,
+            ChunkSide::Back | ChunkSide::Front => chunk_sizes.0,
+        }
+    }
+}
+
+/*
+the block types just outside of each side of a chunk, none if that chunk isnt loaded
+each layer is indexed [y * width + across], across is z for right and left and x for back and front
+*/
+pub struct ChunkBorders {
+    pub right: Option<Vec<BlockType>>,
+    pub left: Option<Vec<BlockType>>,
+    pub back: Option<Vec<BlockType>>,
+    pub front: Option<Vec<BlockType>>,
+}
+
+impl ChunkBorders {
+    // a border with no neighbours loaded, so it is all void
+    pub fn new() -> ChunkBorders {
+        ChunkBorders {
+            right: None,
+            left: None,
+            back: None,
+            front: None,
+        }
+    }
+
+    pub fn get_border(&self, side: ChunkSide) -> &Option<Vec<BlockType>> {
+        match side {
+            ChunkSide::Right => &self.right,
+            ChunkSide::Left => &self.left,
+            ChunkSide::Back => &self.back,
+            ChunkSide::Front => &self.front,
+        }
+    }
+
+    pub fn set_border(&mut self, side: ChunkSide, layer: Vec<BlockType>) {
+        match side {
+            ChunkSide::Right => self.right = Some(layer),
+            ChunkSide::Left => self.left = Some(layer),
+            ChunkSide::Back => self.back = Some(layer),
+            ChunkSide::Front => self.front = Some(layer),
+        }
+    }
+
+    // the block in the border on this side, void if that chunk isnt loaded
+    pub fn get_border_block(&self, side: ChunkSide, y: usize, across: usize, chunk_sizes: (usize, usize, usize)) -> BlockType {
+        match self.get_border(side) {
+            Some(layer) => layer[y * side.get_width(chunk_sizes) + across],
+            None => BlockType::Void,
+        }
+    }
+}
+
+impl Default for ChunkBorders {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chunk {
+    // the block types of the layer of blocks on this side of the chunk, in the same order as a border layer
+    pub fn get_edge_layer(&self, side: ChunkSide, chunk_sizes: (usize, usize, usize)) -> Vec<BlockType> {
+        get_edge_positions((self.chunk_id_x, self.chunk_id_z), side, chunk_sizes)
+            .iter()
+            .map(|block_pos| self.get_block_type(*block_pos))
+            .collect()
+    }
+}
+
+// the world positions of the layer of blocks on this side of a chunk, in the same order as a border layer
+pub fn get_edge_positions(chunk_id: (i32, i32), side: ChunkSide, chunk_sizes: (usize, usize, usize)) -> Vec<(i32, i16, i32)> {
+    let half_chunk_y: i32 = chunk_sizes.1 as i32 / 2;
+    let start_x: i32 = chunk_id.0 * chunk_sizes.0 as i32;
+    let start_z: i32 = chunk_id.1 * chunk_sizes.2 as i32;
+    let width: usize = side.get_width(chunk_sizes);
+
+    let mut positions: Vec<(i32, i16, i32)> = Vec::with_capacity(chunk_sizes.1 * width);
+    for y in 0..chunk_sizes.1 as i32 {
+        let world_y: i16 = (y - half_chunk_y) as i16;
+
+        for across in 0..width as i32 {
+            positions.push(match side {
+                ChunkSide::Right => (start_x + chunk_sizes.0 as i32 - 1, world_y, start_z + across),
+                ChunkSide::Left => (start_x, world_y, start_z + across),
+                ChunkSide::Back => (start_x + across, world_y, start_z + chunk_sizes.2 as i32 - 1),
+                ChunkSide::Front => (start_x + across, world_y, start_z),
+            });
+        }
+    }
+
+    positions
+}
+
+/*
+the block type at a position in the padded chunk, these positions are 1 more than in the chunk so the border is at 0 and size + 1
+inside the chunk it comes from the temp vector, on the sides from the borders, everything else (above, below and the corners) is void
+*/
+pub fn get_padded_block_type(
+    temp_chunk_vec: &[Vec<Vec<Block>>],
+    chunk_borders: &ChunkBorders,
+    padded_pos: (usize, usize, usize),
+    chunk_sizes: (usize, usize, usize),
+) -> BlockType {
+    let (x, y, z) = padded_pos;
+
+    let is_inside_x: bool = x >= 1 && x <= chunk_sizes.0;
+    let is_inside_y: bool = y >= 1 && y <= chunk_sizes.1;
+    let is_inside_z: bool = z >= 1 && z <= chunk_sizes.2;
+
+    if !is_inside_y {
+        return BlockType::Void;
+    }
+
+    if is_inside_x && is_inside_z {
+        temp_chunk_vec[x - 1][y - 1][z - 1].block_type
+    } else if is_inside_z && x == 0 {
+        chunk_borders.get_border_block(ChunkSide::Left, y - 1, z - 1, chunk_sizes)
+    } else if is_inside_z && x == chunk_sizes.0 + 1 {
+        chunk_borders.get_border_block(ChunkSide::Right, y - 1, z - 1, chunk_sizes)
+    } else if is_inside_x && z == 0 {
+        chunk_borders.get_border_block(ChunkSide::Front, y - 1, x - 1, chunk_sizes)
+    } else if is_inside_x && z == chunk_sizes.2 + 1 {
+        chunk_borders.get_border_block(ChunkSide::Back, y - 1, x - 1, chunk_sizes)
+    } else {
+        // a corner, no face of a block in the chunk touches these
+        BlockType::Void
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    block::*,
+    block_type::*,
+    chunk::chunk_borders::*,
+};
+
+// which way the touching air pass is run when loading a chunk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TouchingAirPass {
+    // the compute shader (check_air_compute.wgsl)
+    Gpu,
+    // the bitmask version on the cpu, for when there is no gpu to run the shader on
+    Cpu,
+}
+
+impl TouchingAirPass {
+    /*
+    pick the pass from the adapter the renderer got
+    the renderer always has an adapter (it cant draw anything without one) so this only covers software adapters,
+    they run the compute shader on the cpu anyway (and round trip a buffer per chunk to do it) so the cpu pass is used for those
+    */
+    pub fn from_adapter_info(adapter_info: &wgpu::AdapterInfo) -> TouchingAirPass {
+        match adapter_info.device_type {
+            wgpu::DeviceType::Cpu => TouchingAirPass::Cpu,
+            _ => TouchingAirPass::Gpu,
+        }
+    }
+}
+
+/*
+the same as check_for_touching_air but on the cpu, it gives exactly the same visible faces as the compute shader
+
+the padded chunk (with the borders around it, the same as the gpu buffers) is turned into bitmasks
+one row of bits along x for each (y, z), for the blocks that are transparent, fluids and not air
+then each face of a whole row is worked out at once by and-ing the row with the row next to it (or itself shifted by 1 for right and left)
+a row is split into 64 bit words so any chunk width works, and a 32 wide chunk is only 1 word
+*/
+pub fn check_for_touching_air_cpu(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    chunk_borders: &ChunkBorders,
+    chunk_sizes: (usize, usize, usize),
+) {
+    // the size of the chunk with the border around it
+    let padded_sizes: (usize, usize, usize) = (chunk_sizes.0 + 2, chunk_sizes.1 + 2, chunk_sizes.2 + 2);
+    let words_per_row: usize = padded_sizes.0.div_ceil(64);
+    let row_count: usize = padded_sizes.1 * padded_sizes.2;
+
+    // where the row of (y, z) starts in the bitmasks (both including the border)
+    let get_row_start = |y: usize, z: usize| -> usize { (y + z * padded_sizes.1) * words_per_row };
+
+    // fill the bitmasks, bit x of a row is the block at x in the padded chunk
+    let mut transparent_bits: Vec<u64> = vec![0; row_count * words_per_row];
+    let mut fluid_bits: Vec<u64> = vec![0; row_count * words_per_row];
+    let mut block_bits: Vec<u64> = vec![0; row_count * words_per_row];
+    for z in 0..padded_sizes.2 {
+        for y in 0..padded_sizes.1 {
+            let row_start: usize = get_row_start(y, z);
+            for x in 0..padded_sizes.0 {
+                let block_type: BlockType = get_padded_block_type(temp_chunk_vec, chunk_borders, (x, y, z), chunk_sizes);
+                let (word, bit) = (row_start + x / 64, 1u64 << (x % 64));
+
+                if block_type.is_transparent() {
+                    transparent_bits[word] |= bit;
+                }
+                if block_type.is_fluid() {
+                    fluid_bits[word] |= bit;
+                }
+                if block_type != BlockType::Air {
+                    block_bits[word] |= bit;
+                }
+            }
+        }
+    }
+
+    // the rows the chunk itself is in and the faces of that row, made once and reused for every row
+    let mut row_blocks: Vec<u64> = vec![0; words_per_row];
+    let mut row_fluids: Vec<u64> = vec![0; words_per_row];
+    let mut neighbour_transparent: Vec<u64> = vec![0; words_per_row];
+    let mut neighbour_fluids: Vec<u64> = vec![0; words_per_row];
+    let mut face_bits: Vec<Vec<u64>> = vec![vec![0; words_per_row]; BlockFace::ALL.len()];
+
+    for z in 0..chunk_sizes.2 {
+        for y in 0..chunk_sizes.1 {
+            // the padded position of this row is 1 more than the chunk position
+            let (padded_y, padded_z) = (y + 1, z + 1);
+            let row_start: usize = get_row_start(padded_y, padded_z);
+
+            // shifted by 1 so bit x is the block at x in the chunk (skipping the border)
+            shift_row_right(&block_bits[row_start..row_start + words_per_row], 1, &mut row_blocks);
+            shift_row_right(&fluid_bits[row_start..row_start + words_per_row], 1, &mut row_fluids);
+
+            // the row next to each face and how far to shift it, in the same order as BlockFace::ALL
+            // right and left are the same row as the block, just 1 block further along or back
+            let neighbours: [(usize, u32); 6] = [
+                (get_row_start(padded_y + 1, padded_z), 1), // top
+                (get_row_start(padded_y - 1, padded_z), 1), // bottom
+                (row_start, 2),                             // right
+                (row_start, 0),                             // left
+                (get_row_start(padded_y, padded_z + 1), 1), // back
+                (get_row_start(padded_y, padded_z - 1), 1), // front
+            ];
+
+            for (face_index, (neighbour_start, shift)) in neighbours.iter().enumerate() {
+                shift_row_right(&transparent_bits[*neighbour_start..*neighbour_start + words_per_row], *shift, &mut neighbour_transparent);
+                shift_row_right(&fluid_bits[*neighbour_start..*neighbour_start + words_per_row], *shift, &mut neighbour_fluids);
+
+                // a face is showing if the block next to it is transparent, unless they are both fluids (same as the shader)
+                for word in 0..words_per_row {
+                    face_bits[face_index][word] = row_blocks[word]
+                        & neighbour_transparent[word]
+                        & !(row_fluids[word] & neighbour_fluids[word]);
+                }
+            }
+
+            // put the faces back into the blocks
+            for (x, blocks_x) in temp_chunk_vec.iter_mut().enumerate() {
+                let (word, bit) = (x / 64, x % 64);
+                let mut visible_faces: u8 = 0;
+                for (face_index, face) in BlockFace::ALL.iter().enumerate() {
+                    if (face_bits[face_index][word] >> bit) & 1 == 1 {
+                        visible_faces |= face.bit();
+                    }
+                }
+                blocks_x[y][z].set_visible_faces(visible_faces);
+            }
+        }
+    }
+}
+
+// shift a row of bits down by shift (less than 64) into result, so bit x of the result is bit x + shift of the row
+fn shift_row_right(row: &[u64], shift: u32, result: &mut [u64]) {
+    for word in 0..row.len() {
+        result[word] = if shift == 0 {
+            row[word]
+        } else {
+            // the bits from the start of the next word move to the top of this one
+            let next_word: u64 = if word + 1 < row.len() { row[word + 1] } else { 0 };
+            (row[word] >> shift) | (next_word << (64 - shift))
+        };
+    }
+}
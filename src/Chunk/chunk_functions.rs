@@ -1,59 +1,111 @@
 use crate::{
+    biome::*,
     block::*, 
     block_type::*, 
+    error::*,
     file_system::*, 
-    renderer::*, 
+    render_backend::*, 
     types::*, 
-    chunk::create_chunks::generate_chunk,
-    chunk::chunk_gpu_functions::check_for_touching_air,
+    world_generator::*,
+    chunk::{chunk_borders::*, decorate_chunks::*},
 };
 
-use async_std::task;
 use std::collections::{HashMap, HashSet};
 
+// what a chunk is generated with and what it is put next to, from the world it is being loaded into
+pub struct ChunkGenerationContext<'a> {
+    pub generator: &'a dyn WorldGenerator,
+    pub world_seed: u64,
+
+    // decorations from this chunk that reach into other chunks go in here, and ones from other chunks that reach into it are taken out
+    pub pending_block_writes: &'a mut PendingBlockWrites,
+
+    // the edges of the chunks around it, for the touching air pass
+    pub chunk_borders: &'a ChunkBorders,
+}
+
 impl super::Chunk {
     // if this chunk has beenc created before then i create a Chunk obj, and fill it from wherever
+    // an error if its file cant be read (see read_or_generate_chunk), then the chunk is left empty
     pub fn load_chunk(
         &mut self,
         file_system: &mut FileSystem,
-        renderer: &Renderer,
+        render_backend: &dyn RenderBackend,
         chunk_sizes: (usize, usize, usize),
         created_chunks: &mut HashSet<(i32, i32)>,
+        context: ChunkGenerationContext,
+    ) -> Result<(), GameError> {
+        // fill the temp vector with data, from its file if it has been created before
+        let chunk_id: (i32, i32) = (self.chunk_id_x, self.chunk_id_z);
+        let is_created: bool = created_chunks.contains(&chunk_id);
+        let temp_chunk_vec: Vec<Vec<Vec<Block>>> = read_or_generate_chunk(
+            file_system,
+            chunk_id,
+            is_created,
+            chunk_sizes,
+            context.generator,
+            context.world_seed,
+            context.pending_block_writes,
+        )?;
+
+        // add this chunk to created chunks
+        if !is_created {
+            created_chunks.insert(chunk_id);
+        }
+
+        // the biomes are worked out from the seed again rather than saved, so loaded chunks get them too
+        let biomes: Vec<Vec<Biome>> = get_chunk_biomes(chunk_id, chunk_sizes, context.generator, context.world_seed);
+
+        self.finish_loading_chunk(
+            temp_chunk_vec,
+            biomes,
+            render_backend,
+            chunk_sizes,
+            context.pending_block_writes,
+            context.chunk_borders,
+        );
+
+        Ok(())
+    }
+
+    /*
+    the rest of loading a chunk once its blocks have been read or generated, when it is loaded on the main thread
+    since it needs the chunks around it and the gpu (chunks from the workers have already had the touching air pass, see fill_from_checked_blocks)
+    */
+    pub fn finish_loading_chunk(
+        &mut self,
+        mut temp_chunk_vec: Vec<Vec<Vec<Block>>>,
+        biomes: Vec<Vec<Biome>>,
+        render_backend: &dyn RenderBackend,
+        chunk_sizes: (usize, usize, usize),
+        pending_block_writes: &mut PendingBlockWrites,
+        chunk_borders: &ChunkBorders,
     ) {
-        // create the temp chunk Vector, which creates all blocks
-        let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((self.chunk_id_x, self.chunk_id_z), chunk_sizes);
-
-        // fill the temp vector with data
-        // first check if the chunk has been created before if so load it
-        if created_chunks.contains(&(self.chunk_id_x, self.chunk_id_z)) {
-            // now check if it is loaded, if it is then i can just ignore it, if it isnt loaded then i need to read it from a file
-            // it will already be loaded if i try to load a chunk already in the game
-            // has been created before so load from file
-            file_system.read_chunks_from_file(
-                &mut temp_chunk_vec,
-                self.chunk_id_x,
-                self.chunk_id_z,
-                chunk_sizes,
-            );
-        } else {
-            // else create a new one
-            generate_chunk(&mut temp_chunk_vec, chunk_sizes);
+        // put in any blocks from features (like trees) in other chunks that reached into this one
+        // this has to be after decorating so this chunks own trees dont see them
+        let block_writes = pending_block_writes.take_chunk_writes((self.chunk_id_x, self.chunk_id_z));
+        apply_block_writes(&mut temp_chunk_vec, &block_writes, chunk_sizes);
 
-            // add this chunk to created chunks
-            created_chunks.insert((self.chunk_id_x, self.chunk_id_z));
-        }
+        // check each block if it is touching air (on the gpu if there is one)
+        // the borders are the edges of the chunks next to this one so the blocks on its walls are checked against them
+        render_backend.check_for_touching_air(&mut temp_chunk_vec, chunk_borders, chunk_sizes);
 
-        // check each block if it is touching air (async because reading from gpu is async)
-        task::block_on(check_for_touching_air(
-            &mut temp_chunk_vec, 
-            &renderer.device, 
-            &renderer.queue, 
-            &renderer.check_air_compute_shader_code, 
-            chunk_sizes
-        ));
+        self.fill_from_checked_blocks(temp_chunk_vec, biomes, render_backend, chunk_sizes);
+    }
+
+    // put the blocks into the chunk and make its buffers, once their visible faces have been set by the touching air pass
+    pub fn fill_from_checked_blocks(
+        &mut self,
+        temp_chunk_vec: Vec<Vec<Vec<Block>>>,
+        biomes: Vec<Vec<Biome>>,
+        render_backend: &dyn RenderBackend,
+        chunk_sizes: (usize, usize, usize),
+    ) {
+        self.biomes = biomes;
 
         // fill the chunkBlocks hashmap from the temp vector
         fill_chunk_hashmap(&mut self.chunk_blocks, &mut self.instances_to_render, temp_chunk_vec, chunk_sizes);
+        self.apply_biome_colours();
 
         // update the number of alive blocks
         self.alive_blocks = self.chunk_blocks.len() as u32;
@@ -61,16 +113,16 @@ impl super::Chunk {
         // update instance size
         self.instance_size = self.instances_to_render.len() as u32;
         if self.instance_size > self.instance_capacity {
-            self.update_instance_buffers_capacity(renderer);
+            self.update_instance_buffers_capacity(render_backend);
         }
 
-        self.update_instance_staging_buffer(renderer);
+        self.update_instance_staging_buffer(render_backend);
     }
 
     
 
     // this is called on each chunk per frame so i can do updates if needed
-    pub fn update(&mut self, renderer: &Renderer) {
+    pub fn update(&mut self, render_backend: &dyn RenderBackend) {
         // first check if there is a new instance buffer to be updated
         if self.creating_new_instance_buffers {
             // check if the new instance buffer is finished being written to
@@ -89,7 +141,100 @@ impl super::Chunk {
             // set creating new instance buffers to false
         } else {
             // if i am not currently creating new instance buffers
-            self.update_instance_buffer(renderer);
+            // first send any instances changed on the cpu to the staging buffer
+            self.update_changed_instances(render_backend);
+
+            self.update_instance_buffer(render_backend);
+        }
+    }
+
+    // get the type of the block at this world position, if nothing is stored there it is air
+    pub fn get_block_type(&self, block_pos: (i32, i16, i32)) -> BlockType {
+        match self.chunk_blocks.get(&block_pos) {
+            Some(block) => block.block_type,
+            None => BlockType::Air,
+        }
+    }
+
+    // put a block into the chunk (or remove whatever is there if it is air) and keep everything else in sync with it
+    // the block passed in should already have is_touching_air set correctly
+    pub fn set_block(&mut self, block: Block) {
+        let key: (i32, i16, i32) = (block.position.x, block.position.y, block.position.z);
+
+        if block.block_type == BlockType::Air {
+            self.chunk_blocks.remove(&key);
+        } else {
+            self.chunk_blocks.insert(key, block);
+        }
+
+        self.alive_blocks = self.chunk_blocks.len() as u32;
+
+        self.update_block_instance(key);
+    }
+
+    // update which faces of a block in this chunk are showing, and add, change or remove its instance to match
+    // does nothing if there is no block at this position
+    pub fn set_block_visible_faces(&mut self, block_pos: (i32, i16, i32), visible_faces: u8) {
+        if let Some(block) = self.chunk_blocks.get_mut(&block_pos) {
+            if block.visible_faces != visible_faces {
+                block.set_visible_faces(visible_faces);
+                self.update_block_instance(block_pos);
+            }
+        }
+    }
+
+    // make sure the block at this position is only in the instances to render if it is touching air
+    pub fn update_block_instance(&mut self, block_pos: (i32, i16, i32)) {
+        let changed: bool = match self.chunk_blocks.get(&block_pos) {
+            Some(block) if block.is_touching_air => {
+                self.instances_to_render.insert(
+                    block_pos,
+                    InstanceData {
+                        model_matrix: block.model_matrix,
+                        colour: self.get_block_colour(block),
+                        visible_faces: block.visible_faces as u32,
+                    },
+                );
+                true
+            }
+            _ => self.instances_to_render.remove(&block_pos).is_some(),
+        };
+
+        // the greedy mesh is built from the blocks so it needs rebuilding whenever one of them changes
+        self.mesh_outdated = true;
+
+        // so the next update sends the new instances to the gpu
+        if changed {
+            self.instances_to_render_changed = true;
+        }
+    }
+
+    // the biome of the column this world position is in, none if this chunk doesnt have biomes
+    pub fn get_biome(&self, world_x: i32, world_z: i32) -> Option<Biome> {
+        get_column_biome(&self.biomes, world_x, world_z)
+    }
+
+    // the colour to draw this block with, some blocks (like grass) are tinted by the biome they are in
+    pub fn get_block_colour(&self, block: &Block) -> [f32; 4] {
+        match self.get_biome(block.position.x, block.position.z) {
+            Some(biome) => biome.get_block_colour(block.block_type),
+            None => block.block_type.block_colour(),
+        }
+    }
+
+    // fill chunk hashmap gives every instance its plain block colour, so go back and tint the ones that change per biome
+    pub fn apply_biome_colours(&mut self) {
+        if self.biomes.is_empty() {
+            return;
+        }
+
+        for (block_pos, instance) in self.instances_to_render.iter_mut() {
+            if let (Some(block), Some(biome)) = (
+                self.chunk_blocks.get(block_pos),
+                get_column_biome(&self.biomes, block_pos.0, block_pos.2),
+            ) {
+                instance.colour = biome.get_block_colour(block.block_type);
+            }
         }
     }
 }
@@ -139,7 +284,124 @@ pub fn create_temp_chunk_vector(chunk_ids: (i32, i32), chunk_sizes: (usize, usiz
 }
 
 
+// the biome of every column in a chunk from the world generator, [x][z] relative to the chunk
+// empty if the generator doesnt use biomes
+pub fn get_chunk_biomes(
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    generator: &dyn WorldGenerator,
+    world_seed: u64,
+) -> Vec<Vec<Biome>> {
+    let mut biomes: Vec<Vec<Biome>> = Vec::with_capacity(chunk_sizes.0);
+
+    for x in 0..chunk_sizes.0 {
+        let mut column_x: Vec<Biome> = Vec::with_capacity(chunk_sizes.2);
+
+        for z in 0..chunk_sizes.2 {
+            let world_x: i32 = (chunk_ids.0 * chunk_sizes.0 as i32) + x as i32;
+            let world_z: i32 = (chunk_ids.1 * chunk_sizes.2 as i32) + z as i32;
+
+            match generator.get_biome(world_x, world_z, world_seed) {
+                Some(biome) => column_x.push(biome),
+                None => return Vec::new(),
+            }
+        }
+
+        biomes.push(column_x);
+    }
+
+    biomes
+}
+
+
+// look up the biome of a world column in a chunks biomes, the chunk size is just the length of the vectors
+fn get_column_biome(biomes: &[Vec<Biome>], world_x: i32, world_z: i32) -> Option<Biome> {
+    if biomes.is_empty() || biomes[0].is_empty() {
+        return None;
+    }
+
+    let relative_x: usize = world_x.rem_euclid(biomes.len() as i32) as usize;
+    let relative_z: usize = world_z.rem_euclid(biomes[0].len() as i32) as usize;
+
+    Some(biomes[relative_x][relative_z])
+}
+
+
 // once the temp chunk vector has all the blocks in it correctly ill fill them into the hashmap to save space on non air blocks
+/*
+read a chunk from its file if it has been created before, otherwise generate and decorate a new one
+any decorations that reach into other chunks are added to the pending block writes
+if a created chunk is corrupted it is quarantined and generated again, so a corrupted file doesnt stop the game
+but any other error (like a chunk from a newer version, or not being allowed to read the file) is given back
+so the chunk isnt generated again and saved over the one that is there
+quarantining changes the region files, so this is only used on the main thread (the workers use try_read_or_generate_chunk)
+*/
+pub fn read_or_generate_chunk(
+    file_system: &mut FileSystem,
+    chunk_id: (i32, i32),
+    is_created: bool,
+    chunk_sizes: (usize, usize, usize),
+    generator: &dyn WorldGenerator,
+    world_seed: u64,
+    pending_block_writes: &mut PendingBlockWrites,
+) -> Result<Vec<Vec<Vec<Block>>>, GameError> {
+    match try_read_or_generate_chunk(file_system, chunk_id, is_created, chunk_sizes, generator, world_seed, pending_block_writes) {
+        Err(e @ GameError::CorruptedChunk(..)) => {
+            quarantine_corrupted_chunk(file_system, &e, chunk_id, chunk_sizes);
+            try_read_or_generate_chunk(file_system, chunk_id, false, chunk_sizes, generator, world_seed, pending_block_writes)
+        }
+        result => result,
+    }
+}
+
+/*
+the same as read_or_generate_chunk but a corrupted chunk is given back as an error too, instead of being quarantined
+this only reads the files so it can be run on a worker thread, the main thread quarantines the chunk (it is the only one that writes to the regions)
+*/
+pub fn try_read_or_generate_chunk(
+    file_system: &mut FileSystem,
+    chunk_id: (i32, i32),
+    is_created: bool,
+    chunk_sizes: (usize, usize, usize),
+    generator: &dyn WorldGenerator,
+    world_seed: u64,
+    pending_block_writes: &mut PendingBlockWrites,
+) -> Result<Vec<Vec<Vec<Block>>>, GameError> {
+    // create the temp chunk Vector, which creates all blocks
+    let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_id, chunk_sizes);
+
+    if is_created {
+        // has been created before so load from file
+        match file_system.read_chunks_from_file(&mut temp_chunk_vec, chunk_id.0, chunk_id.1, chunk_sizes) {
+            Ok(()) => return Ok(temp_chunk_vec),
+            // there is nothing to keep so it can just be generated
+            Err(e @ GameError::ChunkFileMissing(..)) => eprintln!("{}, it will be generated again", e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    // not created before (or it couldnt be read) so create a new one
+    generator.generate_chunk(&mut temp_chunk_vec, chunk_id, chunk_sizes, world_seed);
+    generator.decorate_chunk(
+        &mut temp_chunk_vec,
+        chunk_id,
+        chunk_sizes,
+        world_seed,
+        pending_block_writes,
+    );
+
+    Ok(temp_chunk_vec)
+}
+
+// move a corrupted chunk into Quarantine/ so it can be generated again, and say where it went (only on the main thread)
+pub fn quarantine_corrupted_chunk(file_system: &mut FileSystem, e: &GameError, chunk_id: (i32, i32), chunk_sizes: (usize, usize, usize)) {
+    match file_system.quarantine_chunk(chunk_id, chunk_sizes) {
+        Ok(Some(quarantine_path)) => eprintln!("{}, it was moved to {:?} and will be generated again", e, quarantine_path),
+        Ok(None) => eprintln!("{}, it will be generated again", e),
+        Err(quarantine_e) => eprintln!("{}, it could not be moved out of the way ({}) but will be generated again", e, quarantine_e),
+    }
+}
+
 pub fn fill_chunk_hashmap( 
     chunk_blocks: &mut HashMap<(i32, i16, i32), Block>, 
     instances_to_render: &mut HashMap<(i32, i16, i32), InstanceData>,
@@ -180,6 +442,7 @@ pub fn fill_chunk_hashmap(
                             InstanceData {
                                 model_matrix: cached_block.model_matrix.clone(),
                                 colour: cached_block.block_type.block_colour(),
+                                visible_faces: cached_block.visible_faces as u32,
                             }
                         ));
                     }
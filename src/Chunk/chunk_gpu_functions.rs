@@ -2,7 +2,7 @@
 This is where any chunk functions related to gpu buffers and compute shaders will live
 */
 
-use crate::{chunk::*, renderer::*};
+use crate::{block_type::*, chunk::{chunk_borders::*, *}, greedy_mesh::*, render_backend::*};
 
 use std::mem;
 use wgpu::{Device, Queue, ShaderModule};
@@ -10,27 +10,33 @@ use wgpu::util::DeviceExt;
 
 impl Chunk {
     // each frame call this on each chunk. it will update the instance buffer if the instances have been modified
-    pub fn update_instance_buffer(&mut self, renderer: &Renderer) {
+    pub fn update_instance_buffer(&mut self, render_backend: &dyn RenderBackend) {
+        // there is nothing to update without a gpu
+        let (device, queue, instance_buffers) = match (render_backend.get_gpu(), &self.instance_buffers) {
+            (Some((device, queue)), Some(instance_buffers)) => (device, queue, instance_buffers),
+            _ => return,
+        };
+
         // if the instances have been modified then update the instance buffer
         if self.instances_modified {
             // and if the staging buffer is finished writing
             let staging_buffer_writing = self.staging_buffer_writing.lock().unwrap();
             if !*staging_buffer_writing {
                 // copy the staging buffer to the instance buffer
-                let mut encoder = renderer.device.create_command_encoder(
+                let mut encoder = device.create_command_encoder(
                     &(wgpu::CommandEncoderDescriptor {
                         label: Some("instance buffer copy encoder"),
                     }),
                 );
                 encoder.copy_buffer_to_buffer(
-                    &self.instance_staging_buffer,
+                    &instance_buffers.instance_staging_buffer,
                     0,
-                    &self.instance_buffer,
+                    &instance_buffers.instance_buffer,
                     0,
                     ((self.instance_size as usize) * mem::size_of::<InstanceData>())
                         as wgpu::BufferAddress,
                 );
-                renderer.queue.submit(Some(encoder.finish()));
+                queue.submit(Some(encoder.finish()));
 
                 // set the flag to false
                 self.instances_modified = false;
@@ -39,7 +45,13 @@ impl Chunk {
     }
 
     // update the instance buffer for the chunk with the instances
-    pub fn update_instance_staging_buffer(&mut self, renderer: &Renderer) {
+    pub fn update_instance_staging_buffer(&mut self, render_backend: &dyn RenderBackend) {
+        // there is nothing to write to without a gpu
+        let (queue, instance_buffers) = match (render_backend.get_gpu(), &self.instance_buffers) {
+            (Some((_, queue)), Some(instance_buffers)) => (queue, instance_buffers),
+            _ => return,
+        };
+
         let new_instance_buffers_writing = self.new_instance_buffers_writing.lock().unwrap();
 
         // only start staging buffer commands if no new instance buffers are being created and written to
@@ -53,14 +65,14 @@ impl Chunk {
             let instances_slice = instances_vector.as_slice();
 
             // update the staging gpu buffers and set the flag that this data has changed
-            renderer.queue.write_buffer(
-                &self.instance_staging_buffer,
+            queue.write_buffer(
+                &instance_buffers.instance_staging_buffer,
                 0,
                 bytemuck::cast_slice(instances_slice),
             );
 
             // submit the write command
-            renderer.queue.submit(std::iter::empty());
+            queue.submit(std::iter::empty());
 
             self.instances_modified = true;
 
@@ -72,7 +84,7 @@ impl Chunk {
             *staging_buffer_writing = true;
 
             // Set up a callback to run when the instance buffer copy is finished
-            renderer.queue.on_submitted_work_done(move || {
+            queue.on_submitted_work_done(move || {
                 // set the staging buffer writing flag to false so i know its finished writing
                 let mut staging_buffer_writing_gpu = staging_buffer_writing_clone.lock().unwrap();
                 *staging_buffer_writing_gpu = false;
@@ -81,10 +93,33 @@ impl Chunk {
         }
     }
 
+    // if the instances to render were changed on the cpu since the last update, send them to the gpu
+    // this is only called when new instance buffers are not being created so the staging buffer is safe to write to
+    pub fn update_changed_instances(&mut self, render_backend: &dyn RenderBackend) {
+        if !self.instances_to_render_changed {
+            return;
+        }
+
+        self.instance_size = self.instances_to_render.len() as u32;
+        if self.instance_size > self.instance_capacity {
+            // the new buffers get filled with the instances when they are made
+            self.update_instance_buffers_capacity(render_backend);
+        } else {
+            self.update_instance_staging_buffer(render_backend);
+        }
+
+        self.instances_to_render_changed = false;
+    }
+
     // increase instance buffers capasity
     // this will be called when i try to add blocks to the instance buffer but its size exceeds its capasity
-    pub fn update_instance_buffers_capacity(&mut self, renderer: &Renderer) {
-        
+    pub fn update_instance_buffers_capacity(&mut self, render_backend: &dyn RenderBackend) {
+        // without a gpu there are no buffers to grow
+        let (device, queue) = match (render_backend.get_gpu(), &self.instance_buffers) {
+            (Some(gpu), Some(_)) => gpu,
+            _ => return,
+        };
+
         self.creating_new_instance_buffers = true;
 
         let mut capacity_increase_amount: u32 = 100;
@@ -108,15 +143,17 @@ impl Chunk {
         self.new_instance_capacity = self.instance_capacity + capacity_increase_amount;
 
         // dont overwrite the staging yet since it might have commands being done
-        self.new_instance_staging_buffer = renderer.device.create_buffer(&BufferDescriptor {
+        // (it is copied from once it becomes the staging buffer so it needs COPY_SRC)
+        let instance_buffers: &mut InstanceBuffers = self.instance_buffers.as_mut().unwrap();
+        instance_buffers.new_instance_staging_buffer = device.create_buffer(&BufferDescriptor {
             label: Some("Instance Staging Buffer"),
             size: (std::mem::size_of::<InstanceData>() * self.new_instance_capacity as usize)
                 as wgpu::BufferAddress,
-            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
-        self.new_instance_buffer = renderer.device.create_buffer(&BufferDescriptor {
+        instance_buffers.new_instance_buffer = device.create_buffer(&BufferDescriptor {
             label: Some("Instance Buffer"),
             size: (std::mem::size_of::<InstanceData>() * self.new_instance_capacity as usize)
                 as wgpu::BufferAddress,
@@ -133,34 +170,79 @@ impl Chunk {
         let instances_slice = instances_vector.as_slice();
 
         // update the new actual gpu buffer and set the flag that this data has changed
-        renderer.queue.write_buffer(
-            &self.new_instance_buffer,
+        queue.write_buffer(
+            &instance_buffers.new_instance_buffer,
+            0,
+            bytemuck::cast_slice(instances_slice),
+        );
+        // the staging buffer gets the same data so a later copy from it doesnt overwrite the instances with nothing
+        queue.write_buffer(
+            &instance_buffers.new_instance_staging_buffer,
             0,
             bytemuck::cast_slice(instances_slice),
         );
+        // any copy from the old staging buffer is now out of date
+        self.instances_modified = false;
         // submit the write command
-        renderer.queue.submit(std::iter::empty());
+        queue.submit(std::iter::empty());
 
         // update the fence for the new buffers so i know not to update the old ones anymore
         let new_instance_buffers_writing_clone = self.new_instance_buffers_writing.clone();
         let mut new_instance_buffers_writing = self.new_instance_buffers_writing.lock().unwrap();
         *new_instance_buffers_writing = true;
 
-        renderer.queue.on_submitted_work_done(move || {
+        queue.on_submitted_work_done(move || {
             // set the staging buffer writing flag to false so i know its finished writing
             let mut new_instance_buffers_writing = new_instance_buffers_writing_clone.lock().unwrap();
             *new_instance_buffers_writing = false;
         });
     }
 
+    // build the greedy mesh for this chunk and put it into new vertex and index buffers (the old ones are freed when they are dropped)
+    pub fn update_mesh(&mut self, render_backend: &dyn RenderBackend, chunk_sizes: (usize, usize, usize)) {
+        // the mesh only lives on the gpu
+        let device: &Device = match render_backend.get_gpu() {
+            Some((device, _)) => device,
+            None => return,
+        };
+
+        let mesh: ChunkMesh = build_greedy_mesh(
+            &self.chunk_blocks,
+            (self.chunk_id_x, self.chunk_id_z),
+            chunk_sizes,
+            |block: &Block| self.get_block_colour(block),
+        );
+
+        self.mesh_index_count = mesh.indices.len() as u32;
+        if mesh.is_empty() {
+            self.mesh_vertex_buffer = None;
+            self.mesh_index_buffer = None;
+        } else {
+            self.mesh_vertex_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Chunk Mesh Vertex Buffer"),
+                contents: bytemuck::cast_slice(&mesh.vertices),
+                usage: BufferUsages::VERTEX,
+            }));
+            self.mesh_index_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Chunk Mesh Index Buffer"),
+                contents: bytemuck::cast_slice(&mesh.indices),
+                usage: BufferUsages::INDEX,
+            }));
+        }
+
+        self.mesh_outdated = false;
+    }
+
     // once the new buffer has finished being written to with new data i can overwrite the old buffers
     // since no more commands will be pushed to the old buffers since i started making these new ones all old buffer commands SHOULD be finished at this point
     pub fn overwrite_old_instance_buffers(&mut self) {
         // overwrite the old buffers with the new ones
-        self.instance_buffer.destroy();
+        if let Some(instance_buffers) = self.instance_buffers.as_mut() {
+            instance_buffers.instance_buffer.destroy();
 
-        mem::swap(&mut self.instance_buffer, &mut self.new_instance_buffer);
-        mem::swap(&mut self.instance_staging_buffer, &mut self.new_instance_staging_buffer);
+            mem::swap(&mut instance_buffers.instance_buffer, &mut instance_buffers.new_instance_buffer);
+            mem::swap(&mut instance_buffers.instance_staging_buffer, &mut instance_buffers.new_instance_staging_buffer);
+        }
 
         self.creating_new_instance_buffers = false;
 
@@ -175,6 +257,7 @@ impl Chunk {
     // this is run once on chunk creation
     pub async fn check_for_touching_air(
         temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_borders: &ChunkBorders,
         device: &Device,
         queue: &Queue,
         shader_code: &ShaderModule,
@@ -185,9 +268,9 @@ impl Chunk {
         buffer 1: hold all block types (as u16)
         buffer 2: hold a bool transperency value of that block
 
-        each buffer will also have a boarder around 1 thick
-        and ill put the touching layer of the next chunk there so it can calulate on that too
-        if the next chunk doesnt exist then ill just put void there.
+        each buffer also has a boarder around 1 thick
+        and the touching layer of the next chunk is put there (from chunk borders) so it can calulate on that too
+        if the next chunk doesnt exist then void is put there. above and below the chunk is always void
 
         buffer 2 is needed so i can easily check if a block is touching air, instead of trying to iterate though
         an array checking the block against all block types that are transparent
@@ -195,46 +278,37 @@ impl Chunk {
         the buffer will just hold the block type, exact position isnt necessary just relative to other blocks is,
         which is its index
 
-        the gpus output buffer is a boolean buffer of true if touching air and false otherwise
+        the gpus output buffer has the faces of each block that are touching air, one bit per face (in the order of BlockFace::ALL)
 
         all buffers need to be of type u32. booleans dont work between rust and gpu
         and wgsl only supports these types: f32, i32, u32, bool
 
         */
 
-        // create the block types array
-        let mut chunk_block_types: Vec<u32> = Vec::with_capacity(chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2);
-        for z in 0..chunk_sizes.2 as i32 {
-            for y in 0..chunk_sizes.1 as i16 {
-                for x in 0..chunk_sizes.0 as i32 {
-                    chunk_block_types.push(
-                        temp_chunk_vec[x as usize][y as usize][z as usize]
-                            .block_type
-                            .to_int() as u32,
-                    );
+        // the size of the chunk with the border around it
+        let padded_sizes: (usize, usize, usize) = (chunk_sizes.0 + 2, chunk_sizes.1 + 2, chunk_sizes.2 + 2);
+        let padded_length: usize = padded_sizes.0 * padded_sizes.1 * padded_sizes.2;
+
+        // create the block types, block transparancy and block fluid arrays
+        let mut chunk_block_types: Vec<u32> = Vec::with_capacity(padded_length);
+        let mut chunk_block_transparency: Vec<u32> = Vec::with_capacity(padded_length);
+        let mut chunk_block_fluid: Vec<u32> = Vec::with_capacity(padded_length);
+        for z in 0..padded_sizes.2 {
+            for y in 0..padded_sizes.1 {
+                for x in 0..padded_sizes.0 {
+                    let block_type: BlockType = get_padded_block_type(temp_chunk_vec, chunk_borders, (x, y, z), chunk_sizes);
+                    chunk_block_types.push(block_type.to_int() as u32);
+                    chunk_block_transparency.push(block_type.is_transparent() as u32);
+                    chunk_block_fluid.push(block_type.is_fluid() as u32);
                 }
             }
         }
 
-        // create the block transparancy array
-        let mut chunk_block_transparency: Vec<u32> = Vec::with_capacity(chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2);
-        for z in 0..chunk_sizes.2 as i32 {
-            for y in 0..chunk_sizes.1 as i16 {
-                for x in 0..chunk_sizes.0 as i32 {
-                    chunk_block_transparency.push(
-                        temp_chunk_vec[x as usize][y as usize][z as usize]
-                            .block_type
-                            .is_transparent() as u32,
-                    );
-                }
-            }
-        }
-
-        // create the dimentions buffer so the gpu knows the max of xyz
+        // create the dimentions buffer so the gpu knows the max of xyz (including the border)
         let dimentions: [u32; 3] = [
-            chunk_sizes.0 as u32,
-            chunk_sizes.1 as u32,
-            chunk_sizes.2 as u32,
+            padded_sizes.0 as u32,
+            padded_sizes.1 as u32,
+            padded_sizes.2 as u32,
         ];
 
         // now the resulting buffer (cant use bool with the gpu, since rust bools arnt guarenteed to be 1 byte)
@@ -257,6 +331,14 @@ impl Chunk {
             }),
         );
 
+        let block_fluid_buffer = device.create_buffer_init(
+            &(wgpu::util::BufferInitDescriptor {
+                label: Some("block fluid compute buffer"),
+                contents: bytemuck::cast_slice(&chunk_block_fluid),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            }),
+        );
+
         let dimentions_buffer = device.create_buffer_init(
             &(wgpu::util::BufferInitDescriptor {
                 label: Some("block transparency compute buffer"),
@@ -265,9 +347,9 @@ impl Chunk {
             }),
         );
 
+        // the result buffer has the border too so it uses the same indexes, the border is never written to
         let result_buffer_size: wgpu::BufferAddress =
-            (chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2 * mem::size_of::<u32>())
-                as wgpu::BufferAddress;
+            (padded_length * mem::size_of::<u32>()) as wgpu::BufferAddress;
         let result_buffer = device.create_buffer(
             &(wgpu::BufferDescriptor {
                 label: Some("Result compute buffer"),
@@ -311,7 +393,7 @@ impl Chunk {
                         },
                         count: None,
                     },
-                    // dimentions_buffer
+                    // block_fluid_buffer
                     wgpu::BindGroupLayoutEntry {
                         binding: 2,
                         visibility: wgpu::ShaderStages::COMPUTE,
@@ -322,10 +404,21 @@ impl Chunk {
                         },
                         count: None,
                     },
-                    // result_buffer
+                    // dimentions_buffer
                     wgpu::BindGroupLayoutEntry {
                         binding: 3,
                         visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    // result_buffer
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Storage { read_only: false },
                             has_dynamic_offset: false,
@@ -352,10 +445,14 @@ impl Chunk {
                     },
                     wgpu::BindGroupEntry {
                         binding: 2,
-                        resource: dimentions_buffer.as_entire_binding(),
+                        resource: block_fluid_buffer.as_entire_binding(),
                     },
                     wgpu::BindGroupEntry {
                         binding: 3,
+                        resource: dimentions_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
                         resource: result_buffer.as_entire_binding(),
                     },
                 ],
@@ -398,7 +495,7 @@ impl Chunk {
                 chunk_sizes.0 as u32,
                 chunk_sizes.1 as u32,
                 chunk_sizes.2 as u32,
-            ); // Number of cells to run, the (x,y,z) size of item being processed
+            ); // Number of cells to run, the (x,y,z) size of item being processed (just the chunk, not the border)
         }
 
         // submit the compute shader render pass command
@@ -521,13 +618,13 @@ impl Chunk {
         }
         */
 
-        // update the blocks with the results
+        // update the blocks with the results (skipping over the border)
         let mut index: usize;
         for y in 0..chunk_sizes.1 {
             for z in 0..chunk_sizes.2 {
                 for x in 0..chunk_sizes.0 {
-                    index = x + (y * chunk_sizes.0) + (z * chunk_sizes.0 * chunk_sizes.1);
-                    temp_chunk_vec[x][y][z].is_touching_air = result[index] != 0;
+                    index = (x + 1) + ((y + 1) * padded_sizes.0) + ((z + 1) * padded_sizes.0 * padded_sizes.1);
+                    temp_chunk_vec[x][y][z].set_visible_faces(result[index] as u8);
                 }
             }
         }
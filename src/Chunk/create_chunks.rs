@@ -1,14 +1,102 @@
 /*
 This file will be all of the rules for creating chunks and all the blocks within them
  */
-use crate::{block::*, block_type::*};
+use crate::{biome::*, block::*, block_type::*, noise::*};
 
 impl super::Chunk {
-    
 
-    
+
+
 }
 
+// terrain settings
+// how wide the hills are in blocks, bigger is smoother
+const TERRAIN_SCALE: f64 = 96.0;
+
+// how many layers of noise make up the height map, and how much each layer is scaled by compared to the last
+const TERRAIN_OCTAVES: u32 = 4;
+const TERRAIN_PERSISTENCE: f64 = 0.5;
+const TERRAIN_LACUNARITY: f64 = 2.0;
+
+// the terrain goes up and down by (chunk height / this) blocks around the middle of the chunk
+const TERRAIN_HEIGHT_DIVISOR: usize = 16;
+
+// cave settings
+// caves are carved wherever two 3d noise values are both close to 0, the places where that happens make long winding tunnels
+// the noise is squashed vertically so the tunnels are wider than they are tall
+const CAVE_SCALE_HORIZONTAL: f64 = 40.0;
+const CAVE_SCALE_VERTICAL: f64 = 20.0;
+
+// how close to 0 the noise values have to be to carve, bigger makes wider tunnels
+const CAVE_THRESHOLD: f64 = 0.09;
+
+// mixed into the world seed so the two cave noises are different from each other and from the terrain
+const CAVE_SEED_SALTS: [u64; 2] = [0x6361_7665_5f61_0001, 0x6361_7665_5f62_0002];
+
+// water settings
+// the world y of the top of the water in oceans and lakes, anything lower than this open to the sky is filled with water
+pub const DEFAULT_SEA_LEVEL: i16 = -6;
+
+// ore settings
+// how one type of ore is spread through the stone
+pub struct OreSettings {
+    pub block_type: BlockType,
+
+    // the lowest and highest y index (in the temp chunk vector, 0 is the bedrock layer) ore can be placed at
+    pub min_height: usize,
+    pub max_height: usize,
+
+    // how many veins start in each ore region, picked randomly between these (inclusive)
+    pub min_veins_per_region: i32,
+    pub max_veins_per_region: i32,
+
+    // how many steps each vein wanders for, each step turns the stone it is on into ore
+    pub vein_size: i32,
+}
+
+// veins are started in square regions of the world this many blocks wide, instead of per chunk
+// so where the veins are doesnt depend on the chunk size and they can carry on over chunk borders
+pub const ORE_REGION_SIZE: i32 = 16;
+
+// rarer ores are lower down, in fewer and smaller veins
+pub const ORE_SETTINGS: [OreSettings; 4] = [
+    OreSettings {
+        block_type: BlockType::CoalOre,
+        min_height: 1,
+        max_height: 128,
+        min_veins_per_region: 3,
+        max_veins_per_region: 5,
+        vein_size: 12,
+    },
+    OreSettings {
+        block_type: BlockType::IronOre,
+        min_height: 1,
+        max_height: 100,
+        min_veins_per_region: 2,
+        max_veins_per_region: 3,
+        vein_size: 8,
+    },
+    OreSettings {
+        block_type: BlockType::GoldOre,
+        min_height: 1,
+        max_height: 60,
+        min_veins_per_region: 0,
+        max_veins_per_region: 1,
+        vein_size: 8,
+    },
+    OreSettings {
+        block_type: BlockType::DiamondOre,
+        min_height: 1,
+        max_height: 30,
+        min_veins_per_region: 0,
+        max_veins_per_region: 1,
+        vein_size: 6,
+    },
+];
+
+// mixed into the world seed so the ore random numbers arnt related to the terrain noise
+const ORE_SEED_SALT: u64 = 0x6f72_655f_7665_696e;
+
 /*
 if i havent created this chunk before then i create it, by creating a new chunk object and filling it with all the data it needs
 
@@ -18,46 +106,300 @@ i can potentially create a 3d vector so initially write everything to so it is e
 then i can just write it to the hashmap at the end, skipping air
 
 remember the origin of the chunk (index 000) is the front bottom right
+
+the height of each column comes from noise in world coordinates, so the edges of chunks line up with their neighbours
+and the same seed will always make exactly the same chunk
 */
-pub fn generate_chunk(temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>, chunk_sizes: (usize, usize, usize)) {
+pub fn generate_chunk(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    world_seed: u64,
+) {
     /*println!(
         "Generating Chunk: ({}, {})",
         self.chunk_id_x, self.chunk_id_z
     );*/
 
-    let half_chunk_y: usize = chunk_sizes.1 / 2;
-
-    // start with the bottom level being bedrock
     for x in 0..chunk_sizes.0 {
         for z in 0..chunk_sizes.2 {
+            let world_x: i32 = (chunk_ids.0 * chunk_sizes.0 as i32) + x as i32;
+            let world_z: i32 = (chunk_ids.1 * chunk_sizes.2 as i32) + z as i32;
+
+            let surface_height: usize = get_surface_height(world_x, world_z, chunk_sizes, world_seed);
+
+            // the biome picks what the top few blocks are made of
+            let (surface_block, filler_block) =
+                get_biome(world_x, world_z, world_seed).get_surface_blocks(surface_height, chunk_sizes);
+
+            // start with the bottom level being bedrock
             temp_chunk_vec[x][0][z].block_type = BlockType::Bedrock;
+
+            // a chunk 1 block high only has room for the bedrock
+            if surface_height == 0 {
+                continue;
+            }
+
+            // in chunks only a few blocks high the surface can be right above the bedrock, so the layers are squashed and never go below y 1
+            let filler_start: usize = surface_height.saturating_sub(2).max(1);
+
+            // stone up until 3 below the surface
+            for y_vec in temp_chunk_vec[x][1..filler_start].iter_mut() {
+                y_vec[z].block_type = BlockType::Stone;
+            }
+
+            // then 2 layers of filler (dirt under grass)
+            for y_vec in temp_chunk_vec[x][filler_start..surface_height].iter_mut() {
+                y_vec[z].block_type = filler_block;
+            }
+
+            // then one layer of the surface block
+            temp_chunk_vec[x][surface_height][z].block_type = surface_block;
         }
     }
 
-    // stone up until the halfway point - 3
-    for x in 0..chunk_sizes.0 {
-        for y in 1..half_chunk_y - 3 {
-            for z in 0..chunk_sizes.2 {
-                temp_chunk_vec[x][y][z].block_type = BlockType::Stone;
+    // ores go in before the caves so caves can cut through veins and show them
+    place_ores(temp_chunk_vec, chunk_ids, chunk_sizes, world_seed);
+
+    carve_caves(temp_chunk_vec, chunk_ids, chunk_sizes, world_seed);
+}
+
+/*
+fill every column with water sources from the sea level down to the ground
+it stops at the first block that isnt air, so caves under the ground dont get flooded
+grass cant grow under water so the block the water is sitting on is turned into dirt
+*/
+pub fn fill_water(temp_chunk_vec: &mut [Vec<Vec<Block>>], chunk_sizes: (usize, usize, usize), sea_level: i16) {
+    let sea_level_index: i32 = (sea_level as i32 + (chunk_sizes.1 as i32 / 2)).min(chunk_sizes.1 as i32 - 1);
+    if sea_level_index < 1 {
+        return;
+    }
+    let sea_level_index: usize = sea_level_index as usize;
+
+    for x_vec in temp_chunk_vec.iter_mut() {
+        // each column along z is filled until it gets to the ground, going down a layer at a time
+        let mut is_filling: Vec<bool> = vec![true; chunk_sizes.2];
+
+        for (y, y_vec) in x_vec.iter_mut().enumerate().take(sea_level_index + 1).skip(1).rev() {
+            for (block, is_filling) in y_vec.iter_mut().zip(is_filling.iter_mut()) {
+                if !*is_filling {
+                    continue;
+                }
+
+                if block.block_type == BlockType::Air {
+                    block.block_type = BlockType::Water;
+                    continue;
+                }
+                *is_filling = false;
+
+                // only if water was actually put on top of it
+                if y < sea_level_index && block.block_type == BlockType::Grass {
+                    block.block_type = BlockType::Dirt;
+                }
             }
         }
     }
+}
 
-    // then 2 layers of dirt
-    for x in 0..chunk_sizes.0 {
-        for y in (half_chunk_y - 3)..(half_chunk_y - 1) {
-            for z in 0..chunk_sizes.2 {
-                temp_chunk_vec[x][y][z].block_type = BlockType::Dirt;
+/*
+put veins of each ore into the stone of this chunk
+each ore in each ore region gets its own random numbers from the seed, so the same seed always has the same veins
+veins wander randomly from their start and can cross into other chunks, so every vein that starts close enough to reach
+this chunk is followed and only the part of it inside this chunk is placed
+veins stay inside the ores height range and only replace stone
+*/
+pub fn place_ores(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    world_seed: u64,
+) {
+    let chunk_start_x: i32 = chunk_ids.0 * chunk_sizes.0 as i32;
+    let chunk_start_z: i32 = chunk_ids.1 * chunk_sizes.2 as i32;
+
+    for (ore_index, ore) in ORE_SETTINGS.iter().enumerate() {
+        // small chunks might not reach all the way up the range
+        let min_height: i32 = ore.min_height as i32;
+        let max_height: i32 = ore.max_height.min(chunk_sizes.1 - 1) as i32;
+        if min_height > max_height {
+            continue;
+        }
+
+        // a vein can only wander vein_size blocks from where it started
+        let first_region_x: i32 = (chunk_start_x - ore.vein_size).div_euclid(ORE_REGION_SIZE);
+        let last_region_x: i32 = (chunk_start_x + chunk_sizes.0 as i32 - 1 + ore.vein_size).div_euclid(ORE_REGION_SIZE);
+        let first_region_z: i32 = (chunk_start_z - ore.vein_size).div_euclid(ORE_REGION_SIZE);
+        let last_region_z: i32 = (chunk_start_z + chunk_sizes.2 as i32 - 1 + ore.vein_size).div_euclid(ORE_REGION_SIZE);
+
+        for region_x in first_region_x..=last_region_x {
+            for region_z in first_region_z..=last_region_z {
+                let mut random: SeededRandom = SeededRandom::new(hash_position(
+                    world_seed ^ ORE_SEED_SALT,
+                    region_x,
+                    ore_index as i32,
+                    region_z,
+                ));
+
+                let veins: i32 = random.next_range(ore.min_veins_per_region, ore.max_veins_per_region + 1);
+                for _ in 0..veins {
+                    // world position of the start of the vein
+                    let mut x: i32 = (region_x * ORE_REGION_SIZE) + random.next_range(0, ORE_REGION_SIZE);
+                    let mut y: i32 = random.next_range(min_height, max_height + 1);
+                    let mut z: i32 = (region_z * ORE_REGION_SIZE) + random.next_range(0, ORE_REGION_SIZE);
+
+                    for _ in 0..ore.vein_size {
+                        let relative_x: i32 = x - chunk_start_x;
+                        let relative_z: i32 = z - chunk_start_z;
+                        if relative_x >= 0
+                            && relative_x < chunk_sizes.0 as i32
+                            && relative_z >= 0
+                            && relative_z < chunk_sizes.2 as i32
+                        {
+                            let block: &mut Block = &mut temp_chunk_vec[relative_x as usize][y as usize][relative_z as usize];
+                            if block.block_type == BlockType::Stone {
+                                block.block_type = ore.block_type;
+                            }
+                        }
+
+                        // move one block in a random direction
+                        match random.next_range(0, 6) {
+                            0 => x += 1,
+                            1 => x -= 1,
+                            2 => y += 1,
+                            3 => y -= 1,
+                            4 => z += 1,
+                            _ => z -= 1,
+                        }
+                        y = y.clamp(min_height, max_height);
+                    }
+                }
             }
         }
     }
+}
 
-    // then one layer of grass
-    for x in 0..chunk_sizes.0 {
-        for y in (half_chunk_y - 1)..half_chunk_y {
-            for z in 0..chunk_sizes.2 {
-                temp_chunk_vec[x][y][z].block_type = BlockType::Grass;
+/*
+turn any blocks inside of a cave into air
+the bottom layer is never touched so the bedrock floor always stays whole
+this only uses world coordinates so caves carry on across chunk borders
+*/
+pub fn carve_caves(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    world_seed: u64,
+) {
+    let half_chunk_y: i32 = chunk_sizes.1 as i32 / 2;
+
+    for (x, x_vec) in temp_chunk_vec.iter_mut().enumerate() {
+        let world_x: i32 = (chunk_ids.0 * chunk_sizes.0 as i32) + x as i32;
+
+        for (y, y_vec) in x_vec.iter_mut().enumerate().skip(1) {
+            for (z, block) in y_vec.iter_mut().enumerate() {
+                // only solid ground gets carved, and air doesnt need the noise worked out
+                if block.block_type == BlockType::Air || block.block_type == BlockType::Bedrock {
+                    continue;
+                }
+
+                let world_z: i32 = (chunk_ids.1 * chunk_sizes.2 as i32) + z as i32;
+                if is_cave(world_x, y as i32 - half_chunk_y, world_z, world_seed) {
+                    block.block_type = BlockType::Air;
+                }
+            }
+        }
+    }
+}
+
+// is this world position inside of a cave tunnel
+pub fn is_cave(world_x: i32, world_y: i32, world_z: i32, world_seed: u64) -> bool {
+    let sample_x: f64 = world_x as f64 / CAVE_SCALE_HORIZONTAL;
+    let sample_y: f64 = world_y as f64 / CAVE_SCALE_VERTICAL;
+    let sample_z: f64 = world_z as f64 / CAVE_SCALE_HORIZONTAL;
+
+    let first: f64 = gradient_noise_3d(world_seed ^ CAVE_SEED_SALTS[0], sample_x, sample_y, sample_z);
+    if first.abs() > CAVE_THRESHOLD {
+        return false;
+    }
+
+    let second: f64 = gradient_noise_3d(world_seed ^ CAVE_SEED_SALTS[1], sample_x, sample_y, sample_z);
+
+    (first * first) + (second * second) < CAVE_THRESHOLD * CAVE_THRESHOLD
+}
+
+// the original flat world, the same slab of bedrock, stone, dirt and grass in every chunk with the grass just below the middle
+pub fn generate_flat_chunk(temp_chunk_vec: &mut [Vec<Vec<Block>>], chunk_sizes: (usize, usize, usize)) {
+    let half_chunk_y: usize = chunk_sizes.1 / 2;
+
+    for x_vec in temp_chunk_vec.iter_mut() {
+        for (y, y_vec) in x_vec.iter_mut().enumerate() {
+            // start with the bottom level being bedrock, then stone up until the halfway point - 3, then 2 layers of dirt and one layer of grass
+            let block_type: BlockType = if y == 0 {
+                BlockType::Bedrock
+            } else if y < half_chunk_y.saturating_sub(3) {
+                BlockType::Stone
+            } else if y < half_chunk_y.saturating_sub(1) {
+                BlockType::Dirt
+            } else if y < half_chunk_y {
+                BlockType::Grass
+            } else {
+                break;
+            };
+
+            for block in y_vec.iter_mut() {
+                block.block_type = block_type;
+            }
+        }
+    }
+}
+
+// a flat world made of the given layers, listed from the bottom up as (block type, how many blocks thick)
+// any layers that dont fit in the chunk are cut off
+pub fn generate_superflat_chunk(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    layers: &[(BlockType, usize)],
+    chunk_sizes: (usize, usize, usize),
+) {
+    let mut y: usize = 0;
+    for (block_type, thickness) in layers.iter() {
+        for _ in 0..*thickness {
+            if y >= chunk_sizes.1 {
+                return;
+            }
+
+            for x_vec in temp_chunk_vec.iter_mut() {
+                for block in x_vec[y].iter_mut() {
+                    block.block_type = *block_type;
+                }
             }
+            y += 1;
         }
     }
 }
+
+/*
+get the y index (in the temp chunk vector, not the world y) of the top block of this column
+it is the middle of the chunk moved up or down by the noise, and kept far enough from the bottom and top that
+there is always bedrock, stone and dirt under it
+how far it is moved and how hilly it is comes from the biomes around the column, blended so the borders are smooth
+*/
+pub fn get_surface_height(world_x: i32, world_z: i32, chunk_sizes: (usize, usize, usize), world_seed: u64) -> usize {
+    let noise: f64 = fractal_noise_2d(
+        world_seed,
+        world_x as f64 / TERRAIN_SCALE,
+        world_z as f64 / TERRAIN_SCALE,
+        TERRAIN_OCTAVES,
+        TERRAIN_PERSISTENCE,
+        TERRAIN_LACUNARITY,
+    );
+
+    let (height_offset, height_amplitude) = get_blended_height_settings(world_x, world_z, world_seed);
+
+    let half_chunk_y: i32 = chunk_sizes.1 as i32 / 2;
+    let terrain_amplitude: f64 = (chunk_sizes.1 / TERRAIN_HEIGHT_DIVISOR) as f64;
+
+    // the flat world had its grass one below the middle so the terrain is centered there
+    let height: i32 = (half_chunk_y - 1) + ((height_offset + (noise * height_amplitude)) * terrain_amplitude).round() as i32;
+
+    // not clamp, chunks shorter than 4 blocks would have the min above the max
+    height.max(3).min(chunk_sizes.1 as i32 - 1).max(0) as usize
+}
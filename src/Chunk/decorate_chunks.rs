@@ -0,0 +1,248 @@
+/*
+The decoration stage of world generation, this runs after the terrain, ores and caves have been made
+it adds features that sit on top of the terrain, like trees
+
+a feature that starts near the edge of a chunk can need to put blocks into the chunk next to it
+those blocks are saved as pending block writes for that chunk, and are put in once it is generated or loaded
+ */
+use crate::{biome::*, block::*, block_type::*, noise::*, types::Position};
+
+use std::collections::HashMap;
+
+// decoration settings
+// how many times a tree tries to be placed in each chunk, the biome decides how likely each try is to work
+const TREE_ATTEMPTS_PER_CHUNK: i32 = 8;
+
+// how tall the trunk of a tree can be (inclusive)
+const TREE_MIN_TRUNK_HEIGHT: i32 = 4;
+const TREE_MAX_TRUNK_HEIGHT: i32 = 6;
+
+// mixed into the world seed so the tree random numbers arnt related to anything else
+const TREE_SEED_SALT: u64 = 0x7472_6565_5f70_6c61;
+
+// a block to write into the world, (world position, block type)
+pub type BlockWrite = ((i32, i16, i32), BlockType);
+
+/*
+blocks that need to be put into a chunk that hasnt been generated (or loaded) yet, keyed by the id of that chunk
+the positions are world positions
+*/
+pub struct PendingBlockWrites {
+    pub writes: HashMap<(i32, i32), Vec<BlockWrite>>,
+}
+
+impl PendingBlockWrites {
+    pub fn new() -> PendingBlockWrites {
+        PendingBlockWrites {
+            writes: HashMap::new(),
+        }
+    }
+
+    // save a block to be written into whichever chunk this position is in
+    pub fn add_block_write(&mut self, block_pos: (i32, i16, i32), block_type: BlockType, chunk_sizes: (usize, usize, usize)) {
+        let chunk_id: (i32, i32) = (
+            block_pos.0.div_euclid(chunk_sizes.0 as i32),
+            block_pos.2.div_euclid(chunk_sizes.2 as i32),
+        );
+
+        self.writes.entry(chunk_id).or_default().push((block_pos, block_type));
+    }
+
+    // remove and return all of the writes waiting for this chunk
+    pub fn take_chunk_writes(&mut self, chunk_id: (i32, i32)) -> Vec<BlockWrite> {
+        self.writes.remove(&chunk_id).unwrap_or_default()
+    }
+
+    // add all of the writes from another set of pending writes (like the ones from a chunk decorated on a worker thread)
+    pub fn merge(&mut self, other: PendingBlockWrites) {
+        for (chunk_id, block_writes) in other.writes {
+            self.writes.entry(chunk_id).or_default().extend(block_writes);
+        }
+    }
+
+    pub fn has_chunk_writes(&self, chunk_id: (i32, i32)) -> bool {
+        self.writes.contains_key(&chunk_id)
+    }
+
+    // the ids of every chunk that has writes waiting for it
+    pub fn get_chunk_ids(&self) -> Vec<(i32, i32)> {
+        self.writes.keys().cloned().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+}
+
+impl Default for PendingBlockWrites {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/*
+if a decoration block can go where this block currently is
+decorations only go into air, except logs which can also replace leaves
+this means it doesnt matter which order overlapping trees are placed in, the result is always the same
+*/
+pub fn can_decoration_replace(current_block_type: BlockType, new_block_type: BlockType) -> bool {
+    current_block_type == BlockType::Air
+        || (current_block_type == BlockType::Leaves && new_block_type == BlockType::Log)
+}
+
+// put the pending writes for this chunk into its temp vector, any outside of the chunks height are ignored
+pub fn apply_block_writes(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    block_writes: &[BlockWrite],
+    chunk_sizes: (usize, usize, usize),
+) {
+    let half_chunk_y: i32 = chunk_sizes.1 as i32 / 2;
+
+    for (block_pos, block_type) in block_writes.iter() {
+        let y: i32 = block_pos.1 as i32 + half_chunk_y;
+        if y < 0 || y >= chunk_sizes.1 as i32 {
+            continue;
+        }
+
+        let block: &mut Block = &mut temp_chunk_vec[block_pos.0.rem_euclid(chunk_sizes.0 as i32) as usize][y as usize]
+            [block_pos.2.rem_euclid(chunk_sizes.2 as i32) as usize];
+
+        if can_decoration_replace(block.block_type, *block_type) {
+            block.block_type = *block_type;
+        }
+    }
+}
+
+/*
+add trees to a chunk that has just been generated
+each chunk gets its own random numbers from the seed and its id, so the same seed always has the same trees
+trees are only put on grass and only in biomes that have trees
+any part of a tree that goes outside of this chunk is added to the pending block writes
+*/
+pub fn decorate_chunk(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    world_seed: u64,
+    pending_block_writes: &mut PendingBlockWrites,
+) {
+    let mut random: SeededRandom = SeededRandom::new(hash_position(world_seed ^ TREE_SEED_SALT, chunk_ids.0, 0, chunk_ids.1));
+
+    for _ in 0..TREE_ATTEMPTS_PER_CHUNK {
+        // pick all the random numbers first so every try uses the same amount, whether it works or not
+        let x: usize = random.next_range(0, chunk_sizes.0 as i32) as usize;
+        let z: usize = random.next_range(0, chunk_sizes.2 as i32) as usize;
+        let chance: i32 = random.next_range(0, 100);
+        let trunk_height: i32 = random.next_range(TREE_MIN_TRUNK_HEIGHT, TREE_MAX_TRUNK_HEIGHT + 1);
+        let leaves_seed: u64 = random.next_u64();
+
+        let world_x: i32 = temp_chunk_vec[x][0][z].position.x;
+        let world_z: i32 = temp_chunk_vec[x][0][z].position.z;
+        if chance >= get_biome(world_x, world_z, world_seed).get_tree_chance() {
+            continue;
+        }
+
+        // find the top block of the column, it has to be grass for a tree to grow
+        let mut surface_y: usize = chunk_sizes.1 - 1;
+        while surface_y > 0 && temp_chunk_vec[x][surface_y][z].block_type == BlockType::Air {
+            surface_y -= 1;
+        }
+        if temp_chunk_vec[x][surface_y][z].block_type != BlockType::Grass {
+            continue;
+        }
+
+        // the whole tree has to fit under the top of the chunk
+        if surface_y as i32 + trunk_height + 2 >= chunk_sizes.1 as i32 {
+            continue;
+        }
+
+        // grass cant be under a tree
+        temp_chunk_vec[x][surface_y][z].block_type = BlockType::Dirt;
+
+        let base_position: Position = temp_chunk_vec[x][surface_y + 1][z].position;
+        place_tree(
+            temp_chunk_vec,
+            chunk_ids,
+            chunk_sizes,
+            (base_position.x, base_position.y, base_position.z),
+            trunk_height,
+            leaves_seed,
+            pending_block_writes,
+        );
+    }
+}
+
+/*
+put a tree with its trunk starting at this world position
+the leaves are 2 wide layers around the top of the trunk with 2 smaller layers above them
+the corners of the wide layers are randomly left off so the trees dont all look the same
+*/
+pub fn place_tree(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    base_pos: (i32, i16, i32),
+    trunk_height: i32,
+    leaves_seed: u64,
+    pending_block_writes: &mut PendingBlockWrites,
+) {
+    let mut random: SeededRandom = SeededRandom::new(leaves_seed);
+    let top_y: i32 = base_pos.1 as i32 + trunk_height - 1;
+
+    for y in (top_y - 1)..=(top_y + 2) {
+        // the bottom 2 layers are wider
+        let radius: i32 = if y <= top_y { 2 } else { 1 };
+
+        for offset_x in -radius..=radius {
+            for offset_z in -radius..=radius {
+                let is_corner: bool = offset_x.abs() == radius && offset_z.abs() == radius;
+
+                // the top layer is a plus shape, the corners of the others are random
+                if is_corner && (y == top_y + 2 || random.next_range(0, 2) == 0) {
+                    continue;
+                }
+
+                set_decoration_block(
+                    temp_chunk_vec,
+                    chunk_ids,
+                    chunk_sizes,
+                    (base_pos.0 + offset_x, y as i16, base_pos.2 + offset_z),
+                    BlockType::Leaves,
+                    pending_block_writes,
+                );
+            }
+        }
+    }
+
+    for y in 0..trunk_height {
+        set_decoration_block(
+            temp_chunk_vec,
+            chunk_ids,
+            chunk_sizes,
+            (base_pos.0, base_pos.1 + y as i16, base_pos.2),
+            BlockType::Log,
+            pending_block_writes,
+        );
+    }
+}
+
+// put a decoration block into this chunk if it is inside of it, otherwise save it for the chunk it is in
+pub fn set_decoration_block(
+    temp_chunk_vec: &mut [Vec<Vec<Block>>],
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    block_pos: (i32, i16, i32),
+    block_type: BlockType,
+    pending_block_writes: &mut PendingBlockWrites,
+) {
+    let block_chunk_ids: (i32, i32) = (
+        block_pos.0.div_euclid(chunk_sizes.0 as i32),
+        block_pos.2.div_euclid(chunk_sizes.2 as i32),
+    );
+
+    if block_chunk_ids == chunk_ids {
+        apply_block_writes(temp_chunk_vec, &[(block_pos, block_type)], chunk_sizes);
+    } else {
+        pending_block_writes.add_block_write(block_pos, block_type, chunk_sizes);
+    }
+}
@@ -1,8 +1,11 @@
+pub mod chunk_borders;
+pub mod chunk_cpu_functions;
 pub mod chunk_functions;
 pub mod chunk_gpu_functions;
 pub mod create_chunks;
+pub mod decorate_chunks;
 
-use crate::{block::*, renderer::*, types::*};
+use crate::{biome::*, block::*, render_backend::*, types::*};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use wgpu::{BufferDescriptor, BufferUsages};
@@ -16,6 +19,10 @@ pub struct Chunk {
     // just the blocks that are touching air that will be sent to the gpu
     pub instances_to_render: HashMap<(i32, i16, i32), InstanceData>,
 
+    // the biome of each column in the chunk, indexed by the position relative to the chunk [x][z]
+    // empty if the world generator doesnt use biomes
+    pub biomes: Vec<Vec<Biome>>,
+
     // other objects like mobs that dont fit into a single block
     //pub chunkObjects: Vec
 
@@ -35,17 +42,17 @@ pub struct Chunk {
     // the actual size of the instance buffer being used, (size of hashmap once updated)
     pub instance_size: u32,
 
-    // instance buffer for the chunk
-    pub instance_buffer: wgpu::Buffer,
-    pub instance_staging_buffer: wgpu::Buffer,
-
-    pub new_instance_buffer: wgpu::Buffer,
-    pub new_instance_staging_buffer: wgpu::Buffer,
+    // instance buffers for the chunk, none if there is no gpu (like on a server)
+    pub instance_buffers: Option<InstanceBuffers>,
 
 
     // if i update the staging buffer set this true so i know to copy it to the instance buffer
     pub instances_modified: bool,
 
+    // if the instances to render hashmap has been changed on the cpu (eg. a block was set)
+    // so i know to write it to the staging buffer on the next update
+    pub instances_to_render_changed: bool,
+
     // so i know if the staging buffer is currently being written to
     // i only update the actual buffer is staging buffer write is false and modified is true
     pub staging_buffer_writing: Arc<Mutex<bool>>,
@@ -55,10 +62,18 @@ pub struct Chunk {
 
     // used on the cpu side to overwrite the old buffers once the new ones have been update with data
     pub creating_new_instance_buffers: bool,
+
+    // the greedy mesh of the chunk, only used when the renderer is using greedy meshing (none if there is nothing to draw)
+    pub mesh_vertex_buffer: Option<wgpu::Buffer>,
+    pub mesh_index_buffer: Option<wgpu::Buffer>,
+    pub mesh_index_count: u32,
+
+    // if a block has changed since the mesh was built, so it is rebuilt on the next update
+    pub mesh_outdated: bool,
 }
 
 impl Chunk {
-    pub fn new(idx: i32, idz: i32, num_blocks: i32, renderer: &Renderer) -> Chunk {
+    pub fn new(idx: i32, idz: i32, num_blocks: i32, render_backend: &dyn RenderBackend) -> Chunk {
         // if a numBlocks was passed in ill allocate the hashmap of that size
         let chunk_blocks: HashMap<(i32, i16, i32), Block>;
         let instances_to_render: HashMap<(i32, i16, i32), InstanceData> = HashMap::new();
@@ -73,9 +88,61 @@ impl Chunk {
             alive_blocks = 0;
         }
 
-        // make the instance buffer for the chunk init it with size of 100
+        // make the instance buffers for the chunk init it with size of 100, if there is a gpu to make them on
         let instance_capacity: u32 = 100;
-        let instance_buffer: wgpu::Buffer = renderer.device.create_buffer(&BufferDescriptor {
+        let instance_buffers: Option<InstanceBuffers> = render_backend
+            .get_gpu()
+            .map(|(device, _)| InstanceBuffers::new(device, instance_capacity));
+
+        Self {
+            chunk_blocks,
+            instances_to_render,
+            biomes: Vec::new(),
+
+            chunk_id_x: idx,
+            chunk_id_z: idz,
+            alive_blocks,
+
+            instance_size: 0,
+            instance_capacity,
+            new_instance_capacity: instance_capacity,
+
+            instance_buffers,
+
+            instances_modified: false,
+            instances_to_render_changed: false,
+            staging_buffer_writing: Arc::new(Mutex::new(false)),
+            new_instance_buffers_writing: Arc::new(Mutex::new(false)),
+            creating_new_instance_buffers: false,
+
+            mesh_vertex_buffer: None,
+            mesh_index_buffer: None,
+            mesh_index_count: 0,
+            mesh_outdated: true,
+        }
+    }
+
+    pub fn get_instance_capacity(&self) -> u32 {
+        self.instance_capacity
+    }
+
+    pub fn get_instance_size(&self) -> u32 {
+        self.instance_size
+    }
+}
+
+// the gpu buffers of a chunks instances
+pub struct InstanceBuffers {
+    pub instance_buffer: wgpu::Buffer,
+    pub instance_staging_buffer: wgpu::Buffer,
+
+    pub new_instance_buffer: wgpu::Buffer,
+    pub new_instance_staging_buffer: wgpu::Buffer,
+}
+
+impl InstanceBuffers {
+    pub fn new(device: &wgpu::Device, instance_capacity: u32) -> InstanceBuffers {
+        let instance_buffer: wgpu::Buffer = device.create_buffer(&BufferDescriptor {
             label: Some("Instance Buffer"),
             size: (std::mem::size_of::<InstanceData>() * instance_capacity as usize)
                 as wgpu::BufferAddress,
@@ -83,7 +150,7 @@ impl Chunk {
             mapped_at_creation: false,
         });
 
-        let instance_staging_buffer: wgpu::Buffer = renderer.device.create_buffer(&BufferDescriptor {
+        let instance_staging_buffer: wgpu::Buffer = device.create_buffer(&BufferDescriptor {
                 label: Some("Instance Staging Buffer"),
                 size: (std::mem::size_of::<InstanceData>() * instance_capacity as usize)
                     as wgpu::BufferAddress,
@@ -91,7 +158,7 @@ impl Chunk {
                 mapped_at_creation: false,
             });
 
-        let new_instance_buffer: wgpu::Buffer = renderer.device.create_buffer(&BufferDescriptor {
+        let new_instance_buffer: wgpu::Buffer = device.create_buffer(&BufferDescriptor {
             label: Some("Instance Buffer"),
             size: (std::mem::size_of::<InstanceData>() * instance_capacity as usize)
                 as wgpu::BufferAddress,
@@ -99,7 +166,7 @@ impl Chunk {
             mapped_at_creation: false,
         });
 
-        let new_instance_staging_buffer: wgpu::Buffer = renderer.device.create_buffer(&BufferDescriptor {
+        let new_instance_staging_buffer: wgpu::Buffer = device.create_buffer(&BufferDescriptor {
                 label: Some("Instance Staging Buffer"),
                 size: (std::mem::size_of::<InstanceData>() * instance_capacity as usize)
                     as wgpu::BufferAddress,
@@ -107,35 +174,11 @@ impl Chunk {
                 mapped_at_creation: false,
             });
 
-        Self {
-            chunk_blocks,
-            instances_to_render,
-
-            chunk_id_x: idx,
-            chunk_id_z: idz,
-            alive_blocks,
-
-            instance_size: 0,
-            instance_capacity,
-            new_instance_capacity: instance_capacity,
-
+        InstanceBuffers {
             instance_buffer,
             instance_staging_buffer,
             new_instance_buffer,
             new_instance_staging_buffer,
-
-            instances_modified: false,
-            staging_buffer_writing: Arc::new(Mutex::new(false)),
-            new_instance_buffers_writing: Arc::new(Mutex::new(false)),
-            creating_new_instance_buffers: false,
         }
     }
-
-    pub fn get_instance_capacity(&self) -> u32 {
-        self.instance_capacity
-    }
-
-    pub fn get_instance_size(&self) -> u32 {
-        self.instance_size
-    }
 }
@@ -6,16 +6,26 @@ var<storage, read> block_type_buffer: array<u32>;
 @binding(1)
 var<storage, read> block_transparency_buffer: array<u32>;
 
+// 1 if the block is a fluid (BlockType::is_fluid), so the ids of fluids arnt written out in here as well
 @group(0)
 @binding(2)
-var<storage, read> dimentions_buffer: array<u32>;
+var<storage, read> block_fluid_buffer: array<u32>;
 
 @group(0)
 @binding(3)
+var<storage, read> dimentions_buffer: array<u32>;
+
+@group(0)
+@binding(4)
 var<storage, read_write> result_buffer: array<u32>;
 
 
-// function to take the xyz values and return the index
+// a face is only showing if the block next to it is transparent, and the 2 blocks arnt both fluids (so the inside of lakes isnt drawn)
+fn is_face_visible(id: u32, newid: u32) -> bool {
+    return block_transparency_buffer[newid] > 0 && !(block_fluid_buffer[id] > 0 && block_fluid_buffer[newid] > 0);
+}
+
+// function to take the xyz values (including the border) and return the index
 fn get_index(x: u32, y: u32, z: u32) -> u32 {
     return (x + (y * dimentions_buffer[0]) + (z * dimentions_buffer[0] * dimentions_buffer[1]));
 }
@@ -26,17 +36,17 @@ fn get_index(x: u32, y: u32, z: u32) -> u32 {
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
 
-    let id: u32 = global_id.x + global_id.y * dimentions_buffer[0] + global_id.z * dimentions_buffer[0] * dimentions_buffer[1];
-    var newid: u32 = 0;
-
+    // the buffers have a 1 block border around the chunk (filled from the chunks next to it or void)
+    // so the xyz coords are 1 more than the global id, and the border is never checked itself
+    let x: u32 = global_id.x + 1;
+    let y: u32 = global_id.y + 1;
+    let z: u32 = global_id.z + 1;
 
-    // get its xyz coords from its id
-    let z: u32 = id / (dimentions_buffer[0] * dimentions_buffer[1]);
-    let y: u32 = (id % (dimentions_buffer[0] * dimentions_buffer[1])) / dimentions_buffer[0];
-    let x: u32 = id % dimentions_buffer[0];
+    let id: u32 = get_index(x, y, z);
+    var newid: u32 = 0;
 
-    // touching air count variable (increases for every block touching air, not necessary but saves the bool if i can just keep adding and if > 1 true)
-    // will do all faces now do i can later check only which faces not that any face it touching air
+    // which faces are touching air, one bit for each face in the same order as BlockFace::ALL (block.rs)
+    // top = 1, bottom = 2, right = 4, left = 8, back = 16, front = 32
     var touching_air: u32 = 0;
 
     // check the type of this block, if it is air ignore it and return
@@ -45,61 +55,49 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         return;
     }
 
-    // go through each of the 6 sides of the block if it is touching air increment its value by one
-    // also check that the block ill check is in bounds, eg not x=-1
+    // go through each of the 6 sides of the block if it is touching air set the bit for that face
+    // no bounds checks are needed since every block in the chunk has the border around it
 
     // top
-    if y + 1 < dimentions_buffer[1] { // check bounds
-        newid = get_index(x, y+1, z);
-        if block_transparency_buffer[newid] > 0 { // true (so it is transparent)
-            touching_air = touching_air + 1;
-        }
+    newid = get_index(x, y+1, z);
+    if is_face_visible(id, newid) { // true (so it is transparent)
+        touching_air = touching_air | (1u << 0u);
     }
-    
+
     // bottom
-    if y != 0 { // check bounds (since u32 0-1 goes to max so i make sure y isnt 0 , not y-1 >= 0, because this will overflow)
-        newid = get_index(x, y-1, z);
-        if block_transparency_buffer[newid] > 0 { // true (so it is transparent)
-            touching_air = touching_air + 1;
-        }
+    newid = get_index(x, y-1, z);
+    if is_face_visible(id, newid) { // true (so it is transparent)
+        touching_air = touching_air | (1u << 1u);
     }
-    
 
-    
+
+
     // right
-    if x + 1 < dimentions_buffer[0] { // check bounds
-        newid = get_index(x+1, y, z);
-        if block_transparency_buffer[newid] > 0 { // true (so it is transparent)
-            touching_air = touching_air + 1;
-        }
+    newid = get_index(x+1, y, z);
+    if is_face_visible(id, newid) { // true (so it is transparent)
+        touching_air = touching_air | (1u << 2u);
     }
-    
+
     // left
-    if x != 0 { // check bounds
-        newid = get_index(x-1, y, z);
-        if block_transparency_buffer[newid] > 0 { // true (so it is transparent)
-            touching_air = touching_air + 1;
-        }
+    newid = get_index(x-1, y, z);
+    if is_face_visible(id, newid) { // true (so it is transparent)
+        touching_air = touching_air | (1u << 3u);
     }
-    
 
-    
+
+
     // back
-    if z + 1 < dimentions_buffer[2] { // check bounds
-        newid = get_index(x, y, z+1);
-        if block_transparency_buffer[newid] > 0 { // true (so it is transparent)
-            touching_air = touching_air + 1;
-        }
+    newid = get_index(x, y, z+1);
+    if is_face_visible(id, newid) { // true (so it is transparent)
+        touching_air = touching_air | (1u << 4u);
     }
-    
+
     // front
-    if z != 0 { // check bounds
-        newid = get_index(x, y, z-1);
-        if block_transparency_buffer[newid] > 0 { // true (so it is transparent)
-            touching_air = touching_air + 1;
-        }
+    newid = get_index(x, y, z-1);
+    if is_face_visible(id, newid) { // true (so it is transparent)
+        touching_air = touching_air | (1u << 5u);
     }
-    
+
 
 
     // finally save the variable to the results buffer
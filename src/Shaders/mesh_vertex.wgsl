@@ -0,0 +1,23 @@
+struct VertexOutput {
+    @builtin(position) pos: vec4<f32>,
+    @location(1) fragColor: vec4<f32>,
+};
+
+struct VertexUniforms {
+    projection_view_matrix: mat4x4<f32>,
+};
+
+
+// group binding   // buffer type  // var name    // var type
+@group(0) @binding(0) var<uniform> uniformBuffer: VertexUniforms;
+
+// the vertex shader for greedy meshed chunks, the vertices are already in world space so there is no model matrix
+@vertex
+fn main(@location(0) position: vec3<f32>, @location(1) colour: vec4<f32>) -> VertexOutput {
+    var output: VertexOutput;
+
+    output.pos = uniformBuffer.projection_view_matrix * vec4<f32>(position, 1.0);
+
+    output.fragColor = colour;
+    return output;
+}
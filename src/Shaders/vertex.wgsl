@@ -17,6 +17,9 @@ struct Instance {
     @location(5) model3: vec4<f32>,
 
     @location(6) colour: vec4<f32>,
+
+    // one bit for each face of the block that is showing
+    @location(7) visible_faces: u32,
 };
 
 
@@ -24,9 +27,17 @@ struct Instance {
 @group(0) @binding(0) var<uniform> uniformBuffer: VertexUniforms;
 
 @vertex
-fn main(@location(0) position: vec3<f32>, instance: Instance) -> VertexOutput {
+fn main(@location(0) position: vec3<f32>, @location(1) face: f32, instance: Instance) -> VertexOutput {
     var output: VertexOutput;
 
+    // if this vertex is part of a face that isnt showing put it outside of the screen
+    // all of the vertices of the face get moved to the same point so its triangles have no area and are never drawn
+    if (instance.visible_faces & (1u << u32(face))) == 0u {
+        output.pos = vec4<f32>(2.0, 2.0, 2.0, 1.0);
+        output.fragColor = instance.colour;
+        return output;
+    }
+
     // Reconstruct the model matrix from the instance data
     let model: mat4x4<f32> = mat4x4<f32>(
         instance.model0,
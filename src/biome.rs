@@ -0,0 +1,171 @@
+/*
+Biomes are big areas of the world that change how the terrain looks
+each column of blocks picks a biome from two noise maps made from the world seed, temperature and humidity
+the biome decides the blocks on the surface, the colour of the grass and how high and hilly the terrain is
+the height and hilliness are blended with the biomes around each column so there arnt cliffs at the borders
+*/
+
+use crate::{block_type::*, noise::*};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Biome {
+    Plains,
+    Desert,
+    Mountains,
+    Ocean,
+}
+
+// biome settings
+// how wide the temperature and humidity areas are in blocks, so biomes are a lot bigger than the hills
+const BIOME_SCALE: f64 = 384.0;
+const BIOME_OCTAVES: u32 = 3;
+const BIOME_PERSISTENCE: f64 = 0.5;
+const BIOME_LACUNARITY: f64 = 2.0;
+
+// mixed into the world seed so temperature, humidity and the terrain all have different noise
+const TEMPERATURE_SEED_SALT: u64 = 0x7465_6d70_6572_6174;
+const HUMIDITY_SEED_SALT: u64 = 0x6875_6d69_6469_7479;
+
+// the noise values where the biomes change
+const OCEAN_HUMIDITY: f64 = 0.2;
+const DESERT_TEMPERATURE: f64 = 0.1;
+const DESERT_HUMIDITY: f64 = 0.0;
+const MOUNTAINS_TEMPERATURE: f64 = -0.15;
+
+// blending settings
+// the heights of the biomes this many blocks around a column are averaged, in a grid with this spacing
+pub const BIOME_BLEND_RADIUS: i32 = 16;
+const BIOME_BLEND_SPACING: i32 = 8;
+
+impl Biome {
+    // the blocks that make up the top of a column, (the top block, the few blocks under it)
+    // mountains only have grass on their lower slopes, above that it is bare stone
+    pub fn get_surface_blocks(&self, surface_height: usize, chunk_sizes: (usize, usize, usize)) -> (BlockType, BlockType) {
+        match self {
+            Biome::Plains => (BlockType::Grass, BlockType::Dirt),
+            Biome::Desert => (BlockType::Sand, BlockType::Sand),
+            Biome::Mountains => {
+                let tree_line: usize = (chunk_sizes.1 / 2) + (chunk_sizes.1 / 16);
+                if surface_height < tree_line {
+                    (BlockType::Grass, BlockType::Dirt)
+                } else {
+                    (BlockType::Stone, BlockType::Stone)
+                }
+            }
+            Biome::Ocean => (BlockType::Sand, BlockType::Sand),
+        }
+    }
+
+    // (how far the middle of the terrain is moved up or down, how far the hills go up and down)
+    // both are in multiples of the terrain amplitude, the chunk height / terrain height divisor
+    pub fn get_height_settings(&self) -> (f64, f64) {
+        match self {
+            Biome::Plains => (0.0, 0.5),
+            Biome::Desert => (0.25, 0.35),
+            Biome::Mountains => (1.0, 2.5),
+            Biome::Ocean => (-1.5, 0.4),
+        }
+    }
+
+    // the colour grass is tinted in this biome
+    pub fn grass_colour(&self) -> [f32; 4] {
+        match self {
+            Biome::Plains => [75.0, 150.0, 50.0, 255.0].map(|x: f32| x / 255.0),
+            Biome::Desert => [150.0, 160.0, 60.0, 255.0].map(|x: f32| x / 255.0),
+            Biome::Mountains => [60.0, 115.0, 70.0, 255.0].map(|x: f32| x / 255.0),
+            Biome::Ocean => [55.0, 145.0, 75.0, 255.0].map(|x: f32| x / 255.0),
+        }
+    }
+
+    // the colour leaves are tinted in this biome, a bit darker than the grass
+    pub fn leaves_colour(&self) -> [f32; 4] {
+        let grass_colour: [f32; 4] = self.grass_colour();
+        [grass_colour[0] * 0.7, grass_colour[1] * 0.8, grass_colour[2] * 0.7, grass_colour[3]]
+    }
+
+    // the colour a block is drawn with in this biome, only some blocks change colour between biomes
+    pub fn get_block_colour(&self, block_type: BlockType) -> [f32; 4] {
+        match block_type {
+            BlockType::Grass => self.grass_colour(),
+            BlockType::Leaves => self.leaves_colour(),
+            _ => block_type.block_colour(),
+        }
+    }
+
+    // the percentage chance that each try at placing a tree in this biome works
+    pub fn get_tree_chance(&self) -> i32 {
+        match self {
+            Biome::Plains => 20,
+            Biome::Desert => 0,
+            Biome::Mountains => 45,
+            Biome::Ocean => 0,
+        }
+    }
+}
+
+// the temperature and humidity noise at this column, both roughly between -1 and 1
+pub fn get_climate(world_x: i32, world_z: i32, world_seed: u64) -> (f64, f64) {
+    let temperature: f64 = fractal_noise_2d(
+        world_seed ^ TEMPERATURE_SEED_SALT,
+        world_x as f64 / BIOME_SCALE,
+        world_z as f64 / BIOME_SCALE,
+        BIOME_OCTAVES,
+        BIOME_PERSISTENCE,
+        BIOME_LACUNARITY,
+    );
+
+    let humidity: f64 = fractal_noise_2d(
+        world_seed ^ HUMIDITY_SEED_SALT,
+        world_x as f64 / BIOME_SCALE,
+        world_z as f64 / BIOME_SCALE,
+        BIOME_OCTAVES,
+        BIOME_PERSISTENCE,
+        BIOME_LACUNARITY,
+    );
+
+    (temperature, humidity)
+}
+
+// which biome this column is in
+pub fn get_biome(world_x: i32, world_z: i32, world_seed: u64) -> Biome {
+    let (temperature, humidity) = get_climate(world_x, world_z, world_seed);
+
+    if humidity > OCEAN_HUMIDITY {
+        Biome::Ocean
+    } else if temperature > DESERT_TEMPERATURE && humidity < DESERT_HUMIDITY {
+        Biome::Desert
+    } else if temperature < MOUNTAINS_TEMPERATURE {
+        Biome::Mountains
+    } else {
+        Biome::Plains
+    }
+}
+
+/*
+the height settings for this column averaged with all the biomes around it
+this is what makes the terrain slope smoothly from one biome into the next instead of jumping at the border
+it only uses world coordinates so it is the same no matter which chunk asks
+*/
+pub fn get_blended_height_settings(world_x: i32, world_z: i32, world_seed: u64) -> (f64, f64) {
+    let mut total_offset: f64 = 0.0;
+    let mut total_amplitude: f64 = 0.0;
+    let mut samples: u32 = 0;
+
+    let mut offset_x: i32 = -BIOME_BLEND_RADIUS;
+    while offset_x <= BIOME_BLEND_RADIUS {
+        let mut offset_z: i32 = -BIOME_BLEND_RADIUS;
+        while offset_z <= BIOME_BLEND_RADIUS {
+            let (height_offset, height_amplitude) =
+                get_biome(world_x + offset_x, world_z + offset_z, world_seed).get_height_settings();
+
+            total_offset += height_offset;
+            total_amplitude += height_amplitude;
+            samples += 1;
+
+            offset_z += BIOME_BLEND_SPACING;
+        }
+        offset_x += BIOME_BLEND_SPACING;
+    }
+
+    (total_offset / samples as f64, total_amplitude / samples as f64)
+}
@@ -12,8 +12,12 @@ pub struct Block {
     // this stores the transform to the camera for this block from world space to camera
     pub model_matrix: [[f32; 4]; 4],
 
-    // so i know to send it to the gpu or not (later only send faces touching air)
+    // so i know to send it to the gpu or not
     pub is_touching_air: bool,
+
+    // which of the 6 faces are touching air, one bit for each face in the order of BlockFace::ALL
+    // only these faces are drawn, is touching air is true if any of them are set
+    pub visible_faces: u8,
 }
 
 impl Block {
@@ -42,6 +46,62 @@ impl Block {
             },
             model_matrix,
             is_touching_air: false,
+            visible_faces: 0,
+        }
+    }
+
+    // set which faces are showing, and keep is touching air in sync with it
+    pub fn set_visible_faces(&mut self, visible_faces: u8) {
+        self.visible_faces = visible_faces;
+        self.is_touching_air = visible_faces != 0;
+    }
+}
+
+// the 6 faces of a block, in the same order the touching air compute shader checks them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockFace {
+    Top,    // y + 1
+    Bottom, // y - 1
+    Right,  // x + 1
+    Left,   // x - 1
+    Back,   // z + 1
+    Front,  // z - 1
+}
+
+impl BlockFace {
+    pub const ALL: [BlockFace; 6] = [
+        BlockFace::Top,
+        BlockFace::Bottom,
+        BlockFace::Right,
+        BlockFace::Left,
+        BlockFace::Back,
+        BlockFace::Front,
+    ];
+
+    // which way this face points, add it to a blocks position to get the block touching this face
+    pub fn offset(&self) -> (i32, i16, i32) {
+        match self {
+            BlockFace::Top => (0, 1, 0),
+            BlockFace::Bottom => (0, -1, 0),
+            BlockFace::Right => (1, 0, 0),
+            BlockFace::Left => (-1, 0, 0),
+            BlockFace::Back => (0, 0, 1),
+            BlockFace::Front => (0, 0, -1),
+        }
+    }
+
+    // the bit for this face in a blocks visible faces
+    pub fn bit(&self) -> u8 {
+        match self {
+            BlockFace::Top => 1 << 0,
+            BlockFace::Bottom => 1 << 1,
+            BlockFace::Right => 1 << 2,
+            BlockFace::Left => 1 << 3,
+            BlockFace::Back => 1 << 4,
+            BlockFace::Front => 1 << 5,
         }
     }
 }
+
+// all 6 faces showing
+pub const ALL_FACES_VISIBLE: u8 = 0b11_1111;
@@ -1,3 +1,5 @@
+use crate::error::*;
+
 // what type of block is it
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BlockType {
@@ -18,6 +20,20 @@ pub enum BlockType {
 
     Stone,
     Cobblestone,
+
+    // fluids
+    Water,             // a source block, it never runs out
+    FlowingWater(u8),  // water that has flowed from a source, the level goes down from 7 as it spreads (see fluid.rs)
+
+    // trees
+    Log,
+    Leaves,
+
+    // ores
+    CoalOre,
+    IronOre,
+    GoldOre,
+    DiamondOre,
 }
 
 impl BlockType {
@@ -36,6 +52,18 @@ impl BlockType {
 
             BlockType::Stone => false,
             BlockType::Cobblestone => false,
+
+            // fluids are updated by the fluid simulation instead
+            BlockType::Water => false,
+            BlockType::FlowingWater(_) => false,
+
+            BlockType::Log => false,
+            BlockType::Leaves => false,
+
+            BlockType::CoalOre => false,
+            BlockType::IronOre => false,
+            BlockType::GoldOre => false,
+            BlockType::DiamondOre => false,
         }
     }
 
@@ -53,6 +81,17 @@ impl BlockType {
 
             BlockType::Stone => [100.0, 100.0, 100.0, 255.0].map(|x: f32| x / 255.0),
             BlockType::Cobblestone => [150.0, 150.0, 150.0, 255.0].map(|x: f32| x / 255.0),
+
+            BlockType::Water => [40.0, 80.0, 200.0, 160.0].map(|x: f32| x / 255.0),
+            BlockType::FlowingWater(_) => [60.0, 100.0, 210.0, 140.0].map(|x: f32| x / 255.0),
+
+            BlockType::Log => [100.0, 70.0, 40.0, 255.0].map(|x: f32| x / 255.0),
+            BlockType::Leaves => [50.0, 120.0, 40.0, 255.0].map(|x: f32| x / 255.0),
+
+            BlockType::CoalOre => [40.0, 40.0, 40.0, 255.0].map(|x: f32| x / 255.0),
+            BlockType::IronOre => [180.0, 140.0, 110.0, 255.0].map(|x: f32| x / 255.0),
+            BlockType::GoldOre => [230.0, 190.0, 40.0, 255.0].map(|x: f32| x / 255.0),
+            BlockType::DiamondOre => [90.0, 220.0, 220.0, 255.0].map(|x: f32| x / 255.0),
         }
     }
 
@@ -72,9 +111,36 @@ impl BlockType {
 
             BlockType::Stone => false,
             BlockType::Cobblestone => false,
+
+            BlockType::Water => true,
+            BlockType::FlowingWater(_) => true,
+
+            BlockType::Log => false,
+            BlockType::Leaves => false,
+
+            BlockType::CoalOre => false,
+            BlockType::IronOre => false,
+            BlockType::GoldOre => false,
+            BlockType::DiamondOre => false,
         }
     }
 
+    // if things (like the character) collide with this block, or can move through it
+    pub fn is_solid(&self) -> bool {
+        !matches!(self, BlockType::Air | BlockType::Void | BlockType::Water | BlockType::FlowingWater(_))
+    }
+
+    // if the face of this block touching the neighbour block can be seen
+    // transparent blocks show the faces behind them, but not between two fluids (eg. inside a lake) so only the outside is drawn
+    pub fn is_face_visible(&self, neighbour: BlockType) -> bool {
+        neighbour.is_transparent() && !(self.is_fluid() && neighbour.is_fluid())
+    }
+
+    // if this block is a liquid, source or flowing
+    pub fn is_fluid(&self) -> bool {
+        matches!(self, BlockType::Water | BlockType::FlowingWater(_))
+    }
+
     pub fn to_int(&self) -> u16 {
         match self {
             // special blocks 0-20
@@ -94,11 +160,23 @@ impl BlockType {
 
             BlockType::Stone => 400,
             BlockType::Cobblestone => 401,
+
+            // flowing water is 331 - 337 depending on its level
+            BlockType::Water => 330,
+            BlockType::FlowingWater(level) => 330 + (*level).clamp(1, 7) as u16,
+
+            BlockType::Log => 350,
+            BlockType::Leaves => 351,
+
+            BlockType::CoalOre => 410,
+            BlockType::IronOre => 411,
+            BlockType::GoldOre => 412,
+            BlockType::DiamondOre => 413,
         }
     }
 
-    pub fn from_int(id: u16) -> Self {
-        match id {
+    pub fn from_int(id: u16) -> Result<BlockType, GameError> {
+        let block_type: BlockType = match id {
             // special blocks 0-20
             0 => BlockType::Air,
             1 => BlockType::Bedrock,
@@ -117,8 +195,20 @@ impl BlockType {
             400 => BlockType::Stone,
             401 => BlockType::Cobblestone,
 
-            // if not found panic
-            _ => panic!("BlockType not found for id: {}", id),
-        }
+            330 => BlockType::Water,
+            331..=337 => BlockType::FlowingWater((id - 330) as u8),
+
+            350 => BlockType::Log,
+            351 => BlockType::Leaves,
+
+            410 => BlockType::CoalOre,
+            411 => BlockType::IronOre,
+            412 => BlockType::GoldOre,
+            413 => BlockType::DiamondOre,
+
+            // an id from a newer version of the game or a corrupted file
+            _ => return Err(GameError::UnknownBlockType(id)),
+        };
+        Ok(block_type)
     }
 }
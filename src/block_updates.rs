@@ -0,0 +1,61 @@
+/*
+Block updates are how blocks that can change on their own (dynamic blocks, like sand) get a chance to do something
+when a block changes the dynamic blocks it could affect are scheduled a tick (see tick_scheduler.rs)
+then when the tick runs the block can move and schedule more ticks, so sand on top of sand
+keeps falling one after the other as a chain reaction
+
+like the fluid simulation, the falling blocks only read the world through the get block function passed in
+so it can be tested without any chunks or a gpu
+*/
+
+use crate::{block_type::*, chunk::decorate_chunks::BlockWrite};
+
+use std::collections::BTreeSet;
+
+// how many ticks a falling block waits before moving down another block
+pub const FALLING_BLOCK_DELAY: u64 = 1;
+
+/*
+where the block at this position falls to this tick, none if it isnt a dynamic block or there is something solid under it
+blocks fall one block per tick into anything that isnt solid (air or water)
+get block returns none for blocks that cant be read (unloaded chunks or under the world) so nothing falls into them
+*/
+pub fn get_falling_block_move<F>(block_pos: (i32, i16, i32), get_block: &F) -> Option<(i32, i16, i32)>
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    if !get_block(block_pos)?.is_dynamic() {
+        return None;
+    }
+
+    let below_pos: (i32, i16, i32) = (block_pos.0, block_pos.1 - 1, block_pos.2);
+    if get_block(below_pos)?.is_solid() {
+        return None;
+    }
+
+    Some(below_pos)
+}
+
+/*
+work out how the dynamic blocks at these positions move this tick
+each block that falls leaves air behind and is written one block lower, the moves are all worked out from how the world
+was at the start of the tick so a block only follows the one under it on the next tick
+*/
+pub fn simulate_falling_blocks<F>(positions: &BTreeSet<(i32, i16, i32)>, get_block: F) -> Vec<BlockWrite>
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    let mut block_writes: Vec<BlockWrite> = Vec::new();
+
+    for block_pos in positions.iter() {
+        if let Some(fall_pos) = get_falling_block_move(*block_pos, &get_block) {
+            // already checked the block can be read
+            let block_type: BlockType = get_block(*block_pos).unwrap();
+
+            block_writes.push((*block_pos, BlockType::Air));
+            block_writes.push((fall_pos, block_type));
+        }
+    }
+
+    block_writes
+}
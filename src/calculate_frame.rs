@@ -40,6 +40,9 @@ pub fn calculate_frame(
     // update characters chunk position
     character.update_chunk_position(world.chunk_sizes);
 
+    // load the chunks nearest to where the character is and where it is looking first
+    world.set_chunk_load_centre(character);
+
     // update the pending chunks and add them to the chunks if they are ready
     world.update_pending_chunks(renderer);
 
@@ -49,8 +52,14 @@ pub fn calculate_frame(
         character.chunk_changed = false;
         let chunks_to_load: HashSet<(i32, i32)> = world.get_chunks_around_character(character);
         world.update_chunks_around_character(renderer, file_system, chunks_to_load)
+    } else {
+        // keep loading the chunks that are still queued, a few each frame
+        world.load_queued_chunks(renderer, file_system);
     }
 
+    // run the world ticks, so any water that is moving flows and any blocks that are falling fall
+    world.update_ticks();
+
     // Calculate the new view and combined matrices
     camera.update(renderer, gpu_data, character);
 
@@ -61,4 +70,7 @@ pub fn calculate_frame(
     for chunk in world.chunks.values_mut() {
         chunk.update(renderer);
     }
+
+    // and their greedy meshes if they are being used
+    world.update_chunk_meshes(renderer);
 }
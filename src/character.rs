@@ -19,6 +19,9 @@ pub struct Character {
 
     // settings
     pub movement_speed: f32,
+
+    // how far away (in blocks) the character can break and place blocks
+    pub reach: f32,
 }
 
 impl Character {
@@ -39,6 +42,7 @@ impl Character {
             pitch: 0.0,
             chunk_changed: true, // init to true to it loads in the correct chunks
             movement_speed,
+            reach: 5.0,
         }
     }
 
@@ -96,6 +100,15 @@ impl Character {
     pub fn get_current_chunk(&self) -> (i32, i32) {
         self.chunk_position
     }
+
+    // the direction the character is looking in (length 1), the same direction used to calculate the target
+    pub fn get_look_direction(&self) -> FPosition {
+        FPosition {
+            x: self.yaw.cos() * self.pitch.cos(),
+            y: self.pitch.sin(),
+            z: self.yaw.sin() * self.pitch.cos(),
+        }
+    }
     
 
     pub fn move_forward(&mut self, amount: f32) {
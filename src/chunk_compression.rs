@@ -0,0 +1,222 @@
+/*
+How a chunks blocks are turned into bytes to be saved in a region file
+most of a chunk is long runs of the same block (air above the ground, stone under it) so saving every block id on its own wastes a lot of space
+instead the chunk is split into sections SECTION_HEIGHT blocks high, and each section has
+    a palette, the block ids that are in it
+    runs of blocks, each one a palette index and how many blocks in a row have it
+a section that is all air is only a few bytes
+
+the first byte is the format (the chunks version) so the raw version (every id as a u16) can still be read
+the blocks are always in the same order as the text files, x then z then y, so a section is one slice of the list
+*/
+
+use crate::block_type::*;
+
+use std::io;
+
+// how many y layers are in each section, the top section is smaller if the chunk height isnt a multiple of this
+pub const SECTION_HEIGHT: usize = 16;
+
+const RAW_FORMAT: u8 = 0;
+const PALETTE_FORMAT: u8 = 1;
+// the newest format this version of the game can read
+const NEWEST_FORMAT: u8 = PALETTE_FORMAT;
+
+// palettes bigger than this use u16 palette indexes instead of u8
+const SMALL_PALETTE_SIZE: usize = 256;
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/*
+each section is
+    palette length                  u16
+    palette                         a u16 block id for each
+    runs                            (palette index, run length) until the section is full
+palette indexes are a u8, or a u16 if the palette has more than 256 blocks
+run lengths are a varint (7 bits a byte, the top bit is set if there is another byte)
+*/
+pub fn encode_chunk_blocks(block_types: &[BlockType], chunk_sizes: (usize, usize, usize)) -> Vec<u8> {
+    let mut data: Vec<u8> = vec![PALETTE_FORMAT];
+
+    let section_size: usize = chunk_sizes.0 * chunk_sizes.2 * SECTION_HEIGHT;
+    for section in block_types.chunks(section_size) {
+        encode_section(section, &mut data);
+    }
+
+    data
+}
+
+fn encode_section(section: &[BlockType], data: &mut Vec<u8>) {
+    // the palette is in the order the blocks are first found
+    let mut palette: Vec<u16> = Vec::new();
+    let mut palette_indexes: Vec<u16> = Vec::with_capacity(section.len());
+    for block_type in section.iter() {
+        let block_id: u16 = block_type.to_int();
+        let palette_index: usize = match palette.iter().position(|palette_id| *palette_id == block_id) {
+            Some(palette_index) => palette_index,
+            None => {
+                palette.push(block_id);
+                palette.len() - 1
+            }
+        };
+        palette_indexes.push(palette_index as u16);
+    }
+
+    data.extend_from_slice(&(palette.len() as u16).to_le_bytes());
+    for block_id in palette.iter() {
+        data.extend_from_slice(&block_id.to_le_bytes());
+    }
+
+    let is_small_palette: bool = palette.len() <= SMALL_PALETTE_SIZE;
+    let mut index: usize = 0;
+    while index < palette_indexes.len() {
+        let palette_index: u16 = palette_indexes[index];
+        let mut run_length: usize = 1;
+        while index + run_length < palette_indexes.len() && palette_indexes[index + run_length] == palette_index {
+            run_length += 1;
+        }
+
+        if is_small_palette {
+            data.push(palette_index as u8);
+        } else {
+            data.extend_from_slice(&palette_index.to_le_bytes());
+        }
+        write_varint(run_length, data);
+
+        index += run_length;
+    }
+}
+
+// every block id as a u16, how chunks were saved before they were compressed
+pub fn encode_chunk_blocks_raw(block_types: &[BlockType]) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::with_capacity(block_types.len() * 2 + 1);
+    data.push(RAW_FORMAT);
+    for block_type in block_types.iter() {
+        data.extend_from_slice(&block_type.to_int().to_le_bytes());
+    }
+    data
+}
+
+// read the blocks back in either format, an error if the bytes dont make a whole chunk
+pub fn decode_chunk_blocks(data: &[u8], chunk_sizes: (usize, usize, usize)) -> io::Result<Vec<BlockType>> {
+    let block_count: usize = chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2;
+    let mut reader: ByteReader = ByteReader::new(data);
+
+    let mut block_types: Vec<BlockType> = Vec::with_capacity(block_count);
+    match reader.read_u8()? {
+        RAW_FORMAT => {
+            for _ in 0..block_count {
+                block_types.push(read_block_type(&mut reader)?);
+            }
+        }
+        PALETTE_FORMAT => {
+            let section_size: usize = chunk_sizes.0 * chunk_sizes.2 * SECTION_HEIGHT;
+            while block_types.len() < block_count {
+                let section_end: usize = (block_types.len() + section_size).min(block_count);
+                decode_section(&mut reader, &mut block_types, section_end)?;
+            }
+        }
+        // every format up to the newest one is read above, a newer one isnt corrupted so it is a different kind of error
+        format => {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, format!(
+                "Chunk was saved in format {} by a newer version of the game, this version can only read up to format {}",
+                format, NEWEST_FORMAT
+            )))
+        }
+    }
+
+    if !reader.is_finished() {
+        return Err(invalid_data(format!("Chunk has {} bytes left over", data.len() - reader.position)));
+    }
+
+    Ok(block_types)
+}
+
+fn decode_section(reader: &mut ByteReader, block_types: &mut Vec<BlockType>, section_end: usize) -> io::Result<()> {
+    let palette_length: usize = reader.read_u16()? as usize;
+    let mut palette: Vec<BlockType> = Vec::with_capacity(palette_length);
+    for _ in 0..palette_length {
+        palette.push(read_block_type(reader)?);
+    }
+
+    let is_small_palette: bool = palette_length <= SMALL_PALETTE_SIZE;
+    while block_types.len() < section_end {
+        let palette_index: usize = if is_small_palette { reader.read_u8()? as usize } else { reader.read_u16()? as usize };
+        let run_length: usize = reader.read_varint()?;
+
+        let block_type: BlockType = *palette
+            .get(palette_index)
+            .ok_or_else(|| invalid_data(format!("Palette index {} is outside the palette of {}", palette_index, palette_length)))?;
+        // not block_types.len() + run_length, a bad run length near usize::MAX would overflow
+        if run_length == 0 || run_length > section_end - block_types.len() {
+            return Err(invalid_data(format!("Run of {} blocks doesnt fit in the section", run_length)));
+        }
+
+        block_types.resize(block_types.len() + run_length, block_type);
+    }
+
+    Ok(())
+}
+
+// a block id, an error if it isnt a block this version of the game has
+fn read_block_type(reader: &mut ByteReader) -> io::Result<BlockType> {
+    BlockType::from_int(reader.read_u16()?).map_err(|e| invalid_data(e.to_string()))
+}
+
+fn write_varint(mut value: usize, data: &mut Vec<u8>) {
+    while value >= 0x80 {
+        data.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+// reads numbers from the start of the bytes, with an error instead of a panic if it runs out
+struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> ByteReader<'a> {
+        ByteReader { data, position: 0 }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    fn read_bytes(&mut self, count: usize) -> io::Result<&'a [u8]> {
+        if self.position + count > self.data.len() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Chunk ended before all of its blocks were read"));
+        }
+
+        let bytes: &[u8] = &self.data[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> io::Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> io::Result<u16> {
+        let bytes: &[u8] = self.read_bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_varint(&mut self) -> io::Result<usize> {
+        let mut value: usize = 0;
+        // a usize never needs more than 10 bytes
+        for byte_index in 0..10 {
+            let byte: u8 = self.read_u8()?;
+            value |= ((byte & 0x7f) as usize) << (byte_index * 7);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid_data(String::from("Run length is too long")))
+    }
+}
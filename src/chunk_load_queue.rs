@@ -0,0 +1,196 @@
+/*
+The chunks that are waiting to be loaded, so they are loaded nearest first and only a few each frame
+when the character walks into a new chunk a whole row of chunks needs loading, doing them all at once stops the game for a few frames
+so they are put in here and taken out a bit at a time, the closest ones to the character first (and the ones in front of it before the ones behind)
+chunks that go out of range before they are loaded are just taken back out
+*/
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+// how much further away a chunk directly behind the character counts as, compared to one the same distance in front
+const BEHIND_DISTANCE_MULTIPLIER: f32 = 1.5;
+
+// how many chunks can be loaded in a frame, none for no limit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChunkLoadBudget {
+    // the most chunks added to the world each frame
+    pub max_chunks: Option<usize>,
+
+    // stop adding chunks once this much of the frame has been spent on them (at least 1 chunk is always added)
+    pub max_time: Option<Duration>,
+}
+
+impl ChunkLoadBudget {
+    // load everything straight away, like before there was a queue (used in tests and when the world first loads)
+    pub fn unlimited() -> ChunkLoadBudget {
+        ChunkLoadBudget {
+            max_chunks: None,
+            max_time: None,
+        }
+    }
+
+    pub fn new(max_chunks: Option<usize>, max_time: Option<Duration>) -> ChunkLoadBudget {
+        ChunkLoadBudget { max_chunks, max_time }
+    }
+
+    // if no more chunks should be loaded this frame
+    pub fn is_spent(&self, chunks_loaded: usize, frame_start: Instant) -> bool {
+        if let Some(max_chunks) = self.max_chunks {
+            if chunks_loaded >= max_chunks {
+                return true;
+            }
+        }
+
+        if let Some(max_time) = self.max_time {
+            if chunks_loaded > 0 && frame_start.elapsed() >= max_time {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+// how the queue is doing, to see if chunks are being loaded fast enough
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChunkLoadMetrics {
+    // chunks waiting in the queue (not including the ones the workers are loading)
+    pub queue_depth: usize,
+    // the most chunks there have been in the queue at once
+    pub max_queue_depth: usize,
+    // chunks sent to the workers that havnt come back yet
+    pub loading_count: usize,
+
+    // chunks added to the world last frame and how long it took
+    pub loaded_last_frame: usize,
+    pub last_frame_time: Duration,
+
+    pub total_loaded: usize,
+    // chunks that went out of range before they were loaded
+    pub total_cancelled: usize,
+}
+
+pub struct ChunkLoadQueue {
+    queued_chunks: HashSet<(i32, i32)>,
+
+    // the chunk the character is in and the way it is looking (x, z), so the closest chunks and the ones in front are loaded first
+    centre: (i32, i32),
+    look_direction: Option<(f32, f32)>,
+
+    pub budget: ChunkLoadBudget,
+
+    metrics: ChunkLoadMetrics,
+}
+
+impl ChunkLoadQueue {
+    pub fn new(budget: ChunkLoadBudget) -> ChunkLoadQueue {
+        ChunkLoadQueue {
+            queued_chunks: HashSet::new(),
+            centre: (0, 0),
+            look_direction: None,
+            budget,
+            metrics: ChunkLoadMetrics::default(),
+        }
+    }
+
+    // the order is worked out when chunks are taken out, so moving or turning changes which chunk is next straight away
+    pub fn set_centre(&mut self, centre: (i32, i32), look_direction: Option<(f32, f32)>) {
+        self.centre = centre;
+        self.look_direction = look_direction;
+    }
+
+    pub fn get_centre(&self) -> (i32, i32) {
+        self.centre
+    }
+
+    // add a chunk to the queue, nothing happens if it is already in it
+    pub fn queue_chunk(&mut self, chunk_id: (i32, i32)) {
+        self.queued_chunks.insert(chunk_id);
+        self.metrics.queue_depth = self.queued_chunks.len();
+        self.metrics.max_queue_depth = self.metrics.max_queue_depth.max(self.metrics.queue_depth);
+    }
+
+    pub fn is_queued(&self, chunk_id: (i32, i32)) -> bool {
+        self.queued_chunks.contains(&chunk_id)
+    }
+
+    pub fn get_queue_depth(&self) -> usize {
+        self.queued_chunks.len()
+    }
+
+    // take out every queued chunk that isnt in chunks_to_load any more, returns how many were taken out
+    pub fn cancel_chunks_not_in(&mut self, chunks_to_load: &HashSet<(i32, i32)>) -> usize {
+        let queue_depth: usize = self.queued_chunks.len();
+        self.queued_chunks.retain(|chunk_id| chunks_to_load.contains(chunk_id));
+
+        let cancelled: usize = queue_depth - self.queued_chunks.len();
+        self.metrics.total_cancelled += cancelled;
+        self.metrics.queue_depth = self.queued_chunks.len();
+        cancelled
+    }
+
+    // take out the chunk that should be loaded next
+    pub fn pop_next_chunk(&mut self) -> Option<(i32, i32)> {
+        let mut next_chunk: Option<((i32, i32), f32)> = None;
+        for chunk_id in self.queued_chunks.iter() {
+            let priority: f32 = get_chunk_load_priority(*chunk_id, self.centre, self.look_direction);
+
+            // ties are broken by the chunk id so the order is the same every run (the hashset order isnt)
+            let is_next: bool = match next_chunk {
+                None => true,
+                Some((next_id, next_priority)) => priority < next_priority || (priority == next_priority && *chunk_id < next_id),
+            };
+            if is_next {
+                next_chunk = Some((*chunk_id, priority));
+            }
+        }
+
+        let (chunk_id, _) = next_chunk?;
+        self.queued_chunks.remove(&chunk_id);
+        self.metrics.queue_depth = self.queued_chunks.len();
+        Some(chunk_id)
+    }
+
+    // how many chunks were added to the world this frame
+    pub fn record_frame(&mut self, chunks_loaded: usize, frame_start: Instant) {
+        self.metrics.loaded_last_frame = chunks_loaded;
+        self.metrics.last_frame_time = frame_start.elapsed();
+        self.metrics.total_loaded += chunks_loaded;
+    }
+
+    // the loading count is only known by the chunk loader so it is passed in
+    pub fn get_metrics(&self, loading_count: usize) -> ChunkLoadMetrics {
+        ChunkLoadMetrics {
+            loading_count,
+            ..self.metrics
+        }
+    }
+}
+
+/*
+lower is loaded first, it is the distance from the centre in chunks
+if the look direction is known chunks behind count as further away than chunks in front, up to BEHIND_DISTANCE_MULTIPLIER times
+so the chunk the character is in is always first and nearby chunks still come before far away ones in front
+*/
+pub fn get_chunk_load_priority(chunk_id: (i32, i32), centre: (i32, i32), look_direction: Option<(f32, f32)>) -> f32 {
+    let diff_x: f32 = (chunk_id.0 - centre.0) as f32;
+    let diff_z: f32 = (chunk_id.1 - centre.1) as f32;
+    let distance: f32 = (diff_x * diff_x + diff_z * diff_z).sqrt();
+
+    let look_direction: (f32, f32) = match look_direction {
+        Some(look_direction) => look_direction,
+        None => return distance,
+    };
+    let look_length: f32 = (look_direction.0 * look_direction.0 + look_direction.1 * look_direction.1).sqrt();
+    // looking straight up or down, so every way is as good as the others
+    if look_length < 0.001 || distance == 0.0 {
+        return distance;
+    }
+
+    // 1 straight in front, -1 straight behind
+    let facing: f32 = (diff_x * look_direction.0 + diff_z * look_direction.1) / (distance * look_length);
+    distance * (1.0 + (BEHIND_DISTANCE_MULTIPLIER - 1.0) * (1.0 - facing) / 2.0)
+}
@@ -0,0 +1,291 @@
+/*
+Reads and generates chunks on worker threads, so the game doesnt stop for a frame each time the character walks into a new chunk
+the main thread sends the ids of the chunks it wants to the workers, with the edges of the chunks around them at the time
+the workers read or generate the blocks and run the touching air pass on the cpu against those edges, then send them back
+so the main thread only has to make the buffers, put it into the world and check again any walls whose neighbours changed since
+*/
+
+use crate::{
+    biome::*,
+    block::*,
+    chunk::{chunk_borders::*, chunk_cpu_functions::*, chunk_functions::*, decorate_chunks::*},
+    error::*,
+    file_system::*,
+    world_generator::*,
+};
+
+use flume::{Receiver, Sender};
+use std::{
+    collections::HashSet,
+    thread::{self, JoinHandle},
+};
+
+// how many chunks each worker can have waiting at once, the rest wait in the load queue so they can still be reordered or cancelled
+const MAX_REQUESTS_PER_WORKER: usize = 2;
+
+// a chunk the main thread wants loaded, if it has been created before it is read from its file, otherwise it is generated
+pub struct ChunkLoadRequest {
+    pub chunk_id: (i32, i32),
+    pub is_created: bool,
+
+    // the edges of the chunks next to it when it was requested, for the touching air pass
+    pub chunk_borders: ChunkBorders,
+}
+
+// the blocks of a chunk a worker has finished with
+pub struct LoadedChunk {
+    pub chunk_id: (i32, i32),
+    pub temp_chunk_vec: Vec<Vec<Vec<Block>>>,
+
+    // the biomes are worked out here too, since they only need the seed
+    pub biomes: Vec<Vec<Biome>>,
+
+    // the borders the touching air pass was run against, so the walls can be checked again if the chunks next to it have changed since
+    pub chunk_borders: ChunkBorders,
+
+    // if it was generated rather than read, so it can be added to the created chunks
+    pub is_generated: bool,
+
+    // blocks from its decorations that reach into other chunks (always empty for chunks read from a file)
+    pub pending_block_writes: PendingBlockWrites,
+}
+
+pub struct ChunkLoader {
+    // none once the loader is being shut down, so the workers know to stop
+    request_sender: Option<Sender<ChunkLoadRequest>>,
+    // kept so requests that havnt been started can be thrown away when shutting down
+    request_receiver: Receiver<ChunkLoadRequest>,
+    // a chunk that cant be read (that isnt just corrupted) comes back as an error, so it isnt generated and saved over
+    loaded_receiver: Receiver<((i32, i32), Result<LoadedChunk, GameError>)>,
+
+    workers: Vec<JoinHandle<()>>,
+
+    // the chunks that have been sent to the workers and havnt been taken back yet
+    loading_chunks: HashSet<(i32, i32)>,
+
+    /*
+    chunks that went out of range while a worker had them, a request cant be taken back out of the channel
+    so they are still loaded, but thrown away when they come back instead of being put into the world
+    */
+    cancelled_chunks: HashSet<(i32, i32)>,
+
+    // how many cancelled chunks came back and were thrown away
+    thrown_away_count: usize,
+
+    // chunks the workers found were corrupted, waiting for the main thread to quarantine them (see take_corrupted_chunks)
+    corrupted_chunks: Vec<((i32, i32), GameError)>,
+}
+
+impl ChunkLoader {
+    /*
+    start the worker threads, each gets its own copy of the file system and generator
+    the seed and generator have to be the ones the world is using, so this is made after the world info is read
+    */
+    pub fn new(
+        worker_count: usize,
+        file_system: &FileSystem,
+        generator_type: &WorldGeneratorType,
+        world_seed: u64,
+        chunk_sizes: (usize, usize, usize),
+    ) -> ChunkLoader {
+        let (request_sender, request_receiver) = flume::unbounded::<ChunkLoadRequest>();
+        let (loaded_sender, loaded_receiver) = flume::unbounded::<((i32, i32), Result<LoadedChunk, GameError>)>();
+
+        let mut workers: Vec<JoinHandle<()>> = Vec::with_capacity(worker_count);
+        for worker_index in 0..worker_count.max(1) {
+            let request_receiver: Receiver<ChunkLoadRequest> = request_receiver.clone();
+            let loaded_sender: Sender<((i32, i32), Result<LoadedChunk, GameError>)> = loaded_sender.clone();
+            let mut file_system: FileSystem = file_system.clone();
+            let generator: Box<dyn WorldGenerator> = generator_type.create_generator();
+
+            let worker = thread::Builder::new()
+                .name(format!("chunk loader {}", worker_index))
+                .spawn(move || {
+                    // runs until the request sender is dropped
+                    while let Ok(request) = request_receiver.recv() {
+                        let mut pending_block_writes: PendingBlockWrites = PendingBlockWrites::new();
+                        // a corrupted chunk is given back as an error, it is quarantined on the main thread
+                        let loaded_chunk: Result<LoadedChunk, GameError> = try_read_or_generate_chunk(
+                            &mut file_system,
+                            request.chunk_id,
+                            request.is_created,
+                            chunk_sizes,
+                            generator.as_ref(),
+                            world_seed,
+                            &mut pending_block_writes,
+                        )
+                        .map(|mut temp_chunk_vec| {
+                            // always the cpu pass, the gpu belongs to the main thread
+                            check_for_touching_air_cpu(&mut temp_chunk_vec, &request.chunk_borders, chunk_sizes);
+
+                            let biomes: Vec<Vec<Biome>> = get_chunk_biomes(request.chunk_id, chunk_sizes, generator.as_ref(), world_seed);
+
+                            LoadedChunk {
+                                chunk_id: request.chunk_id,
+                                temp_chunk_vec,
+                                biomes,
+                                chunk_borders: request.chunk_borders,
+                                is_generated: !request.is_created,
+                                pending_block_writes,
+                            }
+                        });
+
+                        // the loader has been dropped so nothing wants this chunk anymore
+                        if loaded_sender.send((request.chunk_id, loaded_chunk)).is_err() {
+                            break;
+                        }
+                    }
+                })
+                .expect("failed to start chunk loader thread");
+
+            workers.push(worker);
+        }
+
+        ChunkLoader {
+            request_sender: Some(request_sender),
+            request_receiver,
+            loaded_receiver,
+            workers,
+            loading_chunks: HashSet::new(),
+            cancelled_chunks: HashSet::new(),
+            thrown_away_count: 0,
+            corrupted_chunks: Vec::new(),
+        }
+    }
+
+    // send a chunk to the workers with the edges of the chunks around it, nothing happens if it is already loading
+    pub fn request_chunk(&mut self, chunk_id: (i32, i32), is_created: bool, chunk_borders: ChunkBorders) {
+        if !self.loading_chunks.insert(chunk_id) {
+            // it was cancelled but is wanted again before it came back, so it can be used after all
+            self.cancelled_chunks.remove(&chunk_id);
+            return;
+        }
+
+        if let Some(request_sender) = &self.request_sender {
+            // the workers only stop once the sender is dropped so this cant fail
+            let _ = request_sender.send(ChunkLoadRequest {
+                chunk_id,
+                is_created,
+                chunk_borders,
+            });
+        }
+    }
+
+    // cancelled chunks are still being loaded by a worker, but they wont be given back
+    pub fn is_loading(&self, chunk_id: (i32, i32)) -> bool {
+        self.loading_chunks.contains(&chunk_id) && !self.cancelled_chunks.contains(&chunk_id)
+    }
+
+    pub fn get_loading_count(&self) -> usize {
+        self.loading_chunks.len() - self.cancelled_chunks.len()
+    }
+
+    pub fn get_thrown_away_count(&self) -> usize {
+        self.thrown_away_count
+    }
+
+    pub fn get_worker_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn has_corrupted_chunks(&self) -> bool {
+        !self.corrupted_chunks.is_empty()
+    }
+
+    // the chunks that came back corrupted since this was last called, with why they couldnt be read
+    pub fn take_corrupted_chunks(&mut self) -> Vec<((i32, i32), GameError)> {
+        std::mem::take(&mut self.corrupted_chunks)
+    }
+
+    // if more chunks can be sent without the workers falling behind (cancelled chunks still take up a worker until they are done)
+    pub fn has_room_for_requests(&self) -> bool {
+        self.loading_chunks.len() < self.workers.len() * MAX_REQUESTS_PER_WORKER
+    }
+
+    // cancel every loading chunk that isnt in chunks_to_load any more, returns how many were cancelled (some of them might be wanted again before they come back)
+    pub fn cancel_chunks_not_in(&mut self, chunks_to_load: &HashSet<(i32, i32)>) -> usize {
+        let mut cancelled: usize = 0;
+        for chunk_id in self.loading_chunks.iter() {
+            if !chunks_to_load.contains(chunk_id) && self.cancelled_chunks.insert(*chunk_id) {
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
+
+    // the next chunk that has finished, without waiting for one
+    pub fn take_loaded_chunk(&mut self) -> Option<LoadedChunk> {
+        loop {
+            let (chunk_id, loaded_chunk) = self.loaded_receiver.try_recv().ok()?;
+            if let Some(loaded_chunk) = self.finish_request(chunk_id, loaded_chunk) {
+                return Some(loaded_chunk);
+            }
+        }
+    }
+
+    // all of the chunks that have finished since this was last called, without waiting for any
+    pub fn take_loaded_chunks(&mut self) -> Vec<LoadedChunk> {
+        let mut loaded_chunks: Vec<LoadedChunk> = Vec::new();
+        while let Some(loaded_chunk) = self.take_loaded_chunk() {
+            loaded_chunks.push(loaded_chunk);
+        }
+        loaded_chunks
+    }
+
+    // wait for the next chunk to finish, none if there arnt any chunks loading
+    pub fn wait_for_loaded_chunk(&mut self) -> Option<LoadedChunk> {
+        while !self.loading_chunks.is_empty() {
+            let (chunk_id, loaded_chunk) = self.loaded_receiver.recv().ok()?;
+            if let Some(loaded_chunk) = self.finish_request(chunk_id, loaded_chunk) {
+                return Some(loaded_chunk);
+            }
+        }
+        None
+    }
+
+    /*
+    the chunk isnt loading any more, none if it was cancelled so it is thrown away
+    or if it couldnt be read, then it is left out of the world and tried again when the chunks around the character change
+    a corrupted chunk is kept for the main thread to quarantine and generate again instead
+    */
+    fn finish_request(&mut self, chunk_id: (i32, i32), loaded_chunk: Result<LoadedChunk, GameError>) -> Option<LoadedChunk> {
+        self.loading_chunks.remove(&chunk_id);
+        if self.cancelled_chunks.remove(&chunk_id) {
+            self.thrown_away_count += 1;
+            return None;
+        }
+
+        match loaded_chunk {
+            Ok(loaded_chunk) => Some(loaded_chunk),
+            Err(e @ GameError::CorruptedChunk(..)) => {
+                self.corrupted_chunks.push((chunk_id, e));
+                None
+            }
+            Err(e) => {
+                eprintln!("{}, it wont be loaded", e);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for ChunkLoader {
+    fn drop(&mut self) {
+        // throw away the chunks that havnt been started, then stop the workers once they finish the ones they are on
+        // chunks that never made it back arnt in the created chunks so they are just generated again next time
+        self.request_sender = None;
+        self.request_receiver.drain().for_each(drop);
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+// one worker for each core, leaving one for the main thread
+pub fn get_default_worker_count() -> usize {
+    thread::available_parallelism()
+        .map(|cores| cores.get().saturating_sub(1))
+        .unwrap_or(1)
+        .max(1)
+}
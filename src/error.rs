@@ -0,0 +1,83 @@
+/*
+The errors reading and writing the world can give, so a bad file is something the game can deal with instead of a panic
+a corrupted chunk is moved into Quarantine/ and generated again (see read_or_generate_chunk)
+but one from a newer version of the game is left alone and not loaded, so it isnt saved over
+and a broken ChunksCreated.txt is worked out again from the saved chunks
+*/
+
+use crate::{world::*, world_format::*};
+
+use std::{fmt, io, path::PathBuf};
+
+#[derive(Debug)]
+pub enum GameError {
+    // a block id that isnt any block type, from a file saved by a newer version of the game or a corrupted one
+    UnknownBlockType(u16),
+
+    // the chunk is in the created chunks but there is no file with it in
+    ChunkFileMissing((i32, i32), PathBuf),
+
+    // the chunks saved data is there but cant be read, (the chunk, the file it is in, what was wrong with it)
+    CorruptedChunk((i32, i32), PathBuf, String),
+
+    // the chunk or its region was saved by a newer version of the game, (the chunk, the file it is in, what was newer)
+    NewerChunkVersion((i32, i32), PathBuf, String),
+
+    // a line in one of the world files cant be read, (the file, the line number starting at 1, what was wrong with it)
+    MalformedFile(PathBuf, usize, String),
+
+    // a file couldnt be written when saving the world, (the file, why it couldnt be written)
+    SaveFailed(PathBuf, io::Error),
+
+    Io(io::Error),
+    World(WorldError),
+    WorldFormat(WorldFormatError),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::UnknownBlockType(id) => {
+                write!(f, "There is no block type with id {}", id)
+            }
+            GameError::ChunkFileMissing(chunk_id, path) => {
+                write!(f, "Chunk ({}, {}) has been created but its file {:?} does not exist", chunk_id.0, chunk_id.1, path)
+            }
+            GameError::CorruptedChunk(chunk_id, path, message) => {
+                write!(f, "Chunk ({}, {}) in {:?} is corrupted: {}", chunk_id.0, chunk_id.1, path, message)
+            }
+            GameError::NewerChunkVersion(chunk_id, path, message) => {
+                write!(f, "Chunk ({}, {}) in {:?} can not be read by this version of the game: {}", chunk_id.0, chunk_id.1, path, message)
+            }
+            GameError::MalformedFile(path, line_number, message) => {
+                write!(f, "Line {} of {:?} can not be read: {}", line_number, path, message)
+            }
+            GameError::SaveFailed(path, e) => {
+                write!(f, "Could not save {:?}: {}", path, e)
+            }
+            GameError::Io(e) => write!(f, "{}", e),
+            GameError::World(e) => write!(f, "{}", e),
+            GameError::WorldFormat(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<io::Error> for GameError {
+    fn from(e: io::Error) -> GameError {
+        GameError::Io(e)
+    }
+}
+
+impl From<WorldError> for GameError {
+    fn from(e: WorldError) -> GameError {
+        GameError::World(e)
+    }
+}
+
+impl From<WorldFormatError> for GameError {
+    fn from(e: WorldFormatError) -> GameError {
+        GameError::WorldFormat(e)
+    }
+}
@@ -1,17 +1,25 @@
 use crate::{
     block::*, 
     block_type::*, 
-    chunk::*,
+    chunk::{decorate_chunks::*, *},
+    chunk_compression::*,
+    error::*,
+    region_file::*,
+    tick_scheduler::*,
+    world_format::*,
+    world_generator::*,
 };
 
 use std::{
     env,
-    fs::{create_dir_all, File},
+    fs::{self, create_dir_all, File},
     io::{self, BufRead, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    str::FromStr,
     collections::HashSet,
 };
 
+#[derive(Clone)]
 pub struct FileSystem {
     pub assets_directory: PathBuf,   // the directory of the assets folder
     pub my_world_directory: PathBuf, // the directory of blah/james's World/
@@ -25,13 +33,13 @@ impl FileSystem {
         }
     }
 
-    // will check if the files have been created for this world and if not it will create them
-    pub fn check_file_system(&mut self, chunk_sizes: (usize, usize, usize), world_name: &str) {
+    // will check if the files have been created for this world and if not it will create them, an error if they cant be made
+    pub fn check_file_system(&mut self, chunk_sizes: (usize, usize, usize), world_name: &str) -> Result<(), GameError> {
         // first check that the data folder exists
         self.check_data_folder();
 
         // now check if this game world has a folder and files, if it doesnt ill make them
-        self.check_game_files(chunk_sizes, world_name);
+        self.check_game_files(chunk_sizes, world_name)
     }
 
     pub fn check_data_folder(&mut self) {
@@ -56,7 +64,7 @@ impl FileSystem {
         }
     }
 
-    pub fn check_game_files(&mut self, chunk_sizes: (usize, usize, usize), world_name: &str) {
+    pub fn check_game_files(&mut self, chunk_sizes: (usize, usize, usize), world_name: &str) -> Result<(), GameError> {
         // get to the data dir
         let mut path: PathBuf = self.assets_directory.clone();
         path.push("data");
@@ -65,25 +73,15 @@ impl FileSystem {
         path.push("Worlds");
         if !path.exists() {
             // Create the directory if it does not exist
-            create_dir_all(&path).unwrap();
+            create_dir_all(&path).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
         }
 
         // now check if there is a folder for this game world
         path.push(world_name);
         if !path.exists() {
             // Create the directory if it does not exist
-            match create_dir_all(&path) {
-                Ok(_) => {
-                    println!("Created new game world directory: {:?}", world_name);
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Failed to create new game world directory at path: {:?}",
-                        path
-                    );
-                    panic!("Error: {}", e);
-                }
-            }
+            create_dir_all(&path).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
+            println!("Created new game world directory: {:?}", world_name);
         }
 
         self.my_world_directory = path.clone();
@@ -91,7 +89,14 @@ impl FileSystem {
         path.push("Chunks");
         if !path.exists() {
             // Create the directory if it does not exist
-            create_dir_all(&path).unwrap();
+            create_dir_all(&path).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
+        }
+
+        // the region files the chunks are saved in
+        path.pop();
+        path.push("Regions");
+        if !path.exists() {
+            create_dir_all(&path).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
         }
 
         // chunks created file
@@ -102,7 +107,7 @@ impl FileSystem {
         let mut file: File;
 
         if !path.exists() {
-            file = File::create(&path).unwrap();
+            file = File::create(&path).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
 
             // now write the headings to the file
             let mut data: String = String::new();
@@ -116,7 +121,7 @@ impl FileSystem {
             data.push_str(&format!("Created Chunks:\n",));
 
             // Write data to the file
-            file.write_all(data.as_bytes()).unwrap()
+            file.write_all(data.as_bytes()).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
         }
 
         // create a world info file
@@ -125,12 +130,12 @@ impl FileSystem {
 
         // if the file doesnt exist make it
         if !path.exists() {
-            file = File::create(&path).unwrap();
+            file = File::create(&path).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
 
             // now write the headings to the file
             let mut data: String = String::new();
             data.push_str("Important Info Goes Here: \n");
-            file.write_all(data.as_bytes()).unwrap()
+            file.write_all(data.as_bytes()).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
         }
 
         // create a stats info file
@@ -139,7 +144,7 @@ impl FileSystem {
 
         // if the file doesnt exist make it
         if !path.exists() {
-            file = File::create(&path).unwrap();
+            file = File::create(&path).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
 
             // now write the headings to the file
             let mut data: String = String::new();
@@ -153,67 +158,328 @@ impl FileSystem {
             data.push_str("MobsKilled: 0\n");
 
             // Write data to the file
-            file.write_all(data.as_bytes()).unwrap()
+            file.write_all(data.as_bytes()).map_err(|e| GameError::SaveFailed(path.clone(), e))?;
         }
+
+        Ok(())
     }
 
     // Once a chunk has been loaded and is in play, and then goes out of range it is unloaded and saved back to a file
-    // the chunk is not borrowed here so after this call it goes out of scope and is dropped
-    pub fn save_chunk_to_file(&mut self, chunk: Chunk, chunk_sizes: (usize, usize, usize)) {
-        // save the chunk to a file then free it
-        //println!("Saving Chunk to File: ({}, {})", chunk.chunk_id_x, chunk.chunk_id_z);
+    // the chunk is only borrowed here so if it cant be saved the world can keep it and try again
+    pub fn save_chunk_to_file(&mut self, chunk: &Chunk, chunk_sizes: (usize, usize, usize)) -> Result<(), GameError> {
+        // compress the chunk and save it into the region file it is in
+        let chunk_id: (i32, i32) = (chunk.chunk_id_x, chunk.chunk_id_z);
+        let block_types: Vec<BlockType> = get_chunk_block_types(chunk, chunk_sizes);
+
+        let region_path: PathBuf = self.get_region_path(get_region_id(chunk_id));
+        if let Some(region_directory) = region_path.parent() {
+            create_dir_all(region_directory).map_err(|e| GameError::SaveFailed(region_directory.to_path_buf(), e))?;
+        }
+
+        let mut region_file: RegionFile = RegionFile::open(&region_path, chunk_sizes)
+            .map_err(|e| GameError::SaveFailed(region_path.clone(), e))?;
+        region_file
+            .write_chunk(chunk_id, &encode_chunk_blocks(&block_types, chunk_sizes))
+            .map_err(|e| GameError::SaveFailed(region_path.clone(), e))
+    }
+
+    /*
+    the old way chunks were saved, one x_z.txt file for each chunk with every block id written out as text
+    worlds saved like this can still be read, and it is kept so the region files can be checked against it
+    */
+    pub fn save_chunk_to_text_file(&mut self, chunk: &Chunk, chunk_sizes: (usize, usize, usize)) -> Result<(), GameError> {
         let mut file_path: PathBuf = self.my_world_directory.clone();
         file_path.push("Chunks");
         file_path.push(format!("{}_{}.txt", chunk.chunk_id_x, chunk.chunk_id_z));
 
-        // first create the file, and overwrite it if it already exists
-        let mut file: File = File::create(file_path).unwrap();
-
-        /*
-        now write the chunk data to the file
-        since the data is stored in a hashmap it is in a random order
-        i will make a vector, load all of the hashmap into the vector into its correct positions,
-        then i can go through the vector in the correct order and write the blocks to a file
-        this way there is an order to the blocks in the file so i dont have to store there exact position as well for each block
-        so it takes less space to store them.
-        */
-
-        // make the 3d vector
-        let mut temp_chunk_vec: Vec<Vec<Vec<BlockType>>> =
-            vec![vec![vec![BlockType::Air; chunk_sizes.0]; chunk_sizes.1]; chunk_sizes.2];
-
-        // now go through the hashmap
-        for (key, block) in chunk.chunk_blocks.iter() {
-            // get the position of the block relative to the chunk
-            let chunk_relative_x: usize = key.0.rem_euclid(chunk_sizes.0 as i32) as usize;
-            let chunk_relative_y: usize = (key.1 + (chunk_sizes.1 as i16 / 2)) as usize;
-            let chunk_relative_z: usize = key.2.rem_euclid(chunk_sizes.2 as i32) as usize;
-
-            // put this block into the temp vector, but just its block type
-            temp_chunk_vec[chunk_relative_x][chunk_relative_y][chunk_relative_z] = block.block_type;
-        }
-
-        /*
-        now write the blocks to the file, starting at 0,0,0, and increasing in x, then y, then z
-        once x is max make a new line and increase z, once z is max make two new lines and increase y
-        */
+        let block_types: Vec<BlockType> = get_chunk_block_types(chunk, chunk_sizes);
+        let data: String = encode_chunk_blocks_text(&block_types, chunk_sizes);
+
+        // write the data string to the file, replacing it if it already exists
+        write_file_atomic(&file_path, data.as_bytes()).map_err(|e| GameError::SaveFailed(file_path, e))
+    }
+
+    // the region file a region is saved in
+    pub fn get_region_path(&self, region_id: (i32, i32)) -> PathBuf {
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("Regions");
+        path.push(get_region_file_name(region_id));
+        path
+    }
+
+    // write the worlds seed and generator to WorldInfo.txt so the world can be generated the same way when it is opened again
+    pub fn save_world_info(&mut self, world_seed: u64, generator_type: &WorldGeneratorType) -> Result<(), GameError> {
         let mut data: String = String::new();
-        for y in 0..chunk_sizes.1 as usize {
-            for z in 0..chunk_sizes.2 as usize {
-                for x in 0..chunk_sizes.0 as usize {
-                    data.push_str(&format!("{:?} ", temp_chunk_vec[x][y][z].to_int()));
+        data.push_str(&format!("Format Version: {}\n", WORLD_FORMAT_VERSION));
+        data.push_str(&format!("World Seed: {}\n", world_seed));
+        data.push_str(&format!("Generator: {}\n", generator_type.to_info_string()));
+
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("WorldInfo.txt");
+        write_file_atomic(&path, data.as_bytes()).map_err(|e| GameError::SaveFailed(path, e))
+    }
+
+    /*
+    read the seed and generator from WorldInfo.txt, none if there is no file yet or it is from before they were saved
+    a line that is there but cant be read is an error, so the world isnt opened (and saved over) with the wrong settings
+    */
+    pub fn read_world_info(&mut self) -> Result<Option<(u64, WorldGeneratorType)>, GameError> {
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("WorldInfo.txt");
+
+        let file: File = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(GameError::Io(e)),
+        };
+        let reader: io::BufReader<File> = io::BufReader::new(file);
+
+        let mut world_seed: Option<u64> = None;
+        let mut generator_type: Option<WorldGeneratorType> = None;
+        let mut line_count: usize = 0;
+        for (line_index, line) in reader.lines().enumerate() {
+            let line: String = line?;
+            let line_number: usize = line_index + 1;
+            line_count = line_number;
+
+            if let Some(seed) = line.strip_prefix("World Seed:") {
+                world_seed = Some(parse_line_value(seed.trim(), &path, line_number)?);
+            } else if let Some(generator) = line.strip_prefix("Generator:") {
+                generator_type = Some(WorldGeneratorType::from_info_string(generator.trim()).ok_or_else(|| {
+                    GameError::MalformedFile(path.clone(), line_number, format!("{:?} is not a world generator", generator.trim()))
+                })?);
+            }
+        }
+
+        // they are always saved together, so only having one of them means the file was changed or cut short
+        match (world_seed, generator_type) {
+            (Some(world_seed), Some(generator_type)) => Ok(Some((world_seed, generator_type))),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(GameError::MalformedFile(path, line_count + 1, String::from("there is a world seed but no generator"))),
+            (None, Some(_)) => Err(GameError::MalformedFile(path, line_count + 1, String::from("there is a generator but no world seed"))),
+        }
+    }
+
+    // if any chunks have been created in this world, from the count at the top of ChunksCreated.txt
+    pub fn has_created_chunks(&self) -> bool {
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("ChunksCreated.txt");
+
+        let first_line: Option<String> = fs::read_to_string(path).ok().and_then(|data| data.lines().next().map(String::from));
+        let total_created_chunks: usize = first_line
+            .and_then(|line| line.split_whitespace().last()?.parse::<usize>().ok())
+            .unwrap_or(0);
+        total_created_chunks > 0
+    }
+
+    // the format version from WorldInfo.txt, none for worlds saved before there were versions, an error if the line is there but cant be read
+    pub fn read_world_format_version(&mut self) -> Result<Option<u32>, GameError> {
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("WorldInfo.txt");
+
+        let file: File = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(GameError::Io(e)),
+        };
+        let reader: io::BufReader<File> = io::BufReader::new(file);
+        for (line_index, line) in reader.lines().enumerate() {
+            if let Some(version) = line?.strip_prefix("Format Version:") {
+                return Ok(Some(parse_line_value(version.trim(), &path, line_index + 1)?));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // set the format version in WorldInfo.txt, keeping everything else in the file
+    pub fn save_world_format_version(&mut self, version: u32) -> io::Result<()> {
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("WorldInfo.txt");
+
+        let mut data: String = format!("Format Version: {}\n", version);
+        if let Ok(old_data) = fs::read_to_string(&path) {
+            for line in old_data.lines() {
+                if !line.starts_with("Format Version:") {
+                    data.push_str(line);
+                    data.push('\n');
                 }
-                data.push_str("\n");
             }
-            data.push_str("\n");
         }
 
-        // now write the data string to the file
-        file.write_all(data.as_bytes()).unwrap();
+        write_file_atomic(&path, data.as_bytes())
+    }
+
+    // the chunks that are still saved as x_z.txt text files
+    pub fn get_text_chunk_ids(&self) -> io::Result<Vec<(i32, i32)>> {
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("Chunks");
+
+        let mut chunk_ids: Vec<(i32, i32)> = Vec::new();
+        if !path.exists() {
+            return Ok(chunk_ids);
+        }
+
+        for entry in fs::read_dir(path)? {
+            let file_name: String = entry?.file_name().to_string_lossy().into_owned();
+            let chunk_id: Option<(i32, i32)> = file_name
+                .strip_suffix(".txt")
+                .and_then(|name| name.split_once('_'))
+                .and_then(|(x, z)| Some((x.parse::<i32>().ok()?, z.parse::<i32>().ok()?)));
+
+            if let Some(chunk_id) = chunk_id {
+                chunk_ids.push(chunk_id);
+            }
+        }
+
+        // sorted so the chunks are always done in the same order
+        chunk_ids.sort();
+        Ok(chunk_ids)
+    }
+
+    // the regions that have a region file
+    pub fn get_region_ids(&self) -> io::Result<Vec<(i32, i32)>> {
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("Regions");
+
+        let mut region_ids: Vec<(i32, i32)> = Vec::new();
+        if !path.exists() {
+            return Ok(region_ids);
+        }
+
+        for entry in fs::read_dir(path)? {
+            let file_name: String = entry?.file_name().to_string_lossy().into_owned();
+            let region_id: Option<(i32, i32)> = file_name
+                .strip_prefix("r_")
+                .and_then(|name| name.strip_suffix(".region"))
+                .and_then(|name| name.split_once('_'))
+                .and_then(|(x, z)| Some((x.parse::<i32>().ok()?, z.parse::<i32>().ok()?)));
+
+            if let Some(region_id) = region_id {
+                region_ids.push(region_id);
+            }
+        }
+
+        region_ids.sort();
+        Ok(region_ids)
+    }
+
+    /*
+    save the block writes that are still waiting for their chunks to PendingBlockWrites.txt
+    each line is one block, "x y z blockid" in world positions, the chunk it belongs to is worked out again when it is read
+    */
+    pub fn save_pending_block_writes(&mut self, pending_block_writes: &PendingBlockWrites) -> Result<(), GameError> {
+        let mut data: String = String::new();
+        for block_writes in pending_block_writes.writes.values() {
+            for (block_pos, block_type) in block_writes.iter() {
+                data.push_str(&format!("{} {} {} {}\n", block_pos.0, block_pos.1, block_pos.2, block_type.to_int()));
+            }
+        }
+
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("PendingBlockWrites.txt");
+        write_file_atomic(&path, data.as_bytes()).map_err(|e| GameError::SaveFailed(path, e))
+    }
+
+    // read the pending block writes back, if there is no file yet there are no writes
+    pub fn read_pending_block_writes(&mut self, chunk_sizes: (usize, usize, usize)) -> Result<PendingBlockWrites, GameError> {
+        let mut pending_block_writes: PendingBlockWrites = PendingBlockWrites::new();
+
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("PendingBlockWrites.txt");
+
+        let file: File = match File::open(&path) {
+            Ok(file) => file,
+            Err(_) => return Ok(pending_block_writes),
+        };
+        let reader: io::BufReader<File> = io::BufReader::new(file);
+
+        for (line_index, line) in reader.lines().enumerate() {
+            let line: String = line?;
+            let values: Vec<&str> = line.split_whitespace().collect();
+            if values.len() != 4 {
+                continue;
+            }
+
+            let line_number: usize = line_index + 1;
+            let block_pos: (i32, i16, i32) = (
+                parse_line_value(values[0], &path, line_number)?,
+                parse_line_value(values[1], &path, line_number)?,
+                parse_line_value(values[2], &path, line_number)?,
+            );
+            let block_type: BlockType = BlockType::from_int(parse_line_value(values[3], &path, line_number)?)
+                .map_err(|e| GameError::MalformedFile(path.clone(), line_number, e.to_string()))?;
+
+            pending_block_writes.add_block_write(block_pos, block_type, chunk_sizes);
+        }
+
+        Ok(pending_block_writes)
+    }
+
+    /*
+    save the block ticks that havent run yet to ScheduledTicks.txt
+    the first line is the current tick, then each line is one tick, "tick x y z ticktype"
+    ticks waiting for their chunk to load are saved as due now, they go back to waiting if the chunk still isnt loaded
+    */
+    pub fn save_scheduled_ticks(&mut self, tick_scheduler: &TickScheduler) -> Result<(), GameError> {
+        let mut data: String = String::new();
+        data.push_str(&format!("Current Tick: {}\n", tick_scheduler.current_tick));
+        for (tick, (block_pos, tick_type)) in tick_scheduler.get_all_ticks() {
+            data.push_str(&format!("{} {} {} {} {}\n", tick, block_pos.0, block_pos.1, block_pos.2, tick_type.to_int()));
+        }
+
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("ScheduledTicks.txt");
+        write_file_atomic(&path, data.as_bytes()).map_err(|e| GameError::SaveFailed(path, e))
+    }
+
+    // read the scheduled ticks back, if there is no file yet the world starts at tick 0 with nothing scheduled
+    pub fn read_scheduled_ticks(&mut self) -> Result<TickScheduler, GameError> {
+        let mut tick_scheduler: TickScheduler = TickScheduler::new();
+
+        let mut path: PathBuf = self.my_world_directory.clone();
+        path.push("ScheduledTicks.txt");
+
+        let file: File = match File::open(&path) {
+            Ok(file) => file,
+            Err(_) => return Ok(tick_scheduler),
+        };
+        let reader: io::BufReader<File> = io::BufReader::new(file);
+
+        for (line_index, line) in reader.lines().enumerate() {
+            let line: String = line?;
+            let line_number: usize = line_index + 1;
+
+            if let Some(current_tick) = line.strip_prefix("Current Tick:") {
+                tick_scheduler.current_tick = parse_line_value(current_tick.trim(), &path, line_number)?;
+                continue;
+            }
+
+            let values: Vec<&str> = line.split_whitespace().collect();
+            if values.len() != 5 {
+                continue;
+            }
+
+            let tick: u64 = parse_line_value(values[0], &path, line_number)?;
+            let block_pos: (i32, i16, i32) = (
+                parse_line_value(values[1], &path, line_number)?,
+                parse_line_value(values[2], &path, line_number)?,
+                parse_line_value(values[3], &path, line_number)?,
+            );
+            let tick_type: TickType = match TickType::from_int(parse_line_value(values[4], &path, line_number)?) {
+                Some(tick_type) => tick_type,
+                None => continue,
+            };
+
+            // the current tick is always the first line so the delay is never negative
+            tick_scheduler.schedule_tick(block_pos, tick_type, tick.saturating_sub(tick_scheduler.current_tick));
+        }
+
+        Ok(tick_scheduler)
     }
 
     // save the created chunks file
-    pub fn save_created_chunks_file(&mut self, chunk_sizes: (usize, usize, usize), created_chunks: &mut HashSet<(i32, i32)>) {
+    pub fn save_created_chunks_file(&mut self, chunk_sizes: (usize, usize, usize), created_chunks: &HashSet<(i32, i32)>) -> Result<(), GameError> {
         let mut data: String = String::new();
 
         // write the header lines
@@ -226,28 +492,59 @@ impl FileSystem {
             chunk_sizes.0, chunk_sizes.1, chunk_sizes.2
         ));
         data.push_str("Created Chunks: \n");
-        for key in created_chunks.drain() {
+        for key in created_chunks.iter() {
             data.push_str(&format!("{} {}\n", key.0, key.1));
         }
 
-        // open the file
+        // now write the data string to the file
         let mut path: PathBuf = self.my_world_directory.clone();
         path.push("ChunksCreated.txt");
-        let mut file: File = File::create(path).unwrap();
-
-        // now write the data string to the file
-        file.write_all(data.as_bytes()).unwrap();
+        write_file_atomic(&path, data.as_bytes()).map_err(|e| GameError::SaveFailed(path, e))
     }
 
+    /*
+    read the chunk from its region file, or its text file if it was saved before there were region files
+    an error if it isnt saved anywhere or what is saved cant be read, the temp vector may have some of the blocks in it then
+    */
     pub fn read_chunks_from_file(
         &mut self,
         temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
         chunk_id_x: i32,
         chunk_id_z: i32,
         chunk_sizes: (usize, usize, usize),
-    ) {
+    ) -> Result<(), GameError> {
+        let chunk_id: (i32, i32) = (chunk_id_x, chunk_id_z);
+        let region_path: PathBuf = self.get_region_path(get_region_id(chunk_id));
+
+        // only open it if it is there, opening it would make an empty one
+        if region_path.exists() {
+            let mut region_file: RegionFile = RegionFile::open(&region_path, chunk_sizes)
+                .map_err(|e| get_chunk_read_error(chunk_id, &region_path, e))?;
+            let data: Option<Vec<u8>> = region_file
+                .read_chunk(chunk_id)
+                .map_err(|e| get_chunk_read_error(chunk_id, &region_path, e))?;
+
+            if let Some(data) = data {
+                let block_types: Vec<BlockType> = decode_chunk_blocks(&data, chunk_sizes)
+                    .map_err(|e| get_chunk_read_error(chunk_id, &region_path, e))?;
+                set_chunk_block_types(temp_chunk_vec, &block_types, chunk_sizes);
+                return Ok(());
+            }
+        }
+
+        self.read_chunk_from_text_file(temp_chunk_vec, chunk_id_x, chunk_id_z, chunk_sizes)
+    }
+
+    pub fn read_chunk_from_text_file(
+        &mut self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_id_x: i32,
+        chunk_id_z: i32,
+        chunk_sizes: (usize, usize, usize),
+    ) -> Result<(), GameError> {
         // read the chunk from a file and fill the temp vector with the data
         //println!("Reading Chunk from File: ({}, {})", chunk_id_x, chunk_id_z);
+        let chunk_id: (i32, i32) = (chunk_id_x, chunk_id_z);
 
         // get the file path
         let mut file_path: PathBuf = self.my_world_directory.clone();
@@ -256,14 +553,11 @@ impl FileSystem {
 
         //check that this file exists
         if !file_path.exists() {
-            panic!(
-                "Trying to read chunk file that does not exist: {:?}",
-                file_path
-            );
+            return Err(GameError::ChunkFileMissing(chunk_id, file_path));
         }
 
         // open the file
-        let file: File = File::open(file_path).unwrap();
+        let file: File = File::open(&file_path)?;
 
         // read the file line by line
         let reader: io::BufReader<File> = io::BufReader::new(file);
@@ -275,8 +569,9 @@ impl FileSystem {
         let mut z: usize = 0;
         let mut new_line: String;
         let mut skip_line: bool = false;
-        for line in lines {
-            new_line = line.unwrap();
+        for (line_index, line) in lines.enumerate() {
+            // a line that isnt utf8 is corrupted
+            new_line = line.map_err(|e| get_chunk_read_error(chunk_id, &file_path, e))?;
 
             // so i can skip empty lines
             if skip_line {
@@ -286,10 +581,26 @@ impl FileSystem {
 
             x = 0;
             for block in new_line.split_whitespace() {
-                temp_chunk_vec[x][y][z].block_type =
-                    BlockType::from_int(block.parse::<u16>().unwrap());
+                if x == chunk_sizes.0 {
+                    return Err(GameError::CorruptedChunk(chunk_id, file_path, format!("line {} has too many blocks", line_index + 1)));
+                }
+
+                let block_type: Option<BlockType> = block.parse::<u16>().ok().and_then(|id| BlockType::from_int(id).ok());
+                temp_chunk_vec[x][y][z].block_type = match block_type {
+                    Some(block_type) => block_type,
+                    None => {
+                        return Err(GameError::CorruptedChunk(
+                            chunk_id,
+                            file_path,
+                            format!("{:?} on line {} is not a block id", block, line_index + 1),
+                        ))
+                    }
+                };
                 x += 1;
             }
+            if x != chunk_sizes.0 {
+                return Err(GameError::CorruptedChunk(chunk_id, file_path, format!("line {} only has {} blocks", line_index + 1, x)));
+            }
             z += 1;
 
             // if ive read the whole layer increase y
@@ -306,5 +617,188 @@ impl FileSystem {
                 }
             }
         }
+
+        // the file ended before every layer was read
+        if y != chunk_sizes.1 {
+            return Err(GameError::CorruptedChunk(chunk_id, file_path, format!("it only has {} of {} layers", y, chunk_sizes.1)));
+        }
+
+        Ok(())
+    }
+
+    /*
+    move a chunk that cant be read into Quarantine/ so it isnt read again, and it can be looked at or put back by hand
+    a chunk in a region is copied out of it (as the bytes that are there) and removed from the region
+    if the region file is too broken to open the whole file is moved, its other chunks will be generated again as well
+    gives where it was moved to, none if there wasnt anything saved for the chunk
+    */
+    pub fn quarantine_chunk(&mut self, chunk_id: (i32, i32), chunk_sizes: (usize, usize, usize)) -> Result<Option<PathBuf>, GameError> {
+        let mut quarantine_directory: PathBuf = self.my_world_directory.clone();
+        quarantine_directory.push("Quarantine");
+        create_dir_all(&quarantine_directory)?;
+
+        let region_id: (i32, i32) = get_region_id(chunk_id);
+        let region_path: PathBuf = self.get_region_path(region_id);
+        if region_path.exists() {
+            match RegionFile::open(&region_path, chunk_sizes) {
+                Ok(mut region_file) => {
+                    if let Some(data) = region_file.read_chunk_unchecked(chunk_id)? {
+                        let quarantine_path: PathBuf = quarantine_directory.join(format!("{}_{}.chunk", chunk_id.0, chunk_id.1));
+                        write_file_atomic(&quarantine_path, &data)?;
+                        region_file.remove_chunk(chunk_id)?;
+                        return Ok(Some(quarantine_path));
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    let quarantine_path: PathBuf = quarantine_directory.join(get_region_file_name(region_id));
+                    fs::rename(&region_path, &quarantine_path)?;
+                    return Ok(Some(quarantine_path));
+                }
+                Err(e) => return Err(GameError::Io(e)),
+            }
+        }
+
+        let mut text_path: PathBuf = self.my_world_directory.clone();
+        text_path.push("Chunks");
+        text_path.push(format!("{}_{}.txt", chunk_id.0, chunk_id.1));
+        if text_path.exists() {
+            let quarantine_path: PathBuf = quarantine_directory.join(format!("{}_{}.txt", chunk_id.0, chunk_id.1));
+            fs::rename(&text_path, &quarantine_path)?;
+            return Ok(Some(quarantine_path));
+        }
+
+        Ok(None)
+    }
+
+    /*
+    every chunk that has been saved, in a text file or a region file
+    used to work out the created chunks again if ChunksCreated.txt cant be read, region files that cant be opened are skipped
+    */
+    pub fn get_saved_chunk_ids(&self, chunk_sizes: (usize, usize, usize)) -> Result<Vec<(i32, i32)>, GameError> {
+        let mut chunk_ids: Vec<(i32, i32)> = self.get_text_chunk_ids()?;
+
+        for region_id in self.get_region_ids()? {
+            let region_path: PathBuf = self.get_region_path(region_id);
+            match RegionFile::open(&region_path, chunk_sizes) {
+                Ok(region_file) => chunk_ids.extend(region_file.get_chunk_ids(region_id)),
+                Err(e) => eprintln!("Skipping region file {:?} that can not be opened: {}", region_path, e),
+            }
+        }
+
+        chunk_ids.sort();
+        chunk_ids.dedup();
+        Ok(chunk_ids)
+    }
+}
+
+// a chunk that has bad bytes is corrupted, anything else (like not being allowed to read the file) is just an io error
+fn get_chunk_read_error(chunk_id: (i32, i32), path: &Path, e: io::Error) -> GameError {
+    match e.kind() {
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => GameError::CorruptedChunk(chunk_id, path.to_path_buf(), e.to_string()),
+        io::ErrorKind::Unsupported => GameError::NewerChunkVersion(chunk_id, path.to_path_buf(), e.to_string()),
+        _ => GameError::Io(e),
+    }
+}
+
+// parse one value from a line of a world file, with the file and line in the error if it isnt a number
+pub fn parse_line_value<T: FromStr>(value: &str, path: &Path, line_number: usize) -> Result<T, GameError> {
+    value
+        .parse::<T>()
+        .map_err(|_| GameError::MalformedFile(path.to_path_buf(), line_number, format!("{:?} is not a number", value)))
+}
+
+/*
+write a whole file so it is either all the old data or all the new data, even if the game crashes or the power goes half way through
+the data goes into <name>.tmp first and is synced to the disk, then the temp file is renamed over the real one (renaming is atomic)
+and the folder is synced so the rename is on the disk too
+*/
+pub fn write_file_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut temp_path: PathBuf = path.to_path_buf();
+    let mut temp_name: std::ffi::OsString = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    temp_path.set_file_name(temp_name);
+
+    let mut file: File = File::create(&temp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&temp_path, path)?;
+
+    // some systems (like windows) cant open a folder to sync it, the rename has still happened so this isnt an error
+    if let Some(parent) = path.parent() {
+        if let Ok(directory) = File::open(parent) {
+            let _ = directory.sync_all();
+        }
+    }
+
+    Ok(())
+}
+
+// where a block is in a chunks list of block types, x then z then y (the same order they are saved in)
+pub fn get_chunk_block_index(position: (usize, usize, usize), chunk_sizes: (usize, usize, usize)) -> usize {
+    position.0 + position.2 * chunk_sizes.0 + position.1 * chunk_sizes.0 * chunk_sizes.2
+}
+
+/*
+since the blocks are stored in a hashmap they are in a random order
+so they are put into a list in the correct positions, with air where there isnt a block
+this way there is an order to the blocks when they are saved so i dont have to store there exact position as well
+*/
+pub fn get_chunk_block_types(chunk: &Chunk, chunk_sizes: (usize, usize, usize)) -> Vec<BlockType> {
+    let mut block_types: Vec<BlockType> = vec![BlockType::Air; chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2];
+
+    for (key, block) in chunk.chunk_blocks.iter() {
+        // get the position of the block relative to the chunk
+        let chunk_relative_x: usize = key.0.rem_euclid(chunk_sizes.0 as i32) as usize;
+        let chunk_relative_y: usize = (key.1 + (chunk_sizes.1 as i16 / 2)) as usize;
+        let chunk_relative_z: usize = key.2.rem_euclid(chunk_sizes.2 as i32) as usize;
+
+        block_types[get_chunk_block_index((chunk_relative_x, chunk_relative_y, chunk_relative_z), chunk_sizes)] = block.block_type;
+    }
+
+    block_types
+}
+
+// put a list of block types back into the temp chunk vector
+pub fn set_chunk_block_types(temp_chunk_vec: &mut [Vec<Vec<Block>>], block_types: &[BlockType], chunk_sizes: (usize, usize, usize)) {
+    for (x, blocks_x) in temp_chunk_vec.iter_mut().enumerate() {
+        for (y, blocks_y) in blocks_x.iter_mut().enumerate() {
+            for (z, block) in blocks_y.iter_mut().enumerate() {
+                block.block_type = block_types[get_chunk_block_index((x, y, z), chunk_sizes)];
+            }
+        }
+    }
+}
+
+// the blocks in a temp chunk vector as a list of block types, in the order they are saved in
+pub fn get_temp_chunk_block_types(temp_chunk_vec: &[Vec<Vec<Block>>], chunk_sizes: (usize, usize, usize)) -> Vec<BlockType> {
+    let mut block_types: Vec<BlockType> = vec![BlockType::Air; chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2];
+    for (x, blocks_x) in temp_chunk_vec.iter().enumerate() {
+        for (y, blocks_y) in blocks_x.iter().enumerate() {
+            for (z, block) in blocks_y.iter().enumerate() {
+                block_types[get_chunk_block_index((x, y, z), chunk_sizes)] = block.block_type;
+            }
+        }
+    }
+    block_types
+}
+
+/*
+the blocks written out as text, starting at 0,0,0, and increasing in x, then z, then y
+once x is max make a new line and increase z, once z is max make two new lines and increase y
+this way there is an order to the blocks in the file so i dont have to store there exact position as well for each block
+*/
+pub fn encode_chunk_blocks_text(block_types: &[BlockType], chunk_sizes: (usize, usize, usize)) -> String {
+    let mut data: String = String::new();
+    for y in 0..chunk_sizes.1 {
+        for z in 0..chunk_sizes.2 {
+            for x in 0..chunk_sizes.0 {
+                data.push_str(&format!("{:?} ", block_types[get_chunk_block_index((x, y, z), chunk_sizes)].to_int()));
+            }
+            data.push('\n');
+        }
+        data.push('\n');
     }
+    data
 }
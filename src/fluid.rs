@@ -0,0 +1,120 @@
+/*
+The water flow simulation
+it runs in ticks, each fluid tick every block that might change looks at the blocks around it and works out what it should be next
+all of the new blocks are worked out from how the world was at the start of the tick, then written all at once
+so the order the blocks are checked in never matters and the same world always flows the same way
+
+source blocks (BlockType::Water) never change, flowing water has a level from 7 (next to a source) down to 1
+water falls down into air first, and only spreads out sideways when it is sitting on something it cant flow into
+each block it spreads sideways the level goes down by one, and water falling down is always level 7
+flowing water with nothing feeding it dries up back into air
+
+this only reads blocks through the get block function passed in, so it can be tested without any chunks or a gpu
+*/
+
+use crate::{block_type::*, chunk::decorate_chunks::BlockWrite, world::World};
+
+use std::collections::HashSet;
+
+// the level of flowing water right next to a source, or falling down
+pub const MAX_FLOW_LEVEL: u8 = 7;
+
+// how many world ticks water waits before flowing another block
+pub const FLUID_TICK_DELAY: u64 = 5;
+
+// the level of water a block has, sources are one higher than any flowing water, none if it isnt water
+pub fn get_fluid_level(block_type: BlockType) -> Option<u8> {
+    match block_type {
+        BlockType::Water => Some(MAX_FLOW_LEVEL + 1),
+        BlockType::FlowingWater(level) => Some(level),
+        _ => None,
+    }
+}
+
+// water can only flow into air or other flowing water, never sources or solid blocks
+pub fn can_fluid_replace(block_type: BlockType) -> bool {
+    matches!(block_type, BlockType::Air | BlockType::FlowingWater(_))
+}
+
+/*
+what the block at this position should be after this tick, none if it isnt something water can change
+get block returns none for blocks that cant be read (unloaded chunks or outside of the world) which water treats as solid
+*/
+pub fn get_next_fluid_state<F>(block_pos: (i32, i16, i32), get_block: &F) -> Option<BlockType>
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    let current_block_type: BlockType = get_block(block_pos)?;
+    if !can_fluid_replace(current_block_type) {
+        return None;
+    }
+
+    let (x, y, z) = block_pos;
+
+    // water above always falls into this block
+    let above: Option<BlockType> = get_block((x, y + 1, z));
+    if above.is_some_and(|block_type| block_type.is_fluid()) {
+        return Some(BlockType::FlowingWater(MAX_FLOW_LEVEL));
+    }
+
+    // otherwise the highest level flowing in from the sides, one less than the block it comes from
+    let mut new_level: u8 = 0;
+    for neighbour_pos in [(x + 1, y, z), (x - 1, y, z), (x, y, z + 1), (x, y, z - 1)] {
+        let neighbour_level: u8 = match get_block(neighbour_pos).and_then(get_fluid_level) {
+            Some(level) => level,
+            None => continue,
+        };
+
+        // water only spreads sideways when it cant fall
+        let below_neighbour: Option<BlockType> = get_block((neighbour_pos.0, neighbour_pos.1 - 1, neighbour_pos.2));
+        if below_neighbour.is_some_and(can_fluid_replace) {
+            continue;
+        }
+
+        // sources are level 8 so the water next to them is 7
+        let spread_level: u8 = neighbour_level - 1;
+        new_level = new_level.max(spread_level);
+    }
+
+    if new_level == 0 {
+        Some(BlockType::Air)
+    } else {
+        Some(BlockType::FlowingWater(new_level))
+    }
+}
+
+/*
+work out one tick of the simulation for these positions
+returns the blocks that change, sorted by position so they are always applied in the same order
+*/
+pub fn simulate_fluid_tick<F>(positions: &HashSet<(i32, i16, i32)>, get_block: F) -> Vec<BlockWrite>
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    let mut block_writes: Vec<BlockWrite> = Vec::new();
+
+    for block_pos in positions.iter() {
+        if let Some(next_block_type) = get_next_fluid_state(*block_pos, &get_block) {
+            if get_block(*block_pos) != Some(next_block_type) {
+                block_writes.push((*block_pos, next_block_type));
+            }
+        }
+    }
+
+    block_writes.sort_by_key(|(block_pos, _)| *block_pos);
+    block_writes
+}
+
+// the positions that need checking next tick after these blocks have changed, each changed block and everything touching it
+pub fn get_positions_to_update(block_writes: &[BlockWrite]) -> HashSet<(i32, i16, i32)> {
+    let mut positions: HashSet<(i32, i16, i32)> = HashSet::new();
+
+    for (block_pos, _) in block_writes.iter() {
+        positions.insert(*block_pos);
+        for neighbour in World::get_neighbour_positions(*block_pos) {
+            positions.insert(neighbour);
+        }
+    }
+
+    positions
+}
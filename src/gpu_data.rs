@@ -17,28 +17,53 @@ pub struct GPUData {
 
 impl GPUData {
     pub fn new(renderer: &Renderer) -> GPUData {
-        // cube vertices (assume starts at (0,0,0) which is not the bottom front right to align with the world coords)
+        /*
+        cube vertices (assume starts at (0,0,0) which is not the bottom front right to align with the world coords)
+        each face has its own 4 vertices so the vertex shader knows which face a vertex is part of
+        each vertex is x, y, z, face. the face is its index in BlockFace::ALL, so it can be checked against the instances visible faces
+        */
         let cube_vertices: Vec<f32> = vec![
-            0.0, 0.0, 0.0, // 0 Bottom Front Right
-            1.0, 0.0, 0.0, // 1 Bottom Front Left
-            1.0, 0.0, 1.0, // 2 Bottom Back Left
-            0.0, 0.0, 1.0, // 3 Bottom Back Right
-            0.0, 1.0, 0.0, // 4 Top Front Right
-            1.0, 1.0, 0.0, // 5 Top Front Left
-            1.0, 1.0, 1.0, // 6 Top Back Left
-            0.0, 1.0, 1.0, // 7 Top Back Right
+            // Top face
+            0.0, 1.0, 0.0, 0.0,
+            1.0, 1.0, 0.0, 0.0,
+            0.0, 1.0, 1.0, 0.0,
+            1.0, 1.0, 1.0, 0.0,
+            // Bottom face
+            0.0, 0.0, 1.0, 1.0,
+            1.0, 0.0, 1.0, 1.0,
+            0.0, 0.0, 0.0, 1.0,
+            1.0, 0.0, 0.0, 1.0,
+            // Right face
+            1.0, 0.0, 0.0, 2.0,
+            1.0, 0.0, 1.0, 2.0,
+            1.0, 1.0, 0.0, 2.0,
+            1.0, 1.0, 1.0, 2.0,
+            // Left face
+            0.0, 0.0, 1.0, 3.0,
+            0.0, 0.0, 0.0, 3.0,
+            0.0, 1.0, 1.0, 3.0,
+            0.0, 1.0, 0.0, 3.0,
+            // Back face
+            0.0, 0.0, 1.0, 4.0,
+            0.0, 1.0, 1.0, 4.0,
+            1.0, 0.0, 1.0, 4.0,
+            1.0, 1.0, 1.0, 4.0,
+            // Front face
+            0.0, 0.0, 0.0, 5.0,
+            1.0, 0.0, 0.0, 5.0,
+            0.0, 1.0, 0.0, 5.0,
+            1.0, 1.0, 0.0, 5.0,
         ];
 
         // this is the indexes into the cubeVertices array, so it knows what vertices to use for what triangles
-        // they are clockwise so the triangles are facing the right way
+        // they are clockwise so the triangles are facing the right way, every face uses its 4 vertices the same way
         let cube_indices: Vec<u16> = vec![
-            // Front face
-            0, 1, 4, 5, 4, 1, // Back face
-            3, 7, 2, 6, 2, 7, // Bottom face
-            3, 2, 0, 1, 0, 2, // Top face
-            4, 5, 7, 6, 7, 5, // Left face
-            1, 2, 5, 6, 5, 2, // Right face
-            3, 0, 7, 4, 7, 0,
+            0, 1, 2, 3, 2, 1, // Top face
+            4, 5, 6, 7, 6, 5, // Bottom face
+            8, 9, 10, 11, 10, 9, // Right face
+            12, 13, 14, 15, 14, 13, // Left face
+            16, 17, 18, 19, 18, 17, // Back face
+            20, 21, 22, 23, 22, 21, // Front face
         ];
 
         // create the buffers for this data
@@ -0,0 +1,67 @@
+/*
+Grass growing and dying, this runs on random ticks so it happens slowly over time
+when a grass block gets a random tick it either dies back into dirt if there is a solid block on top of it
+or it tries to spread to one random block around it, which only works if that block is dirt with air above it
+
+like the fluid simulation, this only reads blocks through the get block function passed in
+so it can be tested without any chunks or a gpu
+*/
+
+use crate::{block_type::*, chunk::decorate_chunks::BlockWrite, noise::*};
+
+// mixed into the world seed so the grass random numbers arnt related to anything else
+const GRASS_SEED_SALT: u64 = 0x6772_6173_735f_7370;
+
+// grass dies if the block on top of it cant be seen through (unreadable blocks, like above the world, dont count)
+pub fn is_grass_covered<F>(block_pos: (i32, i16, i32), get_block: &F) -> bool
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    get_block((block_pos.0, block_pos.1 + 1, block_pos.2)).is_some_and(|block_type| !block_type.is_transparent())
+}
+
+// grass can only spread onto dirt that has air directly above it
+pub fn can_grass_spread_to<F>(block_pos: (i32, i16, i32), get_block: &F) -> bool
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    get_block(block_pos) == Some(BlockType::Dirt)
+        && get_block((block_pos.0, block_pos.1 + 1, block_pos.2)) == Some(BlockType::Air)
+}
+
+/*
+what happens to the grass block at this position on a random tick
+the block it tries to spread to is picked from the blocks around it (one up or down too, so it can go up and down slopes)
+using the seed, the tick and the position, so the same world always grows the same way
+*/
+pub fn get_grass_random_tick<F>(block_pos: (i32, i16, i32), world_seed: u64, tick: u64, get_block: F) -> Vec<BlockWrite>
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    if get_block(block_pos) != Some(BlockType::Grass) {
+        return Vec::new();
+    }
+
+    if is_grass_covered(block_pos, &get_block) {
+        return vec![(block_pos, BlockType::Dirt)];
+    }
+
+    let mut random: SeededRandom = SeededRandom::new(hash_position(
+        world_seed ^ GRASS_SEED_SALT ^ mix_u64(tick),
+        block_pos.0,
+        block_pos.1 as i32,
+        block_pos.2,
+    ));
+
+    let spread_pos: (i32, i16, i32) = (
+        block_pos.0 + random.next_range(-1, 2),
+        block_pos.1 + random.next_range(-1, 2) as i16,
+        block_pos.2 + random.next_range(-1, 2),
+    );
+
+    if can_grass_spread_to(spread_pos, &get_block) {
+        vec![(spread_pos, BlockType::Grass)]
+    } else {
+        Vec::new()
+    }
+}
@@ -0,0 +1,225 @@
+/*
+Greedy meshing, the other way of drawing chunks instead of one instanced cube per block
+the visible faces of every block in a chunk are put into one mesh, and faces next to each other that point the same way
+and have the same block type and colour are merged into one bigger quad, so a flat floor of grass is just a few quads
+
+this is all done on the cpu so it can be tested and benched without a gpu, the mesh is then sent to the gpu by the chunk
+it uses the visible faces of each block (from the touching air pass) so the walls between chunks work the same way as instancing
+*/
+
+use crate::{block::*, block_type::*};
+
+use bytemuck::{Pod, Zeroable};
+use std::collections::HashMap;
+
+// what the chunk should be drawn with, picked when the game starts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeshingMode {
+    // one cube instance per block touching air (the original way)
+    Instanced,
+    // one mesh per chunk with the faces merged
+    Greedy,
+}
+
+impl MeshingMode {
+    // the meshing mode from the command line, --greedy-meshing turns greedy meshing on, otherwise instancing is used
+    pub fn from_args<I>(args: I) -> MeshingMode
+    where
+        I: IntoIterator<Item = String>,
+    {
+        if args.into_iter().any(|arg| arg == "--greedy-meshing") {
+            MeshingMode::Greedy
+        } else {
+            MeshingMode::Instanced
+        }
+    }
+}
+
+// one vertex of a chunk mesh, its world position and colour
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct MeshVertex {
+    pub position: [f32; 3],
+    pub colour: [f32; 4],
+}
+
+// the vertices and indices of a chunks mesh, every 4 vertices is one quad drawn with 6 indices
+pub struct ChunkMesh {
+    pub vertices: Vec<MeshVertex>,
+    pub indices: Vec<u32>,
+}
+
+impl ChunkMesh {
+    pub fn new() -> ChunkMesh {
+        ChunkMesh {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    pub fn get_quad_count(&self) -> usize {
+        self.vertices.len() / 4
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+}
+
+impl Default for ChunkMesh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// faces can only be merged if they are the same block type and colour (colours compared by their bits)
+type FaceKey = (BlockType, [u32; 4]);
+
+// the faces in each slice of the chunk, (face, slice) -> [(u, v, key)]
+type FaceSlices = HashMap<(usize, usize), Vec<(usize, usize, FaceKey)>>;
+
+/*
+how each face is laid out, (normal axis, u axis, v axis, if the face is on the positive side of the block, the 4 corners)
+the axes are 0 = x, 1 = y, 2 = z, the face is a rectangle along u and v
+the corners are (u, v) of a unit face in the same order as the cube vertices in gpu_data.rs so the triangles face the same way
+*/
+type FaceLayout = (usize, usize, usize, bool, [(u32, u32); 4]);
+
+fn get_face_layout(face: BlockFace) -> FaceLayout {
+    match face {
+        BlockFace::Top => (1, 0, 2, true, [(0, 0), (1, 0), (0, 1), (1, 1)]),
+        BlockFace::Bottom => (1, 0, 2, false, [(0, 1), (1, 1), (0, 0), (1, 0)]),
+        BlockFace::Right => (0, 1, 2, true, [(0, 0), (0, 1), (1, 0), (1, 1)]),
+        BlockFace::Left => (0, 1, 2, false, [(0, 1), (0, 0), (1, 1), (1, 0)]),
+        BlockFace::Back => (2, 0, 1, true, [(0, 0), (0, 1), (1, 0), (1, 1)]),
+        BlockFace::Front => (2, 0, 1, false, [(0, 0), (1, 0), (0, 1), (1, 1)]),
+    }
+}
+
+/*
+build the greedy mesh for a chunk from its blocks, get colour gives the colour of a block (so biome colours can be used)
+only the faces set in each blocks visible faces are added
+*/
+pub fn build_greedy_mesh<F>(
+    chunk_blocks: &HashMap<(i32, i16, i32), Block>,
+    chunk_id: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    get_colour: F,
+) -> ChunkMesh
+where
+    F: Fn(&Block) -> [f32; 4],
+{
+    let sizes: [usize; 3] = [chunk_sizes.0, chunk_sizes.1, chunk_sizes.2];
+    let half_chunk_y: i32 = chunk_sizes.1 as i32 / 2;
+
+    // first sort every visible face into the slice of the chunk it is in, (face, slice) -> [(u, v, key)]
+    // this way only the slices that have faces are looked at
+    let mut slices: FaceSlices = HashMap::new();
+    for (block_pos, block) in chunk_blocks.iter() {
+        if block.visible_faces == 0 {
+            continue;
+        }
+
+        let local: [usize; 3] = [
+            block_pos.0.rem_euclid(chunk_sizes.0 as i32) as usize,
+            (block_pos.1 as i32 + half_chunk_y) as usize,
+            block_pos.2.rem_euclid(chunk_sizes.2 as i32) as usize,
+        ];
+        let colour: [f32; 4] = get_colour(block);
+        let key: FaceKey = (block.block_type, colour.map(|value| value.to_bits()));
+
+        for (face_index, face) in BlockFace::ALL.iter().enumerate() {
+            if block.visible_faces & face.bit() == 0 {
+                continue;
+            }
+
+            let (normal_axis, u_axis, v_axis, _, _) = get_face_layout(*face);
+            slices
+                .entry((face_index, local[normal_axis]))
+                .or_default()
+                .push((local[u_axis], local[v_axis], key));
+        }
+    }
+
+    // sorted so the mesh is always built in the same order
+    let mut slice_keys: Vec<(usize, usize)> = slices.keys().cloned().collect();
+    slice_keys.sort();
+
+    let mut mesh: ChunkMesh = ChunkMesh::new();
+    for (face_index, slice) in slice_keys {
+        let face: BlockFace = BlockFace::ALL[face_index];
+        let (normal_axis, u_axis, v_axis, positive, corners) = get_face_layout(face);
+        let (u_size, v_size) = (sizes[u_axis], sizes[v_axis]);
+
+        // a 2d grid of the faces in this slice
+        let mut mask: Vec<Option<FaceKey>> = vec![None; u_size * v_size];
+        for (u, v, key) in slices[&(face_index, slice)].iter() {
+            mask[u + v * u_size] = Some(*key);
+        }
+
+        for v in 0..v_size {
+            let mut u: usize = 0;
+            while u < u_size {
+                let key: FaceKey = match mask[u + v * u_size] {
+                    Some(key) => key,
+                    None => {
+                        u += 1;
+                        continue;
+                    }
+                };
+
+                // grow the quad along u as far as the faces match
+                let mut width: usize = 1;
+                while u + width < u_size && mask[(u + width) + v * u_size] == Some(key) {
+                    width += 1;
+                }
+
+                // then grow it along v while the whole row matches
+                let mut height: usize = 1;
+                'grow: while v + height < v_size {
+                    for k in 0..width {
+                        if mask[(u + k) + (v + height) * u_size] != Some(key) {
+                            break 'grow;
+                        }
+                    }
+                    height += 1;
+                }
+
+                // these faces are now part of this quad
+                for h in 0..height {
+                    for k in 0..width {
+                        mask[(u + k) + (v + h) * u_size] = None;
+                    }
+                }
+
+                // add the 4 corners of the quad
+                let base_index: u32 = mesh.vertices.len() as u32;
+                let colour: [f32; 4] = key.1.map(f32::from_bits);
+                for (corner_u, corner_v) in corners.iter() {
+                    let mut local: [i32; 3] = [0; 3];
+                    local[normal_axis] = slice as i32 + positive as i32;
+                    local[u_axis] = (u + *corner_u as usize * width) as i32;
+                    local[v_axis] = (v + *corner_v as usize * height) as i32;
+
+                    mesh.vertices.push(MeshVertex {
+                        position: [
+                            (chunk_id.0 * chunk_sizes.0 as i32 + local[0]) as f32,
+                            (local[1] - half_chunk_y) as f32,
+                            (chunk_id.1 * chunk_sizes.2 as i32 + local[2]) as f32,
+                        ],
+                        colour,
+                    });
+                }
+
+                // the same 2 triangles as each face of the cube
+                for index in [0, 1, 2, 3, 2, 1] {
+                    mesh.indices.push(base_index + index);
+                }
+
+                u += width;
+            }
+        }
+    }
+
+    mesh
+}
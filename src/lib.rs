@@ -19,17 +19,33 @@
 // create any modules i need
 pub mod optimisations; // where i put functions i am benching to improve them
 
+pub mod biome; // big areas of the world with their own terrain, surface blocks and colours
 pub mod block; // where i create my basic objects like spheres and squares
 pub mod block_type; // seperating the block type from the block struct
+pub mod block_updates; // dynamic blocks, like sand falling
 pub mod calculate_frame;
 pub mod camera; // anything to do with camera
 pub mod character; // where i store everything to do with the character
 pub mod chunk; // where the blocks and chunks are stored
+pub mod chunk_compression; // turning a chunks blocks into as few bytes as possible to save them
+pub mod chunk_load_queue; // the chunks waiting to be loaded, nearest first with a budget each frame
+pub mod chunk_loader; // reading and generating chunks on worker threads
+pub mod error; // the errors reading and writing the world can give
 pub mod file_system; // where anything to read and write to the file system is stored
+pub mod fluid; // the water flow simulation
 pub mod gpu_data; // where the vbo vao ebo, and vertex and index buffers are as well as textures
+pub mod grass; // grass spreading onto dirt and dying when it is covered
+pub mod greedy_mesh; // merging the faces of a chunk into one mesh, instead of drawing a cube per block
 pub mod main_game_loop; // where i create the window and renderer and the main loop
 pub mod my_keyboard; // stores all key presses
+pub mod noise; // seeded noise used for world generation
+pub mod raycast; // finding which block the character is looking at
+pub mod region_file; // the binary files chunks are saved in, a square of chunks in each
+pub mod render_backend; // what chunks need from the renderer, so they can be used without a gpu
 pub mod renderer;
+pub mod tick_scheduler; // runs the world at a fixed tick rate, with scheduled and random block ticks
 pub mod types; // where any small types live, like position, instances
 pub mod window_wrapper; // where i store the window and event loop
 pub mod world; // this is where all of the objects in the world are stored // where i do all the calculations for the frame
+pub mod world_format; // the version the world is saved in, and upgrading worlds saved by older versions
+pub mod world_generator; // the different ways chunks can be generated
@@ -1,25 +1,33 @@
 use crate::{
+    block_type::*,
     calculate_frame::*, 
     camera::*, 
     character::*,
+    chunk_load_queue::*,
+    chunk_loader::*,
+    error::*,
     file_system::*, 
     gpu_data::*,
+    greedy_mesh::*,
     my_keyboard::*, 
     renderer::*, 
     window_wrapper::*, 
     world::*,
+    world_format::*,
+    world_generator::*,
 };
 
 use std::{
     borrow::BorrowMut,
+    env,
     sync::{Arc, Mutex},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use async_std::task;
 
 use winit::{
-    event::{ElementState, Event, WindowEvent},
+    event::{ElementState, Event, MouseButton, WindowEvent},
     keyboard::{KeyCode, PhysicalKey},
     window::Window,
 };
@@ -35,8 +43,11 @@ pub fn run_main_game_loop() {
         camera.screen_height as u32,
     );
 
+    // draw chunks with greedy meshes instead of instanced cubes if the game was started with --greedy-meshing
+    let meshing_mode: MeshingMode = MeshingMode::from_args(env::args());
+
     // create Renderer and window
-    let mut renderer: Renderer = task::block_on(Renderer::new(&window_wrapper, &camera));
+    let mut renderer: Renderer = task::block_on(Renderer::new(&window_wrapper, &camera, meshing_mode));
 
     // create MY file system struct
     let mut file_system: FileSystem = FileSystem::new();
@@ -49,6 +60,9 @@ pub fn run_main_game_loop() {
         (32, 256, 32)
     );
 
+    // how chunks are generated if this is a new world (an existing world keeps the generator it was created with)
+    world.set_generator(WorldGeneratorType::default_noise());
+
     // create the gpudata buffers
     let mut gpu_data: GPUData = GPUData::new(&renderer);
 
@@ -64,11 +78,59 @@ pub fn run_main_game_loop() {
     // load character
     let mut character: Character = Character::new(0.1);
 
+
     // validate the file system and add files and folders if needed
-    file_system.check_file_system(world.chunk_sizes, &world.world_name);
+    if let Err(e) = file_system.check_file_system(world.chunk_sizes, &world.world_name) {
+        panic!("Can not open world {:?}: {}", world.world_name, e);
+    }
+
+    // upgrade worlds saved by older versions of the game, a world saved by a newer version cant be opened
+    match upgrade_world_format(&mut file_system, world.chunk_sizes) {
+        Ok(Some(world_upgrade)) => {
+            println!(
+                "Upgraded world from format version {} to {}, {} chunks moved into region files, {} regions upgraded (backup: {:?})",
+                world_upgrade.old_version,
+                WORLD_FORMAT_VERSION,
+                world_upgrade.migrated_chunks,
+                world_upgrade.upgraded_regions,
+                world_upgrade.backup_directory
+            );
+        }
+        Ok(None) => {}
+        Err(e) => panic!("Can not open world {:?}: {}", world.world_name, e),
+    }
+
+    // keep using the seed and generator the world was created with
+    if let Err(e) = world.load_world_info(&mut file_system) {
+        panic!("Can not open world {:?}: {}", world.world_name, e);
+    }
 
     // temp, add some blocks for testing
-    world.load_created_chunks_file(&mut file_system);
+    if let Err(e) = world.load_created_chunks_file(&mut file_system) {
+        eprintln!("{}, finding the created chunks from the saved chunks instead", e);
+        world
+            .rebuild_created_chunks(&file_system)
+            .unwrap_or_else(|e| panic!("Can not open world {:?}: {}", world.world_name, e));
+    }
+
+    // losing these only means some blocks or ticks are missed, so the world can still be opened without them
+    if let Err(e) = world.load_pending_block_writes(&mut file_system) {
+        eprintln!("{}, starting without the pending block writes", e);
+    }
+    if let Err(e) = world.load_scheduled_ticks(&mut file_system) {
+        eprintln!("{}, starting without the scheduled ticks", e);
+    }
+
+    // read and generate chunks on other threads so walking into new chunks doesnt stop the game
+    world.start_chunk_loader(&file_system, get_default_worker_count());
+
+    // only add a few chunks to the world each frame, so walking into new chunks doesnt drop frames
+    world.chunk_load_queue.budget = ChunkLoadBudget::new(Some(4), Some(Duration::from_millis(4)));
+
+    // start the character just above the terrain at the world origin
+    if let Some(spawn_surface_height) = world.generator.get_surface_height(0, 0, world.chunk_sizes, world.world_seed) {
+        character.position.y = (spawn_surface_height as i32 - (world.chunk_sizes.1 as i32 / 2)) as f32 + 3.0;
+    }
 
     let mut use_cursor: bool = false;
 
@@ -200,6 +262,32 @@ pub fn run_main_game_loop() {
                         };
                     }
 
+                    WindowEvent::MouseInput {
+                        device_id: _,
+                        state: ElementState::Pressed,
+                        button,
+                    } => {
+                        // left click breaks the block being looked at, right click places one against the face being looked at
+                        if let Some(hit) = world.get_block_looked_at(&character) {
+                            let result = match button {
+                                MouseButton::Left => world
+                                    .break_block(hit.block_position.0, hit.block_position.1, hit.block_position.2)
+                                    .map(|_| ()),
+                                MouseButton::Right => world.place_block(
+                                    hit.adjacent_position.0,
+                                    hit.adjacent_position.1,
+                                    hit.adjacent_position.2,
+                                    BlockType::Cobblestone,
+                                ),
+                                _ => Ok(()),
+                            };
+
+                            if let Err(e) = result {
+                                eprintln!("{}", e);
+                            }
+                        }
+                    }
+
                     WindowEvent::CursorMoved {
                         device_id: _,
                         position,
@@ -230,17 +318,39 @@ pub fn run_main_game_loop() {
     println!("Average Frame Rate: {}", avg_fps);
 }
 
-// this will clean up all data before the program ends
+/*
+this will clean up all data before the program ends
+everything that can be saved is, even if something before it failed, and what couldnt be saved is printed
+*/
 pub fn clean_up(world: &mut World, file_system: &mut FileSystem) {
-    let hashmap_chunk_keys: Vec<(i32, i32)> = world.chunks.keys().cloned().collect();
+    // stop loading chunks first, any that are half loaded are generated again next time
+    world.stop_chunk_loader();
+
+    // the pending chunks are saved too, they are already in the created chunks so they wouldnt be generated again
+    let hashmap_chunk_keys: Vec<(i32, i32)> = world.chunks.keys().chain(world.pending_chunks.keys()).cloned().collect();
 
     // go through each chunk and call unload on it
     //let mut chunk: &Chunk;
 
+    let mut save_errors: Vec<GameError> = Vec::new();
     for key in hashmap_chunk_keys {
         // remove the chunk from the hashmap and return it
-        world.remove_chunk(key, file_system);
+        if let Err(e) = world.remove_chunk(key, file_system) {
+            save_errors.push(e);
+        }
     }
 
-    file_system.save_created_chunks_file(world.chunk_sizes, &mut world.created_chunks);
+    let world_file_results: [Result<(), GameError>; 3] = [
+        file_system.save_created_chunks_file(world.chunk_sizes, &world.created_chunks),
+        file_system.save_pending_block_writes(&world.pending_block_writes),
+        file_system.save_scheduled_ticks(&world.tick_scheduler),
+    ];
+    save_errors.extend(world_file_results.into_iter().filter_map(Result::err));
+
+    for e in save_errors.iter() {
+        eprintln!("{}", e);
+    }
+    if !save_errors.is_empty() {
+        eprintln!("{} parts of the world could not be saved", save_errors.len());
+    }
 }
@@ -0,0 +1,169 @@
+/*
+Seeded noise functions used by world generation
+everything is hashed straight from the seed and the world coordinates (there is no permutation table or rng state)
+so the same seed and position always give exactly the same value, no matter what order the chunks are generated in
+*/
+
+// mixes the bits of a number so inputs that are close together give completely different outputs (splitmix64 finaliser)
+pub fn mix_u64(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+// hash a seed and a 3d integer position into a random looking number
+pub fn hash_position(seed: u64, x: i32, y: i32, z: i32) -> u64 {
+    let mut hash: u64 = mix_u64(seed ^ 0x9e37_79b9_7f4a_7c15);
+    hash = mix_u64(hash ^ (x as i64 as u64).wrapping_add(0x632b_e59b_d9b4_e019));
+    hash = mix_u64(hash ^ (y as i64 as u64).wrapping_add(0x8cb9_2ba7_2f3d_8dd7));
+    mix_u64(hash ^ (z as i64 as u64).wrapping_add(0xd1b5_4a32_d192_ed03))
+}
+
+// smooth step so the noise has no sharp edges at grid lines
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + ((b - a) * t)
+}
+
+// the gradients are picked from a fixed list (not made with sin and cos) so the values are identical on every machine
+const GRADIENTS_2D: [(f64, f64); 8] = [
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+    (std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2),
+    (-std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2),
+    (std::f64::consts::FRAC_1_SQRT_2, -std::f64::consts::FRAC_1_SQRT_2),
+    (-std::f64::consts::FRAC_1_SQRT_2, -std::f64::consts::FRAC_1_SQRT_2),
+];
+
+// dot product of the gradient at a grid corner with the distance from that corner
+fn corner_2d(seed: u64, corner_x: i32, corner_z: i32, dx: f64, dz: f64) -> f64 {
+    let gradient: (f64, f64) = GRADIENTS_2D[(hash_position(seed, corner_x, 0, corner_z) % 8) as usize];
+    (gradient.0 * dx) + (gradient.1 * dz)
+}
+
+// 2d gradient (perlin) noise, roughly between -1 and 1, grid cells are 1 unit wide so scale the inputs down for smooth hills
+pub fn gradient_noise_2d(seed: u64, x: f64, z: f64) -> f64 {
+    let x0: f64 = x.floor();
+    let z0: f64 = z.floor();
+    let xi: i32 = x0 as i32;
+    let zi: i32 = z0 as i32;
+
+    // position inside the grid cell
+    let fx: f64 = x - x0;
+    let fz: f64 = z - z0;
+
+    let n00: f64 = corner_2d(seed, xi, zi, fx, fz);
+    let n10: f64 = corner_2d(seed, xi + 1, zi, fx - 1.0, fz);
+    let n01: f64 = corner_2d(seed, xi, zi + 1, fx, fz - 1.0);
+    let n11: f64 = corner_2d(seed, xi + 1, zi + 1, fx - 1.0, fz - 1.0);
+
+    let u: f64 = fade(fx);
+    let v: f64 = fade(fz);
+
+    // 2d perlin noise only reaches about +-0.707 so scale it up to use the whole range
+    (lerp(lerp(n00, n10, u), lerp(n01, n11, u), v) * std::f64::consts::SQRT_2).clamp(-1.0, 1.0)
+}
+
+// layers (octaves) of noise added together, each one smaller and at a higher frequency than the last for detail
+// the result is still between -1 and 1
+pub fn fractal_noise_2d(seed: u64, x: f64, z: f64, octaves: u32, persistence: f64, lacunarity: f64) -> f64 {
+    let mut total: f64 = 0.0;
+    let mut max_amplitude: f64 = 0.0;
+    let mut amplitude: f64 = 1.0;
+    let mut frequency: f64 = 1.0;
+
+    for octave in 0..octaves {
+        // every octave gets its own seed so they dont line up with each other
+        let octave_seed: u64 = mix_u64(seed.wrapping_add(octave as u64));
+        total += gradient_noise_2d(octave_seed, x * frequency, z * frequency) * amplitude;
+
+        max_amplitude += amplitude;
+        amplitude *= persistence;
+        frequency *= lacunarity;
+    }
+
+    total / max_amplitude
+}
+
+// the 12 edges of a cube, the usual gradients for 3d perlin noise
+const GRADIENTS_3D: [(f64, f64, f64); 12] = [
+    (1.0, 1.0, 0.0),
+    (-1.0, 1.0, 0.0),
+    (1.0, -1.0, 0.0),
+    (-1.0, -1.0, 0.0),
+    (1.0, 0.0, 1.0),
+    (-1.0, 0.0, 1.0),
+    (1.0, 0.0, -1.0),
+    (-1.0, 0.0, -1.0),
+    (0.0, 1.0, 1.0),
+    (0.0, -1.0, 1.0),
+    (0.0, 1.0, -1.0),
+    (0.0, -1.0, -1.0),
+];
+
+fn corner_3d(seed: u64, corner: (i32, i32, i32), dx: f64, dy: f64, dz: f64) -> f64 {
+    let gradient: (f64, f64, f64) = GRADIENTS_3D[(hash_position(seed, corner.0, corner.1, corner.2) % 12) as usize];
+    (gradient.0 * dx) + (gradient.1 * dy) + (gradient.2 * dz)
+}
+
+// 3d gradient (perlin) noise, roughly between -1 and 1, the same as the 2d version but with a y
+pub fn gradient_noise_3d(seed: u64, x: f64, y: f64, z: f64) -> f64 {
+    let x0: f64 = x.floor();
+    let y0: f64 = y.floor();
+    let z0: f64 = z.floor();
+    let xi: i32 = x0 as i32;
+    let yi: i32 = y0 as i32;
+    let zi: i32 = z0 as i32;
+
+    let fx: f64 = x - x0;
+    let fy: f64 = y - y0;
+    let fz: f64 = z - z0;
+
+    let n000: f64 = corner_3d(seed, (xi, yi, zi), fx, fy, fz);
+    let n100: f64 = corner_3d(seed, (xi + 1, yi, zi), fx - 1.0, fy, fz);
+    let n010: f64 = corner_3d(seed, (xi, yi + 1, zi), fx, fy - 1.0, fz);
+    let n110: f64 = corner_3d(seed, (xi + 1, yi + 1, zi), fx - 1.0, fy - 1.0, fz);
+    let n001: f64 = corner_3d(seed, (xi, yi, zi + 1), fx, fy, fz - 1.0);
+    let n101: f64 = corner_3d(seed, (xi + 1, yi, zi + 1), fx - 1.0, fy, fz - 1.0);
+    let n011: f64 = corner_3d(seed, (xi, yi + 1, zi + 1), fx, fy - 1.0, fz - 1.0);
+    let n111: f64 = corner_3d(seed, (xi + 1, yi + 1, zi + 1), fx - 1.0, fy - 1.0, fz - 1.0);
+
+    let u: f64 = fade(fx);
+    let v: f64 = fade(fy);
+    let w: f64 = fade(fz);
+
+    let bottom: f64 = lerp(lerp(n000, n100, u), lerp(n001, n101, u), w);
+    let top: f64 = lerp(lerp(n010, n110, u), lerp(n011, n111, u), w);
+
+    lerp(bottom, top, v).clamp(-1.0, 1.0)
+}
+
+// a small random number generator (splitmix64) for generation steps that need a sequence of random choices, like ore veins
+// seed it from hash_position so it is the same every time for the same seed and chunk
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> SeededRandom {
+        SeededRandom { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix_u64(self.state)
+    }
+
+    // a random number from min up to but not including max
+    pub fn next_range(&mut self, min: i32, max: i32) -> i32 {
+        if max <= min {
+            return min;
+        }
+        min + (self.next_u64() % (max - min) as u64) as i32
+    }
+}
@@ -41,6 +41,7 @@ pub fn fill_chunk_hashmap_old(
                             InstanceData {
                                 model_matrix: temp_chunk_vec[x][y][z].model_matrix.clone(),
                                 colour: temp_chunk_vec[x][y][z].block_type.block_colour(),
+                                visible_faces: temp_chunk_vec[x][y][z].visible_faces as u32,
                             },
                         );
                     }
@@ -81,6 +82,7 @@ pub fn fill_chunk_hashmap_new_1(
                             InstanceData {
                                 model_matrix: temp_chunk_vec[x][y][z].model_matrix.clone(),
                                 colour: temp_chunk_vec[x][y][z].block_type.block_colour(),
+                                visible_faces: temp_chunk_vec[x][y][z].visible_faces as u32,
                             },
                         );
                     }
@@ -150,6 +152,7 @@ pub fn fill_chunk_hashmap_new_2(
                             InstanceData {
                                 model_matrix: cached_block.model_matrix.clone(),
                                 colour: cached_block.block_type.block_colour(),
+                                visible_faces: cached_block.visible_faces as u32,
                             },
                         );
                     }
@@ -212,6 +215,7 @@ pub fn fill_chunk_hashmap_new_3(
                         instances_to_insert.push(InstanceData {
                             model_matrix: cached_block.model_matrix.clone(),
                             colour: cached_block.block_type.block_colour(),
+                            visible_faces: cached_block.visible_faces as u32,
                         });
                         instances_keys_to_insert.push(cached_key);
                     }
@@ -277,6 +281,7 @@ pub fn fill_chunk_hashmap_new_4(
                             InstanceData {
                                 model_matrix: cached_block.model_matrix.clone(),
                                 colour: cached_block.block_type.block_colour(),
+                                visible_faces: cached_block.visible_faces as u32,
                             }
                         ));
                     }
@@ -333,6 +338,7 @@ pub fn fill_chunk_hashmap_new_5(
                             InstanceData {
                                 model_matrix: block.model_matrix.clone(),
                                 colour: block.block_type.block_colour(),
+                                visible_faces: block.visible_faces as u32,
                             }
                         ));
                     }
@@ -396,6 +402,7 @@ pub fn fill_chunk_hashmap_new_6(
                             InstanceData {
                                 model_matrix: cached_block.model_matrix.clone(),
                                 colour: cached_block.block_type.block_colour(),
+                                visible_faces: cached_block.visible_faces as u32,
                             }
                         ));
                     }
@@ -446,6 +453,7 @@ pub fn fill_chunk_hashmap_new_7(
                             InstanceData {
                                 model_matrix: block.model_matrix.clone(),
                                 colour: block.block_type.block_colour(),
+                                visible_faces: block.visible_faces as u32,
                             }
                         ));
                     }
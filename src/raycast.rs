@@ -0,0 +1,131 @@
+/*
+Finds the block the character is looking at by stepping through every block the ray passes through (DDA voxel traversal)
+this is instead of the queue idea in the readme, it only visits the blocks the ray actually touches and in order so the first
+solid block found is the closest one
+*/
+
+use crate::{block::*, block_type::*, types::*};
+
+// everything about the block a ray hit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RaycastHit {
+    // the world position and type of the block that was hit
+    pub block_position: (i32, i16, i32),
+    pub block_type: BlockType,
+
+    // the face of the hit block the ray went through
+    pub face: BlockFace,
+
+    // the block touching that face, this is where a new block would be placed
+    pub adjacent_position: (i32, i16, i32),
+
+    // how far along the ray the hit face is
+    pub distance: f32,
+}
+
+/*
+walk along the ray from origin in direction until a non transparent block is hit or reach is passed
+get_block returns the block at a world position, or None if that position isnt loaded (which stops the ray)
+positions above or below the world should be given back as air, so a ray from above the world can still come down onto it
+the block the origin is inside of is skipped so standing inside a block doesnt hit it
+*/
+pub fn raycast_blocks<F>(origin: FPosition, direction: FPosition, reach: f32, get_block: F) -> Option<RaycastHit>
+where
+    F: Fn((i32, i16, i32)) -> Option<BlockType>,
+{
+    let length: f32 = ((direction.x * direction.x) + (direction.y * direction.y) + (direction.z * direction.z)).sqrt();
+    if length < 0.0001 {
+        return None;
+    }
+    let dir: (f32, f32, f32) = (direction.x / length, direction.y / length, direction.z / length);
+
+    // the block the ray is currently in (y is kept as an i32 so it cant overflow while stepping)
+    let mut block: (i32, i32, i32) = (
+        origin.x.floor() as i32,
+        origin.y.floor() as i32,
+        origin.z.floor() as i32,
+    );
+
+    // which way to step on each axis
+    let step: (i32, i32, i32) = (
+        if dir.0 > 0.0 { 1 } else { -1 },
+        if dir.1 > 0.0 { 1 } else { -1 },
+        if dir.2 > 0.0 { 1 } else { -1 },
+    );
+
+    // how far along the ray it takes to cross one whole block on each axis
+    let t_delta: (f32, f32, f32) = (
+        if dir.0 != 0.0 { (1.0 / dir.0).abs() } else { f32::INFINITY },
+        if dir.1 != 0.0 { (1.0 / dir.1).abs() } else { f32::INFINITY },
+        if dir.2 != 0.0 { (1.0 / dir.2).abs() } else { f32::INFINITY },
+    );
+
+    // how far along the ray the next block boundary is on each axis
+    let mut t_max: (f32, f32, f32) = (
+        distance_to_boundary(origin.x, block.0, dir.0),
+        distance_to_boundary(origin.y, block.1, dir.1),
+        distance_to_boundary(origin.z, block.2, dir.2),
+    );
+
+    let mut distance: f32;
+    let mut face: BlockFace;
+    loop {
+        // step into the next block on whichever axis has the closest boundary
+        // the face entered is the opposite of the direction moved
+        if t_max.0 < t_max.1 && t_max.0 < t_max.2 {
+            block.0 += step.0;
+            distance = t_max.0;
+            t_max.0 += t_delta.0;
+            face = if step.0 > 0 { BlockFace::Left } else { BlockFace::Right };
+        } else if t_max.1 < t_max.2 {
+            block.1 += step.1;
+            distance = t_max.1;
+            t_max.1 += t_delta.1;
+            face = if step.1 > 0 { BlockFace::Bottom } else { BlockFace::Top };
+        } else {
+            block.2 += step.2;
+            distance = t_max.2;
+            t_max.2 += t_delta.2;
+            face = if step.2 > 0 { BlockFace::Front } else { BlockFace::Back };
+        }
+
+        if distance > reach {
+            return None;
+        }
+
+        // too far above or below to even have a position, so it is air like the rest of the space outside the world
+        if block.1 < i16::MIN as i32 || block.1 > i16::MAX as i32 {
+            continue;
+        }
+        let block_position: (i32, i16, i32) = (block.0, block.1 as i16, block.2);
+
+        // stop if the ray leaves the loaded world
+        let block_type: BlockType = get_block(block_position)?;
+
+        if !block_type.is_transparent() {
+            let offset: (i32, i16, i32) = face.offset();
+            return Some(RaycastHit {
+                block_position,
+                block_type,
+                face,
+                adjacent_position: (
+                    block_position.0 + offset.0,
+                    block_position.1 + offset.1,
+                    block_position.2 + offset.2,
+                ),
+                distance,
+            });
+        }
+    }
+}
+
+// how far along the ray (on one axis) until it crosses into the next block
+fn distance_to_boundary(origin: f32, block: i32, dir: f32) -> f32 {
+    if dir > 0.0 {
+        ((block + 1) as f32 - origin) / dir
+    } else if dir < 0.0 {
+        (origin - block as f32) / -dir
+    } else {
+        f32::INFINITY
+    }
+}
@@ -0,0 +1,411 @@
+/*
+Binary region files, each one holds a square of REGION_SIZE x REGION_SIZE chunks
+the text files had every block id written out as a decimal number with a space after it, which is slow to write and read and very big
+a region file starts with a header that has the chunk sizes and an offset table, the place and length of each chunk in the file
+so saving or loading a chunk is a seek to its offset and one read or write
+the bytes of each chunk are made by chunk_compression, the region file doesnt look inside them
+
+the header is
+    "RCRG"                          4 bytes
+    version                         u32
+    chunk sizes (x, y, z)           3 u32s
+    offset table                    (u64 offset, u32 length, u32 checksum) for each chunk in the region, 0 length if it hasnt been saved
+all numbers are little endian
+the chunk at (x, z) in the region (0 to REGION_SIZE - 1) is entry x + z * REGION_SIZE in the table
+version 1 regions didnt have the checksum, they can still be read so the world can be upgraded
+
+so a crash while saving cant break a chunk that was already saved, a chunk is never written over where it was before
+its new bytes go at the end of the file and are synced to the disk, and only then is its entry in the offset table changed to point at them
+if the game stops before that the entry still points at the old bytes, which are still there
+the checksum catches anything else (like a disk that only wrote half of the entry), so bad bytes are an error instead of the wrong blocks
+once there is more space taken by old copies of chunks than by the chunks themselves the region is rewritten without them
+*/
+
+use crate::file_system::*;
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+// how many chunks along x and z are in each region file
+pub const REGION_SIZE: i32 = 16;
+
+const REGION_MAGIC: [u8; 4] = *b"RCRG";
+pub const REGION_VERSION: u32 = 2;
+// the version before the offset table had checksums
+const UNCHECKED_REGION_VERSION: u32 = 1;
+
+const CHUNKS_PER_REGION: usize = (REGION_SIZE * REGION_SIZE) as usize;
+const HEADER_START_SIZE: usize = 20;
+
+// dont bother rewriting a region to get rid of old copies of chunks until they take up at least this much space
+const MIN_COMPACT_SIZE: u64 = 256 * 1024;
+
+// the region a chunk is in, this divides properly for negative chunks too
+pub fn get_region_id(chunk_id: (i32, i32)) -> (i32, i32) {
+    (chunk_id.0.div_euclid(REGION_SIZE), chunk_id.1.div_euclid(REGION_SIZE))
+}
+
+pub fn get_region_file_name(region_id: (i32, i32)) -> String {
+    format!("r_{}_{}.region", region_id.0, region_id.1)
+}
+
+// where the chunk is in the offset table
+fn get_chunk_index(chunk_id: (i32, i32)) -> usize {
+    (chunk_id.0.rem_euclid(REGION_SIZE) + chunk_id.1.rem_euclid(REGION_SIZE) * REGION_SIZE) as usize
+}
+
+// the chunk id of an entry in the offset table of a region
+fn get_index_chunk_id(region_id: (i32, i32), index: usize) -> (i32, i32) {
+    (
+        region_id.0 * REGION_SIZE + (index as i32 % REGION_SIZE),
+        region_id.1 * REGION_SIZE + (index as i32 / REGION_SIZE),
+    )
+}
+
+// each entry is a u64 offset and a u32 length, then a u32 checksum from version 2
+fn get_offset_entry_size(version: u32) -> usize {
+    if version == UNCHECKED_REGION_VERSION { 12 } else { 16 }
+}
+
+fn get_header_size(version: u32) -> usize {
+    HEADER_START_SIZE + CHUNKS_PER_REGION * get_offset_entry_size(version)
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// where a chunk is in the region file
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RegionEntry {
+    offset: u64,
+    length: u32,
+    // none for version 1 regions
+    checksum: Option<u32>,
+}
+
+pub struct RegionFile {
+    file: File,
+    path: PathBuf,
+    version: u32,
+    chunk_sizes: (usize, usize, usize),
+
+    // read from the header when the file is opened
+    entries: Vec<RegionEntry>,
+}
+
+impl RegionFile {
+    /*
+    open a region file, making it with an empty offset table if it doesnt exist yet
+    a new region is made in a temp file first, so there is never a region file without a whole header
+    */
+    pub fn open(path: &Path, chunk_sizes: (usize, usize, usize)) -> io::Result<RegionFile> {
+        let is_new: bool = !path.exists() || path.metadata()?.len() == 0;
+        if is_new {
+            let empty_entries: Vec<RegionEntry> = vec![RegionEntry::default(); CHUNKS_PER_REGION];
+            write_file_atomic(path, &make_header(chunk_sizes, &empty_entries))?;
+        }
+
+        let file: File = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut region_file: RegionFile = RegionFile {
+            file,
+            path: path.to_path_buf(),
+            version: REGION_VERSION,
+            chunk_sizes,
+            entries: vec![RegionEntry::default(); CHUNKS_PER_REGION],
+        };
+        region_file.read_header()?;
+
+        Ok(region_file)
+    }
+
+    fn read_header(&mut self) -> io::Result<()> {
+        let mut header_start: [u8; HEADER_START_SIZE] = [0; HEADER_START_SIZE];
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_exact(&mut header_start)
+            .map_err(|_| invalid_data(format!("Region file {:?} is too short for its header", self.path)))?;
+
+        if header_start[0..4] != REGION_MAGIC {
+            return Err(invalid_data(format!("{:?} is not a region file", self.path)));
+        }
+        let version: u32 = read_u32(&header_start, 4);
+        // not corrupted, so this is a different kind of error and the region is left alone
+        if version > REGION_VERSION {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, format!(
+                "Region file {:?} was saved in version {} by a newer version of the game, this version can only read up to version {}",
+                self.path, version, REGION_VERSION
+            )));
+        }
+        if version < UNCHECKED_REGION_VERSION {
+            return Err(invalid_data(format!("Region file {:?} has unknown version {}", self.path, version)));
+        }
+        self.version = version;
+
+        let file_chunk_sizes: (usize, usize, usize) = (
+            read_u32(&header_start, 8) as usize,
+            read_u32(&header_start, 12) as usize,
+            read_u32(&header_start, 16) as usize,
+        );
+        if file_chunk_sizes != self.chunk_sizes {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+                "Region file {:?} has chunk sizes {:?} but the world uses {:?}",
+                self.path, file_chunk_sizes, self.chunk_sizes
+            )));
+        }
+
+        let entry_size: usize = get_offset_entry_size(version);
+        let mut offset_table: Vec<u8> = vec![0; CHUNKS_PER_REGION * entry_size];
+        self.file.read_exact(&mut offset_table)
+            .map_err(|_| invalid_data(format!("Region file {:?} is too short for its header", self.path)))?;
+
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            let entry_start: usize = index * entry_size;
+            *entry = RegionEntry {
+                offset: read_u64(&offset_table, entry_start),
+                length: read_u32(&offset_table, entry_start + 8),
+                checksum: if version == UNCHECKED_REGION_VERSION { None } else { Some(read_u32(&offset_table, entry_start + 12)) },
+            };
+        }
+
+        Ok(())
+    }
+
+    // the version the region was saved in, older ones can only be read
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn has_chunk(&self, chunk_id: (i32, i32)) -> bool {
+        self.entries[get_chunk_index(chunk_id)].length > 0
+    }
+
+    // the chunks that have been saved in this region
+    pub fn get_chunk_ids(&self, region_id: (i32, i32)) -> Vec<(i32, i32)> {
+        (0..CHUNKS_PER_REGION)
+            .filter(|index| self.entries[*index].length > 0)
+            .map(|index| get_index_chunk_id(region_id, index))
+            .collect()
+    }
+
+    /*
+    the saved bytes of a chunk, none if it hasnt been saved to this region
+    an error if the bytes go past the end of the file or dont match their checksum
+    */
+    pub fn read_chunk(&mut self, chunk_id: (i32, i32)) -> io::Result<Option<Vec<u8>>> {
+        let entry: RegionEntry = self.entries[get_chunk_index(chunk_id)];
+        if entry.length == 0 {
+            return Ok(None);
+        }
+
+        let file_length: u64 = self.file.metadata()?.len();
+        if entry.offset.saturating_add(entry.length as u64) > file_length {
+            return Err(invalid_data(format!(
+                "Chunk {:?} in {:?} is cut short, it should end at {} but the file is only {} bytes",
+                chunk_id, self.path, entry.offset.saturating_add(entry.length as u64), file_length
+            )));
+        }
+
+        let mut data: Vec<u8> = vec![0; entry.length as usize];
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+        self.file.read_exact(&mut data)?;
+
+        if let Some(checksum) = entry.checksum {
+            if get_checksum(&data) != checksum {
+                return Err(invalid_data(format!("Chunk {:?} in {:?} is corrupted, its checksum doesnt match", chunk_id, self.path)));
+            }
+        }
+
+        Ok(Some(data))
+    }
+
+    /*
+    whatever bytes are saved for a chunk, without checking them, cut short if the file is
+    so a chunk that cant be read can still be copied somewhere to look at
+    */
+    pub fn read_chunk_unchecked(&mut self, chunk_id: (i32, i32)) -> io::Result<Option<Vec<u8>>> {
+        let entry: RegionEntry = self.entries[get_chunk_index(chunk_id)];
+        if entry.length == 0 {
+            return Ok(None);
+        }
+
+        let file_length: u64 = self.file.metadata()?.len();
+        let end: u64 = (entry.offset + entry.length as u64).min(file_length);
+        let mut data: Vec<u8> = vec![0; end.saturating_sub(entry.offset) as usize];
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+        self.file.read_exact(&mut data)?;
+
+        Ok(Some(data))
+    }
+
+    // take a chunk out of the region so it is as if it was never saved, its bytes are thrown away next time the region is compacted
+    pub fn remove_chunk(&mut self, chunk_id: (i32, i32)) -> io::Result<()> {
+        if self.version != REGION_VERSION {
+            return Err(invalid_data(format!(
+                "Region file {:?} is version {}, it has to be upgraded to version {} before chunks can be removed from it",
+                self.path, self.version, REGION_VERSION
+            )));
+        }
+
+        let index: usize = get_chunk_index(chunk_id);
+        self.entries[index] = RegionEntry::default();
+        self.write_offset_entry(index)?;
+        self.file.sync_data()
+    }
+
+    /*
+    save the bytes of a chunk into the region
+    they go at the end of the file and are synced, then its entry in the offset table is changed to point at them
+    */
+    pub fn write_chunk(&mut self, chunk_id: (i32, i32), data: &[u8]) -> io::Result<()> {
+        if self.version != REGION_VERSION {
+            return Err(invalid_data(format!(
+                "Region file {:?} is version {}, it has to be upgraded to version {} before chunks can be saved in it",
+                self.path, self.version, REGION_VERSION
+            )));
+        }
+
+        let offset: u64 = self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(data)?;
+        // the bytes have to be on the disk before anything points at them
+        self.file.sync_data()?;
+
+        let index: usize = get_chunk_index(chunk_id);
+        self.entries[index] = RegionEntry {
+            offset,
+            length: data.len() as u32,
+            checksum: Some(get_checksum(data)),
+        };
+        self.write_offset_entry(index)?;
+        // and the entry has to be on the disk before the save counts as done
+        self.file.sync_data()?;
+
+        // the chunk is saved now, so a region that cant be compacted only takes up more space and the save still counts
+        if let Err(e) = self.should_compact().and_then(|should_compact| if should_compact { self.compact() } else { Ok(()) }) {
+            eprintln!("Could not compact region file {:?}, it will be tried again on the next save: {}", self.path, e);
+        }
+
+        Ok(())
+    }
+
+    fn write_offset_entry(&mut self, index: usize) -> io::Result<()> {
+        let entry_size: usize = get_offset_entry_size(self.version);
+        self.file.seek(SeekFrom::Start((HEADER_START_SIZE + index * entry_size) as u64))?;
+        self.file.write_all(&make_offset_entry(&self.entries[index]))
+    }
+
+    // if the old copies of chunks take up more space than the chunks that are used
+    fn should_compact(&self) -> io::Result<bool> {
+        let used_size: u64 = self.entries.iter().map(|entry| entry.length as u64).sum();
+        let unused_size: u64 = self.file.metadata()?.len().saturating_sub(get_header_size(self.version) as u64 + used_size);
+        Ok(unused_size > MIN_COMPACT_SIZE && unused_size > used_size)
+    }
+
+    /*
+    rewrite the region with only the chunks that are used, into a temp file that then replaces it
+    so if this stops half way the old region file is still there
+    a chunk whose bytes cant be read at all (like an entry pointing past the end of the file) is left out, so one bad entry
+    doesnt stop the rest of the region being compacted, it would be a corrupted chunk when loaded anyway
+    */
+    pub fn compact(&mut self) -> io::Result<()> {
+        let mut entries: Vec<RegionEntry> = vec![RegionEntry::default(); CHUNKS_PER_REGION];
+        let mut data: Vec<u8> = Vec::new();
+        let header_size: u64 = get_header_size(REGION_VERSION) as u64;
+        let file_length: u64 = self.file.metadata()?.len();
+
+        for (index, (entry, new_entry)) in self.entries.iter().zip(entries.iter_mut()).enumerate() {
+            if entry.length == 0 {
+                continue;
+            }
+
+            let mut chunk_data: Vec<u8> = vec![0; entry.length as usize];
+            let chunk_end: u64 = entry.offset.saturating_add(entry.length as u64);
+            let read_result: io::Result<()> = if chunk_end > file_length {
+                Err(invalid_data(format!("it should end at {} but the file is only {} bytes", chunk_end, file_length)))
+            } else {
+                self.file.seek(SeekFrom::Start(entry.offset)).and_then(|_| self.file.read_exact(&mut chunk_data))
+            };
+            if let Err(e) = read_result {
+                eprintln!("Entry {} in region file {:?} cant be read, it is left out of the compacted region: {}", index, self.path, e);
+                continue;
+            }
+
+            *new_entry = RegionEntry {
+                offset: header_size + data.len() as u64,
+                length: entry.length,
+                checksum: Some(entry.checksum.unwrap_or_else(|| get_checksum(&chunk_data))),
+            };
+            data.extend_from_slice(&chunk_data);
+        }
+
+        let mut region_bytes: Vec<u8> = make_header(self.chunk_sizes, &entries);
+        region_bytes.extend_from_slice(&data);
+        write_file_atomic(&self.path, &region_bytes)?;
+
+        // the old file was replaced so open the new one
+        self.file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        self.version = REGION_VERSION;
+        self.entries = entries;
+        Ok(())
+    }
+}
+
+// the header of a region with these entries, always the newest version
+fn make_header(chunk_sizes: (usize, usize, usize), entries: &[RegionEntry]) -> Vec<u8> {
+    let mut header: Vec<u8> = Vec::with_capacity(get_header_size(REGION_VERSION));
+    header.extend_from_slice(&REGION_MAGIC);
+    header.extend_from_slice(&REGION_VERSION.to_le_bytes());
+    header.extend_from_slice(&(chunk_sizes.0 as u32).to_le_bytes());
+    header.extend_from_slice(&(chunk_sizes.1 as u32).to_le_bytes());
+    header.extend_from_slice(&(chunk_sizes.2 as u32).to_le_bytes());
+    for entry in entries.iter() {
+        header.extend_from_slice(&make_offset_entry(entry));
+    }
+    header
+}
+
+fn make_offset_entry(entry: &RegionEntry) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(get_offset_entry_size(REGION_VERSION));
+    bytes.extend_from_slice(&entry.offset.to_le_bytes());
+    bytes.extend_from_slice(&entry.length.to_le_bytes());
+    bytes.extend_from_slice(&entry.checksum.unwrap_or(0).to_le_bytes());
+    bytes
+}
+
+// the table for the crc32 checksum, made when the game is compiled
+const CHECKSUM_TABLE: [u32; 256] = make_checksum_table();
+
+const fn make_checksum_table() -> [u32; 256] {
+    let mut table: [u32; 256] = [0; 256];
+    let mut index: usize = 0;
+    while index < 256 {
+        let mut value: u32 = index as u32;
+        let mut bit: usize = 0;
+        while bit < 8 {
+            value = if value & 1 == 1 { 0xEDB88320 ^ (value >> 1) } else { value >> 1 };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+}
+
+// the crc32 of some bytes (the same one zip files use), any change to the bytes changes it
+pub fn get_checksum(data: &[u8]) -> u32 {
+    let mut checksum: u32 = 0xFFFFFFFF;
+    for byte in data.iter() {
+        checksum = CHECKSUM_TABLE[((checksum ^ *byte as u32) & 0xFF) as usize] ^ (checksum >> 8);
+    }
+    !checksum
+}
+
+fn read_u32(bytes: &[u8], start: usize) -> u32 {
+    u32::from_le_bytes(bytes[start..start + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], start: usize) -> u64 {
+    u64::from_le_bytes(bytes[start..start + 8].try_into().unwrap())
+}
@@ -0,0 +1,85 @@
+/*
+What chunks and the world need from the renderer, so they can be used without a gpu
+the renderer is the normal backend, and the headless backend does nothing with the gpu at all
+so chunks can be loaded, generated, changed and saved in tests or on a server with no device
+*/
+
+use crate::{
+    block::*,
+    chunk::{chunk_borders::*, chunk_cpu_functions::*, chunk_gpu_functions::check_for_touching_air},
+    greedy_mesh::*,
+    renderer::*,
+};
+
+use async_std::task;
+use wgpu::{Device, Queue};
+
+pub trait RenderBackend {
+    // the device and queue chunks make and write their buffers with, none if there is no gpu so chunks dont have any buffers
+    fn get_gpu(&self) -> Option<(&Device, &Queue)>;
+
+    // set the visible faces of every block in a chunk that is being loaded
+    fn check_for_touching_air(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_borders: &ChunkBorders,
+        chunk_sizes: (usize, usize, usize),
+    );
+
+    // how the chunks are drawn, so greedy meshes are only built if they are going to be used
+    fn get_meshing_mode(&self) -> MeshingMode;
+}
+
+impl RenderBackend for Renderer {
+    fn get_gpu(&self) -> Option<(&Device, &Queue)> {
+        Some((&self.device, &self.queue))
+    }
+
+    fn check_for_touching_air(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_borders: &ChunkBorders,
+        chunk_sizes: (usize, usize, usize),
+    ) {
+        // async because reading from gpu is async
+        // without a real gpu to run the compute shader on it is done on the cpu instead, they both give the same result
+        match self.touching_air_pass {
+            TouchingAirPass::Gpu => task::block_on(check_for_touching_air(
+                temp_chunk_vec,
+                chunk_borders,
+                &self.device,
+                &self.queue,
+                &self.check_air_compute_shader_code,
+                chunk_sizes,
+            )),
+            TouchingAirPass::Cpu => check_for_touching_air_cpu(temp_chunk_vec, chunk_borders, chunk_sizes),
+        }
+    }
+
+    fn get_meshing_mode(&self) -> MeshingMode {
+        self.meshing_mode
+    }
+}
+
+// no gpu, chunks only keep their blocks and the touching air pass is always done on the cpu
+pub struct HeadlessBackend;
+
+impl RenderBackend for HeadlessBackend {
+    fn get_gpu(&self) -> Option<(&Device, &Queue)> {
+        None
+    }
+
+    fn check_for_touching_air(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_borders: &ChunkBorders,
+        chunk_sizes: (usize, usize, usize),
+    ) {
+        check_for_touching_air_cpu(temp_chunk_vec, chunk_borders, chunk_sizes);
+    }
+
+    // nothing is drawn so there are never any meshes to build
+    fn get_meshing_mode(&self) -> MeshingMode {
+        MeshingMode::Instanced
+    }
+}
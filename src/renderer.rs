@@ -3,9 +3,10 @@
 use crate::{
     camera::*, 
     gpu_data::*, 
+    greedy_mesh::*,
     types::*, 
     window_wrapper::*, 
-    chunk::*,
+    chunk::{*, chunk_cpu_functions::*},
 };
 
 use wgpu::{
@@ -27,9 +28,16 @@ pub struct Renderer {
     pub fragment_shader_code: ShaderModule,
     pub check_air_compute_shader_code: ShaderModule,
 
+    // if chunks are drawn as instanced cubes or greedy meshes, picked at startup
+    pub meshing_mode: MeshingMode,
+
+    // if the touching air pass for loading chunks runs on the gpu or cpu, picked from the adapter
+    pub touching_air_pass: TouchingAirPass,
+
     // pipeline
     pub pipeline_layout: PipelineLayout,
     pub render_pipeline: RenderPipeline,
+    pub mesh_render_pipeline: RenderPipeline, // for greedy meshed chunks
     pub surface_config: SurfaceConfiguration,
     pub bind_group: wgpu::BindGroup,
     pub vertex_uniforms: VertexUniforms,
@@ -39,7 +47,7 @@ pub struct Renderer {
 
 // this is where i write the functions for the Renderer Struct
 impl Renderer {
-    pub async fn new(window_wrapper: &WindowWrapper, camera: &Camera) -> Renderer {
+    pub async fn new(window_wrapper: &WindowWrapper, camera: &Camera, meshing_mode: MeshingMode) -> Renderer {
         // set the window height and width variables
         let window_height = window_wrapper.window.inner_size().height;
         let window_width = window_wrapper.window.inner_size().width;
@@ -50,14 +58,24 @@ impl Renderer {
             .create_surface(window_wrapper.window.clone())
             .unwrap();
 
-        let adapter = instance
+        // if there is no hardware adapter try the software one, then the touching air pass runs on the cpu
+        let mut adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::HighPerformance,
                 force_fallback_adapter: false,
                 compatible_surface: Some(&surface),
             })
-            .await
-            .unwrap();
+            .await;
+        if adapter.is_none() {
+            adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::HighPerformance,
+                    force_fallback_adapter: true,
+                    compatible_surface: Some(&surface),
+                })
+                .await;
+        }
+        let adapter = adapter.expect("There is no graphics adapter to draw the game with, not even a software one");
 
         let (device, queue) = adapter
             .request_device(
@@ -71,12 +89,20 @@ impl Renderer {
             .await
             .unwrap();
 
+        // software adapters dont have a real gpu to run the touching air compute shader on
+        let touching_air_pass: TouchingAirPass = TouchingAirPass::from_adapter_info(&adapter.get_info());
+
         // compile my shaders
         let vertex_shader_code = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Vertex Shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("Shaders/vertex.wgsl").into()),
         });
 
+        let mesh_vertex_shader_code = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Mesh Vertex Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("Shaders/mesh_vertex.wgsl").into()),
+        });
+
         let fragment_shader_code = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Fragment Shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("Shaders/fragment.wgsl").into()),
@@ -98,21 +124,18 @@ impl Renderer {
         //let swapchain_capabilities = surface.get_capabilities(&adapter);
         //let swapchain_format = swapchain_capabilities.formats[0];
 
-        // describe the layout of the vertex buffer in memory, 3 floats of pos
+        // describe the layout of the vertex buffer in memory, 3 floats of pos and 1 float for the face it is part of
         let vertex_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: 3 * std::mem::size_of::<f32>() as wgpu::BufferAddress,
+            array_stride: 4 * std::mem::size_of::<f32>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+            attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32],
         };
 
         // describe the layout of the instance buffer in memory, 4x4 matrix which is actually 4x vec4
         let instance_buffer_layout = wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Instance,
-            attributes: &wgpu::vertex_attr_array![
-                2 => Float32x4, 3 => Float32x4, 4 => Float32x4, 5 => Float32x4, // matrix
-                6 => Float32x3, // color
-            ],
+            attributes: &InstanceData::ATTRIBUTES,
         };
 
         // this holsd the uniform data for the vertex shader, the view and projection matrixies combined
@@ -215,6 +238,55 @@ impl Renderer {
             multiview: None,
         });
 
+        // greedy meshes have a position and colour for every vertex and no instances
+        let mesh_vertex_buffer_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4],
+        };
+
+        // the same as the render pipeline but for greedy meshed chunks
+        let mesh_render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mesh Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &mesh_vertex_shader_code,
+                entry_point: "main", // the entry point for the vertex shader
+                buffers: &[mesh_vertex_buffer_layout],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &fragment_shader_code,
+                entry_point: "main", // the entry point for the fragment shader
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_config.format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+                unclipped_depth: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
         Self {
             instance,
             surface,
@@ -227,9 +299,13 @@ impl Renderer {
             fragment_shader_code,
             check_air_compute_shader_code,
 
+            meshing_mode,
+            touching_air_pass,
+
             // pipeline
             pipeline_layout,
             render_pipeline,
+            mesh_render_pipeline,
             surface_config,
             bind_group,
             vertex_uniforms,
@@ -292,24 +368,45 @@ impl Renderer {
                 occlusion_query_set: None,
             });
 
-            // Set the vertex and index buffers here
-            rpass.set_vertex_buffer(0, gpu_data.vertex_buf.slice(..));
-            rpass.set_index_buffer(gpu_data.index_buf.slice(..), wgpu::IndexFormat::Uint16);
             rpass.set_bind_group(0, &self.bind_group, &[]);
 
-            rpass.set_pipeline(&self.render_pipeline);
-
-            // draw all full cubes (indicies 0.36 is all the indicies for a cube
-            // then draw the number of instances specified
-            //rpass.draw_indexed(0..36, 0, 0..gpuData.instancesUsed as u32);
-
-            // Iterate over each chunk
-            for chunk in chunks.values() {
-                // Set the instance buffer for this chunk
-                rpass.set_vertex_buffer(1, chunk.instance_buffer.slice(..));
-
-                // Draw the instances for this chunk
-                rpass.draw_indexed(0..36, 0, 0..chunk.instance_size);
+            match self.meshing_mode {
+                MeshingMode::Instanced => {
+                    // Set the vertex and index buffers here
+                    rpass.set_vertex_buffer(0, gpu_data.vertex_buf.slice(..));
+                    rpass.set_index_buffer(gpu_data.index_buf.slice(..), wgpu::IndexFormat::Uint16);
+
+                    rpass.set_pipeline(&self.render_pipeline);
+
+                    // draw all full cubes (indicies 0.36 is all the indicies for a cube
+                    // then draw the number of instances specified
+                    //rpass.draw_indexed(0..36, 0, 0..gpuData.instancesUsed as u32);
+
+                    // Iterate over each chunk
+                    for chunk in chunks.values() {
+                        // chunks made by this renderer always have their buffers
+                        if let Some(instance_buffers) = &chunk.instance_buffers {
+                            // Set the instance buffer for this chunk
+                            rpass.set_vertex_buffer(1, instance_buffers.instance_buffer.slice(..));
+
+                            // Draw the instances for this chunk
+                            // (while a chunk is growing its buffers the size can be larger than the buffer being drawn)
+                            rpass.draw_indexed(0..36, 0, 0..chunk.instance_size.min(chunk.instance_capacity));
+                        }
+                    }
+                }
+                MeshingMode::Greedy => {
+                    rpass.set_pipeline(&self.mesh_render_pipeline);
+
+                    // each chunk has its own mesh, chunks with nothing to draw dont have one
+                    for chunk in chunks.values() {
+                        if let (Some(vertex_buffer), Some(index_buffer)) = (&chunk.mesh_vertex_buffer, &chunk.mesh_index_buffer) {
+                            rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                            rpass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                            rpass.draw_indexed(0..chunk.mesh_index_count, 0, 0..1);
+                        }
+                    }
+                }
             }
         } // the render pass must go out of scope before submit and present are called
           // it finalises the render pass when it goes out of scope so it can be submitted to the gpu
@@ -0,0 +1,222 @@
+/*
+The world runs on ticks, a fixed number every second no matter what the frame rate is
+each tick the world runs the block ticks that were scheduled for it (like sand falling or water flowing)
+and gives a few random blocks in every section of every loaded chunk a random tick (for slow things like grass spreading)
+
+scheduled ticks for blocks in chunks that arnt loaded wait until their chunk is loaded again
+the scheduled ticks are saved with the world so nothing is lost when the game is closed
+*/
+
+use crate::noise::*;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+// tick settings
+pub const TICKS_PER_SECOND: u32 = 20;
+
+// if the game freezes for a while dont try and catch up on all of the ticks at once
+pub const MAX_TICKS_PER_UPDATE: u32 = 10;
+
+// chunks are split into sections this tall, and each section gets this many random ticks every tick
+pub const CHUNK_SECTION_HEIGHT: usize = 16;
+pub const RANDOM_TICKS_PER_SECTION: u32 = 3;
+
+// mixed into the world seed so the random tick positions arnt related to world generation
+const RANDOM_TICK_SEED_SALT: u64 = 0x7469_636b_5f72_6e64;
+
+// what a scheduled tick does when it runs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TickType {
+    FallingBlock,
+    Fluid,
+}
+
+impl TickType {
+    pub fn to_int(&self) -> u8 {
+        match self {
+            TickType::FallingBlock => 0,
+            TickType::Fluid => 1,
+        }
+    }
+
+    pub fn from_int(id: u8) -> Option<TickType> {
+        match id {
+            0 => Some(TickType::FallingBlock),
+            1 => Some(TickType::Fluid),
+            _ => None,
+        }
+    }
+}
+
+// a tick for the block at a world position
+pub type ScheduledTick = ((i32, i16, i32), TickType);
+
+pub struct TickScheduler {
+    // how many ticks have run in this world
+    pub current_tick: u64,
+
+    // the ticks waiting to run, keyed by the tick they run on
+    // btree collections so the ticks always come out in the same order
+    pub scheduled_ticks: BTreeMap<u64, BTreeSet<ScheduledTick>>,
+
+    // ticks that were due while their chunk wasnt loaded, keyed by chunk id
+    pub waiting_ticks: HashMap<(i32, i32), BTreeSet<ScheduledTick>>,
+
+    // time that has passed but hasnt been turned into ticks yet
+    pub time_since_last_tick: Duration,
+    pub last_update: Instant,
+}
+
+impl TickScheduler {
+    pub fn new() -> TickScheduler {
+        TickScheduler {
+            current_tick: 0,
+            scheduled_ticks: BTreeMap::new(),
+            waiting_ticks: HashMap::new(),
+            time_since_last_tick: Duration::ZERO,
+            last_update: Instant::now(),
+        }
+    }
+
+    // how long each tick is
+    pub fn get_tick_time() -> Duration {
+        Duration::from_secs(1) / TICKS_PER_SECOND
+    }
+
+    // called every frame, gives how many ticks should be run now
+    pub fn update(&mut self) -> u32 {
+        let now: Instant = Instant::now();
+        let elapsed: Duration = now - self.last_update;
+        self.last_update = now;
+
+        self.add_elapsed_time(elapsed)
+    }
+
+    // add time that has passed and get how many whole ticks that makes, the leftover is kept for next time
+    pub fn add_elapsed_time(&mut self, elapsed: Duration) -> u32 {
+        let tick_time: Duration = TickScheduler::get_tick_time();
+        self.time_since_last_tick += elapsed;
+
+        let mut ticks: u32 = 0;
+        while self.time_since_last_tick >= tick_time {
+            self.time_since_last_tick -= tick_time;
+            ticks += 1;
+        }
+
+        // drop any extra ticks, the world just runs slower for a moment
+        ticks.min(MAX_TICKS_PER_UPDATE)
+    }
+
+    // run a tick for the block at this position in this many ticks from now (0 is this tick if it hasnt run yet)
+    pub fn schedule_tick(&mut self, block_pos: (i32, i16, i32), tick_type: TickType, delay_ticks: u64) {
+        self.scheduled_ticks
+            .entry(self.current_tick + delay_ticks)
+            .or_default()
+            .insert((block_pos, tick_type));
+    }
+
+    // remove and return every tick that is due by the current tick
+    pub fn take_due_ticks(&mut self) -> BTreeSet<ScheduledTick> {
+        let later_ticks: BTreeMap<u64, BTreeSet<ScheduledTick>> = self.scheduled_ticks.split_off(&(self.current_tick + 1));
+        let due_ticks: BTreeMap<u64, BTreeSet<ScheduledTick>> = std::mem::replace(&mut self.scheduled_ticks, later_ticks);
+
+        due_ticks.into_values().flatten().collect()
+    }
+
+    pub fn advance_tick(&mut self) {
+        self.current_tick += 1;
+    }
+
+    // keep a tick that couldnt run because its chunk isnt loaded
+    pub fn add_waiting_tick(&mut self, chunk_id: (i32, i32), scheduled_tick: ScheduledTick) {
+        self.waiting_ticks.entry(chunk_id).or_default().insert(scheduled_tick);
+    }
+
+    // a chunk has been loaded so any ticks waiting for it run on the next tick
+    pub fn wake_chunk(&mut self, chunk_id: (i32, i32)) {
+        if let Some(ticks) = self.waiting_ticks.remove(&chunk_id) {
+            for (block_pos, tick_type) in ticks {
+                self.schedule_tick(block_pos, tick_type, 1);
+            }
+        }
+    }
+
+    // every tick still to run (scheduled and waiting) with the tick it should run on, waiting ticks are due now
+    pub fn get_all_ticks(&self) -> Vec<(u64, ScheduledTick)> {
+        let mut all_ticks: Vec<(u64, ScheduledTick)> = Vec::new();
+
+        for (tick, scheduled_ticks) in self.scheduled_ticks.iter() {
+            for scheduled_tick in scheduled_ticks.iter() {
+                all_ticks.push((*tick, *scheduled_tick));
+            }
+        }
+
+        for waiting_ticks in self.waiting_ticks.values() {
+            for scheduled_tick in waiting_ticks.iter() {
+                all_ticks.push((self.current_tick, *scheduled_tick));
+            }
+        }
+
+        all_ticks
+    }
+
+    // the total number of ticks still to run
+    pub fn len(&self) -> usize {
+        self.scheduled_ticks.values().map(|ticks| ticks.len()).sum::<usize>()
+            + self.waiting_ticks.values().map(|ticks| ticks.len()).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scheduled_ticks.is_empty() && self.waiting_ticks.is_empty()
+    }
+}
+
+impl Default for TickScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/*
+the world positions in a chunk that get a random tick on this tick, a few in each section of the chunk
+they come from the seed, the tick and the chunk id so they are the same every time the world is run
+*/
+pub fn get_random_tick_positions(
+    world_seed: u64,
+    tick: u64,
+    chunk_id: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+) -> Vec<(i32, i16, i32)> {
+    let mut random: SeededRandom = SeededRandom::new(hash_position(
+        world_seed ^ RANDOM_TICK_SEED_SALT ^ mix_u64(tick),
+        chunk_id.0,
+        0,
+        chunk_id.1,
+    ));
+
+    let half_chunk_y: i32 = chunk_sizes.1 as i32 / 2;
+    let sections: usize = chunk_sizes.1.div_ceil(CHUNK_SECTION_HEIGHT);
+
+    let mut positions: Vec<(i32, i16, i32)> = Vec::with_capacity(sections * RANDOM_TICKS_PER_SECTION as usize);
+    for section in 0..sections {
+        let section_bottom: i32 = (section * CHUNK_SECTION_HEIGHT) as i32;
+        let section_top: i32 = (section_bottom + CHUNK_SECTION_HEIGHT as i32).min(chunk_sizes.1 as i32);
+
+        for _ in 0..RANDOM_TICKS_PER_SECTION {
+            let x: i32 = random.next_range(0, chunk_sizes.0 as i32);
+            let y: i32 = random.next_range(section_bottom, section_top);
+            let z: i32 = random.next_range(0, chunk_sizes.2 as i32);
+
+            positions.push((
+                (chunk_id.0 * chunk_sizes.0 as i32) + x,
+                (y - half_chunk_y) as i16,
+                (chunk_id.1 * chunk_sizes.2 as i32) + z,
+            ));
+        }
+    }
+
+    positions
+}
@@ -45,4 +45,16 @@ pub struct VertexUniforms {
 pub struct InstanceData {
     pub model_matrix: [[f32; 4]; 4],
     pub colour: [f32; 4],
+
+    // which faces of the block to draw (Block::visible_faces), a u32 since that is the smallest int a vertex attribute can be
+    pub visible_faces: u32,
+}
+
+impl InstanceData {
+    // how the instance buffer is laid out for the vertex shader (vertex.wgsl), the offsets are worked out from the sizes so they have to match the fields
+    pub const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+        2 => Float32x4, 3 => Float32x4, 4 => Float32x4, 5 => Float32x4, // matrix
+        6 => Float32x4, // colour
+        7 => Uint32, // visible faces
+    ];
 }
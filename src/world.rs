@@ -1,12 +1,82 @@
-use crate::{character::*, chunk::*, file_system::*, renderer::*};
+use crate::{
+    biome::*,
+    block::*,
+    block_type::*,
+    block_updates::*,
+    character::*,
+    chunk::{chunk_borders::*, chunk_functions::*, decorate_chunks::*, *},
+    chunk_load_queue::*,
+    chunk_loader::*,
+    error::*,
+    file_system::*,
+    fluid::*,
+    grass::*,
+    greedy_mesh::*,
+    raycast::*,
+    render_backend::*,
+    tick_scheduler::*,
+    types::*,
+    world_generator::*,
+};
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
     fs::File,
     io::{self, BufRead},
     path::PathBuf,
+    time::Instant,
 };
 
+// the ways getting or setting a block in the world can fail
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorldError {
+    // the chunk holding this block is not loaded (or pending), so its blocks cant be read or changed
+    ChunkNotLoaded((i32, i32)),
+
+    // the y value is above or below the chunks
+    OutOfBounds((i32, i16, i32)),
+
+    // this block type cant be put into the world (void is only used for calculations)
+    InvalidBlockType(BlockType),
+
+    // trying to break a block that cant be broken (bedrock)
+    Unbreakable((i32, i16, i32)),
+
+    // trying to break air
+    NoBlock((i32, i16, i32)),
+
+    // trying to place a block where there already is one
+    BlockOccupied((i32, i16, i32)),
+}
+
+impl fmt::Display for WorldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldError::ChunkNotLoaded(chunk_id) => {
+                write!(f, "Chunk ({}, {}) is not loaded", chunk_id.0, chunk_id.1)
+            }
+            WorldError::OutOfBounds(pos) => {
+                write!(f, "Block position ({}, {}, {}) is outside of the world", pos.0, pos.1, pos.2)
+            }
+            WorldError::InvalidBlockType(block_type) => {
+                write!(f, "Block type {:?} can not be placed in the world", block_type)
+            }
+            WorldError::Unbreakable(pos) => {
+                write!(f, "Block at ({}, {}, {}) can not be broken", pos.0, pos.1, pos.2)
+            }
+            WorldError::NoBlock(pos) => {
+                write!(f, "There is no block at ({}, {}, {})", pos.0, pos.1, pos.2)
+            }
+            WorldError::BlockOccupied(pos) => {
+                write!(f, "There is already a block at ({}, {}, {})", pos.0, pos.1, pos.2)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorldError {}
+
 // this struct will hold all of the Chunks as well as arrays of mobs
 pub struct World {
     // Use a hashmap to store currently loaded chunks
@@ -28,6 +98,22 @@ pub struct World {
     pub world_name: String,
     pub world_seed: u64,
 
+    // how new chunks are generated, the type is saved with the world so it always uses the same one
+    pub generator_type: WorldGeneratorType,
+    pub generator: Box<dyn WorldGenerator>,
+
+    // blocks from features like trees that reach into chunks that havent been generated or loaded yet
+    pub pending_block_writes: PendingBlockWrites,
+
+    // runs the world at a fixed tick rate, and keeps the block ticks (falling sand, flowing water) waiting to run
+    pub tick_scheduler: TickScheduler,
+
+    // reads and generates chunks on worker threads, none to load them on the main thread instead (like in tests)
+    pub chunk_loader: Option<ChunkLoader>,
+
+    // the chunks waiting to be loaded, nearest first and only as many each frame as its budget allows
+    pub chunk_load_queue: ChunkLoadQueue,
+
     pub render_distance: usize,
 
     pub chunk_sizes: (usize, usize, usize),
@@ -56,12 +142,64 @@ impl World {
             world_name,
             world_seed,
 
+            generator_type: WorldGeneratorType::default_noise(),
+            generator: WorldGeneratorType::default_noise().create_generator(),
+
+            pending_block_writes: PendingBlockWrites::new(),
+
+            tick_scheduler: TickScheduler::new(),
+
+            chunk_loader: None,
+
+            // everything is loaded straight away unless the game sets a budget
+            chunk_load_queue: ChunkLoadQueue::new(ChunkLoadBudget::unlimited()),
+
             render_distance,
 
             chunk_sizes,
         }
     }
 
+    // change how new chunks in this world are generated
+    pub fn set_generator(&mut self, generator_type: WorldGeneratorType) {
+        self.generator = generator_type.create_generator();
+        self.generator_type = generator_type;
+    }
+
+    // start loading chunks on worker threads, after the world info is read so they use the right seed and generator
+    pub fn start_chunk_loader(&mut self, file_system: &FileSystem, worker_count: usize) {
+        self.chunk_loader = Some(ChunkLoader::new(
+            worker_count,
+            file_system,
+            &self.generator_type,
+            self.world_seed,
+            self.chunk_sizes,
+        ));
+    }
+
+    // stop the workers, any chunks they were still loading are thrown away
+    pub fn stop_chunk_loader(&mut self) {
+        self.chunk_loader = None;
+    }
+
+    // load the chunks closest to the character (and in front of it) first
+    pub fn set_chunk_load_centre(&mut self, character: &Character) {
+        let look_direction: FPosition = character.get_look_direction();
+        self.chunk_load_queue.set_centre(character.chunk_position, Some((look_direction.x, look_direction.z)));
+    }
+
+    pub fn get_chunk_load_metrics(&self) -> ChunkLoadMetrics {
+        let (loading_count, thrown_away_count): (usize, usize) = match self.chunk_loader.as_ref() {
+            Some(chunk_loader) => (chunk_loader.get_loading_count(), chunk_loader.get_thrown_away_count()),
+            None => (0, 0),
+        };
+
+        // the chunks the workers had when they were cancelled only count once they are thrown away, since they can be wanted again before then
+        let mut metrics: ChunkLoadMetrics = self.chunk_load_queue.get_metrics(loading_count);
+        metrics.total_cancelled += thrown_away_count;
+        metrics
+    }
+
     // if the player has changed chunks this frame update the chunks around them
     pub fn get_chunks_around_character(
         &mut self,
@@ -103,7 +241,7 @@ impl World {
 
     pub fn update_chunks_around_character(
         &mut self,
-        renderer: &Renderer,
+        render_backend: &dyn RenderBackend,
         file_system: &mut FileSystem,
         chunks_to_load: HashSet<(i32, i32)>,
     ) {
@@ -112,16 +250,24 @@ impl World {
         // get the chunks that are in chunks_to_load but not in the chunks
         // these will need to be loaded
         let load = chunks_to_load.difference(&chunks_hashset);
-        for (x, z) in load {
-            // also check that it isnt in the pending chunks
-            if !self.pending_chunks.contains_key(&(*x, *z)) {
-                // load this chunk (i know for sure it isnt contained in the hashmap so i can just insert it)
-                let mut c: Chunk = Chunk::new(*x, *z, -1, renderer);
-                c.load_chunk(file_system, renderer, self.chunk_sizes, &mut self.created_chunks);
-
-                // add the chunk to pending
-                self.pending_chunks.insert((*x, *z), c);
+        for chunk_id in load {
+            // also check that it isnt in the pending chunks or already being loaded
+            let is_loading: bool = match self.chunk_loader.as_ref() {
+                Some(chunk_loader) => chunk_loader.is_loading(*chunk_id),
+                None => false,
+            };
+            if self.pending_chunks.contains_key(chunk_id) || is_loading {
+                continue;
             }
+
+            // it is loaded when it gets to the front of the queue
+            self.chunk_load_queue.queue_chunk(*chunk_id);
+        }
+
+        // chunks that were waiting to load but are out of range now arnt needed any more
+        self.chunk_load_queue.cancel_chunks_not_in(&chunks_to_load);
+        if let Some(chunk_loader) = self.chunk_loader.as_mut() {
+            chunk_loader.cancel_chunks_not_in(&chunks_to_load);
         }
 
 
@@ -130,9 +276,18 @@ impl World {
         let unload = chunks_hashset.difference(&chunks_to_load);
         for (x, z) in unload {
             // remove this chunk and save it to a file
-            self.remove_chunk((*x, *z), file_system);
+            if let Err(e) = self.remove_chunk((*x, *z), file_system) {
+                eprintln!("{}, chunk ({}, {}) is kept loaded so it can be saved again later", e, x, z);
+                continue;
+            }
+
+            // the chunks next to it are now next to void, so their walls facing it are hidden
+            self.update_neighbour_edges((*x, *z));
         }
 
+        // start on the chunks that need loading
+        self.load_queued_chunks(render_backend, file_system);
+
 
         /*
         // now go through the chunks loaded and match them to this array.
@@ -179,13 +334,79 @@ impl World {
         */
     }
 
-    // takes in the filesystem, loads the file where all of the chunks that have been created live and writes them to the hashmap
-    pub fn load_created_chunks_file(&mut self, my_file_system: &mut FileSystem) {
+    /*
+    read the seed and generator this world was created with from WorldInfo.txt so reopening a world keeps generating it the same way
+    if the world is new the current settings are written to the file instead
+    a world that already has chunks but no generator was made before generators were saved, when every world was flat
+    an error if the file cant be read or written, since the world would be generated differently without it
+    (a broken file is never written over, so the seed and generator in it can still be fixed by hand)
+    */
+    pub fn load_world_info(&mut self, file_system: &mut FileSystem) -> Result<(), GameError> {
+        match file_system.read_world_info()? {
+            Some((world_seed, generator_type)) => {
+                self.world_seed = world_seed;
+                self.set_generator(generator_type);
+            }
+            None => {
+                if file_system.has_created_chunks() {
+                    self.set_generator(WorldGeneratorType::Flat);
+                }
+                file_system.save_world_info(self.world_seed, &self.generator_type)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // read the block writes that were still waiting for their chunks when the world was last closed
+    pub fn load_pending_block_writes(&mut self, file_system: &mut FileSystem) -> Result<(), GameError> {
+        self.pending_block_writes = file_system.read_pending_block_writes(self.chunk_sizes)?;
+        Ok(())
+    }
+
+    // read the block ticks that were still waiting to run when the world was last closed
+    pub fn load_scheduled_ticks(&mut self, file_system: &mut FileSystem) -> Result<(), GameError> {
+        self.tick_scheduler = file_system.read_scheduled_ticks()?;
+        Ok(())
+    }
+
+    /*
+    put any pending block writes for chunks that are already loaded straight into them
+    they use set block so the blocks around them are updated too, and follow the same rules as when
+    they are put into a chunk as it is generated, so they only go into air (or logs into leaves)
+    */
+    pub fn apply_pending_block_writes(&mut self) {
+        for chunk_id in self.pending_block_writes.get_chunk_ids() {
+            self.apply_chunk_block_writes(chunk_id);
+        }
+    }
+
+    // the same as apply_pending_block_writes for just one chunk, nothing happens if it isnt loaded
+    pub fn apply_chunk_block_writes(&mut self, chunk_id: (i32, i32)) {
+        if self.get_chunk(chunk_id).is_none() {
+            return;
+        }
+
+        for (block_pos, block_type) in self.pending_block_writes.take_chunk_writes(chunk_id) {
+            if let Ok(current_block_type) = self.get_block(block_pos.0, block_pos.1, block_pos.2) {
+                if can_decoration_replace(current_block_type, block_type) {
+                    // the chunk is loaded and the block is in the world height so this cant fail
+                    let _ = self.set_block(block_pos.0, block_pos.1, block_pos.2, block_type);
+                }
+            }
+        }
+    }
+
+    /*
+    takes in the filesystem, loads the file where all of the chunks that have been created live and writes them to the hashset
+    an error if the file cant be read, then none of its chunks are added
+    */
+    pub fn load_created_chunks_file(&mut self, my_file_system: &mut FileSystem) -> Result<(), GameError> {
         // get the path to the ChunksCreated.txt file
         let mut chunks_created_path: PathBuf = my_file_system.my_world_directory.clone();
         chunks_created_path.push("ChunksCreated.txt");
 
-        let chunks_created_file: File = File::open(chunks_created_path).unwrap();
+        let chunks_created_file: File = File::open(&chunks_created_path)?;
 
         let reader: io::BufReader<File> = io::BufReader::new(chunks_created_file);
 
@@ -194,41 +415,33 @@ impl World {
         // read the first line to get the total created chunks
         let line1: String = lines
             .next()
-            .expect("Failed to get next line in ChunksCreated.txt, as it is at EOF")
-            .unwrap();
+            .ok_or_else(|| GameError::MalformedFile(chunks_created_path.clone(), 1, String::from("the file is empty")))??;
 
-        let total_created_chunks: i32 = line1
-            .split_whitespace()
-            .last()
-            .expect("Failed to get last element in split whitespace string (ChunksCreated.txt)")
-            .parse::<i32>()
-            .unwrap();
+        let total_created_chunks: usize = parse_line_value(line1.split_whitespace().last().unwrap_or(""), &chunks_created_path, 1)?;
 
         // skip the next 2 lines
         lines.next();
         lines.next();
 
-        // now read the next totalCreatedChunks lines and insert them into the hashmap
-        let mut line: String;
-        let mut x: i32 = 0;
-        let mut z: i32 = 0;
-        for _ in 0..total_created_chunks {
-            line = lines
-                .next()
-                .expect("Failed to get next line in ChunksCreated.txt, as it is at EOF")
-                .unwrap();
+        // now read the next totalCreatedChunks lines, they are only added once they have all been read
+        let mut created_chunks: Vec<(i32, i32)> = Vec::with_capacity(total_created_chunks);
+        for chunk_index in 0..total_created_chunks {
+            let line_number: usize = chunk_index + 4;
+            let line: String = lines.next().ok_or_else(|| {
+                GameError::MalformedFile(
+                    chunks_created_path.clone(),
+                    line_number,
+                    format!("the file ended after {} of {} chunks", chunk_index, total_created_chunks),
+                )
+            })??;
 
             let mut split_line = line.split_whitespace();
-            x = split_line.next()
-            .expect("Failed to get next element of split whitespace line while reading ChunksCreated.txt")
-            .parse::<i32>()
-            .unwrap();
-
-            z = split_line.next()
-            .expect("Failed to get next element of split whitespace line while reading ChunksCreated.txt")
-            .parse::<i32>()
-            .unwrap();
+            let x: i32 = parse_line_value(split_line.next().unwrap_or(""), &chunks_created_path, line_number)?;
+            let z: i32 = parse_line_value(split_line.next().unwrap_or(""), &chunks_created_path, line_number)?;
+            created_chunks.push((x, z));
+        }
 
+        for (x, z) in created_chunks {
             // insert these into the hashset and check if it is a dupe
             if !self.created_chunks.insert((x, z)) {
                 // if insert returns false then it was already in the hashmap
@@ -238,31 +451,602 @@ impl World {
                 );
             }
         }
+
+        Ok(())
+    }
+
+    // if ChunksCreated.txt cant be read the created chunks are worked out from the chunks that have been saved instead
+    pub fn rebuild_created_chunks(&mut self, file_system: &FileSystem) -> Result<(), GameError> {
+        self.created_chunks.extend(file_system.get_saved_chunk_ids(self.chunk_sizes)?);
+        Ok(())
+    }
+
+    // takes a world block position and gives the id of the chunk it is in (this works for negative positions too)
+    pub fn get_block_chunk_id(&self, world_x: i32, world_z: i32) -> (i32, i32) {
+        (
+            world_x.div_euclid(self.chunk_sizes.0 as i32),
+            world_z.div_euclid(self.chunk_sizes.2 as i32),
+        )
+    }
+
+    // check the y value is within the chunks, chunks go from -half to half - 1 in y
+    pub fn is_in_world_height(&self, world_y: i16) -> bool {
+        let half_chunk_y: i32 = self.chunk_sizes.1 as i32 / 2;
+        (world_y as i32) >= -half_chunk_y && (world_y as i32) < half_chunk_y
+    }
+
+    // get a loaded chunk, this also checks the pending chunks since they have all of their blocks
+    pub fn get_chunk(&self, chunk_id: (i32, i32)) -> Option<&Chunk> {
+        match self.chunks.get(&chunk_id) {
+            Some(chunk) => Some(chunk),
+            None => self.pending_chunks.get(&chunk_id),
+        }
+    }
+
+    pub fn get_chunk_mut(&mut self, chunk_id: (i32, i32)) -> Option<&mut Chunk> {
+        match self.chunks.get_mut(&chunk_id) {
+            Some(chunk) => Some(chunk),
+            None => self.pending_chunks.get_mut(&chunk_id),
+        }
+    }
+
+    // get the type of the block at this world position (air if there is no block there)
+    pub fn get_block(&self, world_x: i32, world_y: i16, world_z: i32) -> Result<BlockType, WorldError> {
+        if !self.is_in_world_height(world_y) {
+            return Err(WorldError::OutOfBounds((world_x, world_y, world_z)));
+        }
+
+        let chunk_id: (i32, i32) = self.get_block_chunk_id(world_x, world_z);
+        match self.get_chunk(chunk_id) {
+            Some(chunk) => Ok(chunk.get_block_type((world_x, world_y, world_z))),
+            None => Err(WorldError::ChunkNotLoaded(chunk_id)),
+        }
+    }
+
+    // set the block at this world position, setting it to air removes the block
+    // this keeps the chunks alive blocks and instances in sync and the instances get sent to the gpu on the chunks next update
+    // the 6 blocks around it have their touching air updated too, since this block may have covered or uncovered them
+    pub fn set_block(
+        &mut self,
+        world_x: i32,
+        world_y: i16,
+        world_z: i32,
+        block_type: BlockType,
+    ) -> Result<(), WorldError> {
+        if block_type == BlockType::Void {
+            return Err(WorldError::InvalidBlockType(block_type));
+        }
+        if !self.is_in_world_height(world_y) {
+            return Err(WorldError::OutOfBounds((world_x, world_y, world_z)));
+        }
+
+        let chunk_id: (i32, i32) = self.get_block_chunk_id(world_x, world_z);
+        if self.get_chunk(chunk_id).is_none() {
+            return Err(WorldError::ChunkNotLoaded(chunk_id));
+        }
+
+        let old_block_type: BlockType = self.get_block(world_x, world_y, world_z)?;
+
+        let mut block: Block = Block::new(block_type, world_x, world_y, world_z);
+        if block_type != BlockType::Air {
+            block.set_visible_faces(self.get_visible_faces((world_x, world_y, world_z), block_type));
+        }
+
+        // already checked that the chunk is loaded
+        self.get_chunk_mut(chunk_id).unwrap().set_block(block);
+
+        self.update_neighbours_touching_air((world_x, world_y, world_z));
+
+        // water next to this might need to flow now, and sand on or next to it might need to fall
+        if old_block_type != block_type {
+            self.notify_block_changed((world_x, world_y, world_z), old_block_type, block_type);
+        }
+
+        Ok(())
+    }
+
+    /*
+    a block has changed, so schedule ticks for it and the blocks touching it
+    dynamic blocks get a falling block tick, and if there is water around everything gets a fluid tick
+    */
+    pub fn notify_block_changed(&mut self, block_pos: (i32, i16, i32), old_block_type: BlockType, new_block_type: BlockType) {
+        let mut positions: Vec<(i32, i16, i32)> = vec![block_pos];
+        positions.extend(World::get_neighbour_positions(block_pos));
+
+        let mut is_fluid_nearby: bool = old_block_type.is_fluid() || new_block_type.is_fluid();
+
+        for update_pos in positions.iter() {
+            if let Ok(block_type) = self.get_block(update_pos.0, update_pos.1, update_pos.2) {
+                if block_type.is_dynamic() {
+                    self.tick_scheduler.schedule_tick(*update_pos, TickType::FallingBlock, FALLING_BLOCK_DELAY);
+                }
+                if block_type.is_fluid() {
+                    is_fluid_nearby = true;
+                }
+            }
+        }
+
+        if is_fluid_nearby {
+            for update_pos in positions {
+                self.tick_scheduler.schedule_tick(update_pos, TickType::Fluid, FLUID_TICK_DELAY);
+            }
+        }
+    }
+
+    // run however many ticks are due since the last frame, this is called every frame
+    pub fn update_ticks(&mut self) {
+        let ticks: u32 = self.tick_scheduler.update();
+        for _ in 0..ticks {
+            self.tick();
+        }
+    }
+
+    /*
+    run one world tick
+    first the scheduled ticks that are due, the blocks are changed with set block which schedules the next ticks
+    so falling blocks keep falling and water keeps flowing until it settles
+    ticks for blocks in chunks that arnt loaded wait until the chunk is loaded again
+    then a few random blocks in every loaded chunk get a random tick
+    */
+    pub fn tick(&mut self) {
+        let due_ticks: BTreeSet<ScheduledTick> = self.tick_scheduler.take_due_ticks();
+
+        let mut falling_positions: BTreeSet<(i32, i16, i32)> = BTreeSet::new();
+        let mut fluid_positions: HashSet<(i32, i16, i32)> = HashSet::new();
+        for (block_pos, tick_type) in due_ticks {
+            let chunk_id: (i32, i32) = self.get_block_chunk_id(block_pos.0, block_pos.2);
+            if self.get_chunk(chunk_id).is_none() {
+                self.tick_scheduler.add_waiting_tick(chunk_id, (block_pos, tick_type));
+                continue;
+            }
+
+            match tick_type {
+                TickType::FallingBlock => {
+                    falling_positions.insert(block_pos);
+                }
+                TickType::Fluid => {
+                    fluid_positions.insert(block_pos);
+                }
+            }
+        }
+
+        if !falling_positions.is_empty() {
+            let block_writes: Vec<BlockWrite> = simulate_falling_blocks(&falling_positions, |block_pos: (i32, i16, i32)| {
+                self.get_block(block_pos.0, block_pos.1, block_pos.2).ok()
+            });
+            self.apply_tick_block_writes(block_writes);
+        }
+
+        if !fluid_positions.is_empty() {
+            let block_writes: Vec<BlockWrite> = simulate_fluid_tick(&fluid_positions, |block_pos: (i32, i16, i32)| {
+                self.get_block(block_pos.0, block_pos.1, block_pos.2).ok()
+            });
+            self.apply_tick_block_writes(block_writes);
+        }
+
+        // sorted so the random ticks always run in the same order
+        let mut chunk_ids: Vec<(i32, i32)> = self.chunks.keys().cloned().collect();
+        chunk_ids.sort();
+        for chunk_id in chunk_ids {
+            for block_pos in get_random_tick_positions(self.world_seed, self.tick_scheduler.current_tick, chunk_id, self.chunk_sizes) {
+                self.random_tick_block(block_pos);
+            }
+        }
+
+        self.tick_scheduler.advance_tick();
+    }
+
+    // change the blocks worked out by a tick
+    fn apply_tick_block_writes(&mut self, block_writes: Vec<BlockWrite>) {
+        for (block_pos, block_type) in block_writes {
+            // the positions were all read from loaded chunks inside of the world so this cant fail
+            let _ = self.set_block(block_pos.0, block_pos.1, block_pos.2, block_type);
+        }
+    }
+
+    // give the block at this position a random tick, grass is the only block that does anything on one
+    pub fn random_tick_block(&mut self, block_pos: (i32, i16, i32)) {
+        let block_type: BlockType = match self.get_block(block_pos.0, block_pos.1, block_pos.2) {
+            Ok(block_type) => block_type,
+            Err(_) => return,
+        };
+
+        if block_type == BlockType::Grass {
+            // the spread can reach into the chunk next to this one, blocks in chunks that arnt loaded are left alone
+            let block_writes: Vec<BlockWrite> =
+                get_grass_random_tick(block_pos, self.world_seed, self.tick_scheduler.current_tick, |block_pos: (i32, i16, i32)| {
+                    self.get_block(block_pos.0, block_pos.1, block_pos.2).ok()
+                });
+            self.apply_tick_block_writes(block_writes);
+        }
+    }
+
+    // break the block at this position, leaving air behind and returning what was there
+    pub fn break_block(&mut self, world_x: i32, world_y: i16, world_z: i32) -> Result<BlockType, WorldError> {
+        let block_type: BlockType = self.get_block(world_x, world_y, world_z)?;
+
+        match block_type {
+            BlockType::Air => return Err(WorldError::NoBlock((world_x, world_y, world_z))),
+            BlockType::Bedrock => return Err(WorldError::Unbreakable((world_x, world_y, world_z))),
+            _ => {}
+        }
+
+        self.set_block(world_x, world_y, world_z, BlockType::Air)?;
+
+        Ok(block_type)
+    }
+
+    // place a block at this position, there cant already be a solid block there (water is replaced)
+    pub fn place_block(
+        &mut self,
+        world_x: i32,
+        world_y: i16,
+        world_z: i32,
+        block_type: BlockType,
+    ) -> Result<(), WorldError> {
+        if block_type == BlockType::Air {
+            return Err(WorldError::InvalidBlockType(block_type));
+        }
+
+        // blocks can be placed into air or water
+        let current_block_type: BlockType = self.get_block(world_x, world_y, world_z)?;
+        if current_block_type != BlockType::Air && !current_block_type.is_fluid() {
+            return Err(WorldError::BlockOccupied((world_x, world_y, world_z)));
+        }
+
+        self.set_block(world_x, world_y, world_z, block_type)
+    }
+
+    // which biome this column of the world is in, this doesnt need the chunk to be loaded since it comes from the seed
+    // none if the worlds generator doesnt use biomes (eg. flat worlds)
+    pub fn biome_at(&self, world_x: i32, world_z: i32) -> Option<Biome> {
+        self.generator.get_biome(world_x, world_z, self.world_seed)
+    }
+
+    // find the first non transparent block along a ray, within reach, through any loaded chunk
+    // the ray only stops at chunks that arnt loaded, above and below the world is air so it can go through to the blocks past it
+    pub fn raycast(&self, origin: FPosition, direction: FPosition, reach: f32) -> Option<RaycastHit> {
+        raycast_blocks(origin, direction, reach, |block_pos: (i32, i16, i32)| {
+            match self.get_block(block_pos.0, block_pos.1, block_pos.2) {
+                Ok(block_type) => Some(block_type),
+                Err(WorldError::OutOfBounds(..)) => Some(BlockType::Air),
+                Err(_) => None,
+            }
+        })
+    }
+
+    // the block the character is looking at (under the crosshair) if there is one within their reach
+    pub fn get_block_looked_at(&self, character: &Character) -> Option<RaycastHit> {
+        self.raycast(character.position, character.get_look_direction(), character.reach)
+    }
+
+    // the 6 positions touching this block
+    pub fn get_neighbour_positions(block_pos: (i32, i16, i32)) -> [(i32, i16, i32); 6] {
+        let (x, y, z) = block_pos;
+        [
+            (x, y + 1, z), // top
+            (x, y - 1, z), // bottom
+            (x + 1, y, z), // right
+            (x - 1, y, z), // left
+            (x, y, z + 1), // back
+            (x, y, z - 1), // front
+        ]
+    }
+
+    // after a block has changed re check if the blocks around it are touching air
+    // this is instead of re running the touching air compute shader on the whole chunk, the neighbours can be in other chunks
+    pub fn update_neighbours_touching_air(&mut self, block_pos: (i32, i16, i32)) {
+        for neighbour in World::get_neighbour_positions(block_pos) {
+            if !self.is_in_world_height(neighbour.1) {
+                continue;
+            }
+
+            let chunk_id: (i32, i32) = self.get_block_chunk_id(neighbour.0, neighbour.2);
+            let neighbour_type: BlockType = self.get_block(neighbour.0, neighbour.1, neighbour.2).unwrap_or(BlockType::Void);
+            let visible_faces: u8 = self.get_visible_faces(neighbour, neighbour_type);
+
+            // if the chunk isnt loaded there is nothing to update
+            if let Some(chunk) = self.get_chunk_mut(chunk_id) {
+                chunk.set_block_visible_faces(neighbour, visible_faces);
+            }
+        }
+    }
+
+    // check the 6 blocks around this position and see which faces of a block of this type would be showing
+    // blocks in chunks that arnt loaded count as void, the same as the touching air compute shader
+    pub fn get_visible_faces(&self, block_pos: (i32, i16, i32), block_type: BlockType) -> u8 {
+        let mut visible_faces: u8 = 0;
+
+        for face in BlockFace::ALL {
+            let offset: (i32, i16, i32) = face.offset();
+            let neighbour: (i32, i16, i32) = (block_pos.0 + offset.0, block_pos.1 + offset.1, block_pos.2 + offset.2);
+
+            if block_type.is_face_visible(self.get_block(neighbour.0, neighbour.1, neighbour.2).unwrap_or(BlockType::Void)) {
+                visible_faces |= face.bit();
+            }
+        }
+
+        visible_faces
+    }
+
+    // get the edge layers of the loaded chunks around this one, so it can be checked for touching air against them
+    pub fn get_chunk_borders(&self, chunk_id: (i32, i32)) -> ChunkBorders {
+        let mut chunk_borders: ChunkBorders = ChunkBorders::new();
+
+        for side in ChunkSide::all() {
+            if let Some(neighbour) = self.get_chunk(side.get_neighbour_id(chunk_id)) {
+                chunk_borders.set_border(side, neighbour.get_edge_layer(side.opposite(), self.chunk_sizes));
+            }
+        }
+
+        chunk_borders
+    }
+
+    // re check if the blocks on this side of a chunk are touching air, eg. after the chunk next to it is loaded or unloaded
+    pub fn update_chunk_edge_touching_air(&mut self, chunk_id: (i32, i32), side: ChunkSide) {
+        if self.get_chunk(chunk_id).is_none() {
+            return;
+        }
+
+        let mut edge_visible_faces: Vec<((i32, i16, i32), u8)> = Vec::new();
+        for block_pos in get_edge_positions(chunk_id, side, self.chunk_sizes) {
+            // already checked the chunk is loaded and the edge is in the world height
+            let block_type: BlockType = self.get_block(block_pos.0, block_pos.1, block_pos.2).unwrap();
+            if block_type != BlockType::Air {
+                edge_visible_faces.push((block_pos, self.get_visible_faces(block_pos, block_type)));
+            }
+        }
+
+        let chunk: &mut Chunk = self.get_chunk_mut(chunk_id).unwrap();
+        for (block_pos, visible_faces) in edge_visible_faces {
+            chunk.set_block_visible_faces(block_pos, visible_faces);
+        }
+    }
+
+    // re check the edges of the 4 chunks around this one that face it
+    pub fn update_neighbour_edges(&mut self, chunk_id: (i32, i32)) {
+        for side in ChunkSide::all() {
+            self.update_chunk_edge_touching_air(side.get_neighbour_id(chunk_id), side.opposite());
+        }
+    }
+
+    /*
+    universal remove chunk function so that i remove it correctly and save it to a file without needing to do this myself
+    if it cant be saved it is kept loaded so the changes to it arnt lost, and it is tried again the next time it is removed
+    a pending chunk is saved and removed too, it is already in the created chunks and can have block writes put in it
+    */
+    pub fn remove_chunk(&mut self, chunk_id: (i32, i32), file_system: &mut FileSystem) -> Result<(), GameError> {
+        let chunk: &Chunk = match self.get_chunk(chunk_id) {
+            Some(chunk) => chunk,
+            None => {
+                // if the key doesnt match a value ill print this but not panic so i can save the rest
+                eprintln!("Failed to remove chunk with key {:?}", chunk_id);
+                return Ok(());
+            }
+        };
+
+        file_system.save_chunk_to_file(chunk, self.chunk_sizes)?;
+        if self.chunks.remove(&chunk_id).is_none() {
+            self.pending_chunks.remove(&chunk_id);
+        }
+        //println!("Removed Chunk ({}, {})", chunk_id.0, chunk_id.1);
+        Ok(())
     }
 
-    // TODO: #63 set world block from world coords
 
-    // TODO: #64 Get world block from world coords
 
-    // TODO: #65 Place block function
+    // rebuild the greedy meshes of the chunks that have changed, only when the renderer is using greedy meshing
+    pub fn update_chunk_meshes(&mut self, render_backend: &dyn RenderBackend) {
+        if render_backend.get_meshing_mode() != MeshingMode::Greedy {
+            return;
+        }
 
-    // TODO: #66 Break block function
+        for chunk in self.chunks.values_mut() {
+            if chunk.mesh_outdated {
+                chunk.update_mesh(render_backend, self.chunk_sizes);
+            }
+        }
+    }
 
-    // universal remove chunk function so that i remove it correctly and save it to a file without needing to do this myself
-    pub fn remove_chunk(&mut self, chunk_id: (i32, i32), file_system: &mut FileSystem) {
-        // remove the chunk from the hashmap and return it
-        if let Some(chunk) = self.chunks.remove(&chunk_id) {
-            file_system.save_chunk_to_file(chunk, self.chunk_sizes);
-            //println!("Removed Chunk ({}, {})", chunk_id.0, chunk_id.1);
+    /*
+    put a chunk into the pending chunks once its blocks have been read or generated
+    this does the rest of loading it, which needs the chunks around it so it is always done on the main thread
+    */
+    fn add_loaded_chunk(
+        &mut self,
+        chunk_id: (i32, i32),
+        temp_chunk_vec: Vec<Vec<Vec<Block>>>,
+        biomes: Vec<Vec<Biome>>,
+        render_backend: &dyn RenderBackend,
+    ) {
+        // the edges of the chunks next to it that are already loaded
+        let chunk_borders: ChunkBorders = self.get_chunk_borders(chunk_id);
+
+        // (i know for sure it isnt contained in the hashmap so i can just insert it)
+        let mut c: Chunk = Chunk::new(chunk_id.0, chunk_id.1, -1, render_backend);
+        c.finish_loading_chunk(
+            temp_chunk_vec,
+            biomes,
+            render_backend,
+            self.chunk_sizes,
+            &mut self.pending_block_writes,
+            &chunk_borders,
+        );
+
+        // add the chunk to pending
+        self.pending_chunks.insert(chunk_id, c);
+
+        // the walls of the chunks next to it were checked against void, so they need checking against this chunk now
+        self.update_neighbour_edges(chunk_id);
+
+        // ticks that were waiting for this chunk can run again
+        self.tick_scheduler.wake_chunk(chunk_id);
+    }
+
+    /*
+    load the chunks at the front of the queue, this is called every frame
+    on the main thread they are read or generated here, until the budget for this frame is spent
+    with the workers the finished chunks are added to the world until the budget is spent, and the nearest queued chunks are sent to the workers
+    */
+    pub fn load_queued_chunks(&mut self, render_backend: &dyn RenderBackend, file_system: &mut FileSystem) {
+        let frame_start: Instant = Instant::now();
+        let budget: ChunkLoadBudget = self.chunk_load_queue.budget;
+        let mut chunks_loaded: usize = 0;
+
+        if self.chunk_loader.is_some() {
+            // add the chunks the workers have finished since the last frame
+            while !budget.is_spent(chunks_loaded, frame_start) {
+                let loaded_chunk: LoadedChunk = match self.chunk_loader.as_mut().and_then(|chunk_loader| chunk_loader.take_loaded_chunk()) {
+                    Some(loaded_chunk) => loaded_chunk,
+                    None => break,
+                };
+                self.add_worker_chunk(loaded_chunk, render_backend);
+                chunks_loaded += 1;
+            }
+
+            // the corrupted chunks go back in the queue to be generated again
+            self.quarantine_corrupted_chunks(file_system);
+
+            // keep the workers busy with the next nearest chunks
+            self.request_queued_chunks(false);
         } else {
-            // if the key doesnt match a value ill print this but not panic so i can save the rest
-            eprintln!("Failed to remove chunk with key {:?}", chunk_id);
+            while !budget.is_spent(chunks_loaded, frame_start) {
+                let chunk_id: (i32, i32) = match self.chunk_load_queue.pop_next_chunk() {
+                    Some(chunk_id) => chunk_id,
+                    None => break,
+                };
+                self.load_chunk_now(chunk_id, render_backend, file_system);
+                chunks_loaded += 1;
+            }
         }
+
+        // new chunks can have trees that reach into chunks that are already loaded
+        if chunks_loaded > 0 {
+            self.apply_pending_block_writes();
+        }
+
+        self.chunk_load_queue.record_frame(chunks_loaded, frame_start);
     }
 
+    /*
+    send the nearest queued chunks to the workers
+    only a few are sent for each worker at a time, so the rest stay in the queue where they can still be reordered or cancelled
+    unless every queued chunk is wanted now
+    */
+    fn request_queued_chunks(&mut self, request_all: bool) {
+        if self.chunk_loader.is_none() {
+            return;
+        }
+
+        while request_all || self.chunk_loader.as_ref().is_some_and(ChunkLoader::has_room_for_requests) {
+            let chunk_id: (i32, i32) = match self.chunk_load_queue.pop_next_chunk() {
+                Some(chunk_id) => chunk_id,
+                None => break,
+            };
+
+            // the worker runs the touching air pass against the chunks around it as they are now
+            let chunk_borders: ChunkBorders = self.get_chunk_borders(chunk_id);
+            let is_created: bool = self.created_chunks.contains(&chunk_id);
+            if let Some(chunk_loader) = self.chunk_loader.as_mut() {
+                chunk_loader.request_chunk(chunk_id, is_created, chunk_borders);
+            }
+        }
+    }
+
+    // read or generate a chunk on the main thread and put it in the world
+    fn load_chunk_now(&mut self, chunk_id: (i32, i32), render_backend: &dyn RenderBackend, file_system: &mut FileSystem) {
+        let is_created: bool = self.created_chunks.contains(&chunk_id);
+        let temp_chunk_vec: Vec<Vec<Vec<Block>>> = match read_or_generate_chunk(
+            file_system,
+            chunk_id,
+            is_created,
+            self.chunk_sizes,
+            self.generator.as_ref(),
+            self.world_seed,
+            &mut self.pending_block_writes,
+        ) {
+            Ok(temp_chunk_vec) => temp_chunk_vec,
+            // left out of the world so it isnt saved over, it is tried again when the chunks around the character change
+            Err(e) => {
+                eprintln!("{}, it wont be loaded", e);
+                return;
+            }
+        };
+        if !is_created {
+            self.created_chunks.insert(chunk_id);
+        }
+
+        // the biomes are worked out from the seed again rather than saved, so loaded chunks get them too
+        let biomes: Vec<Vec<Biome>> = get_chunk_biomes(chunk_id, self.chunk_sizes, self.generator.as_ref(), self.world_seed);
+        self.add_loaded_chunk(chunk_id, temp_chunk_vec, biomes, render_backend);
+    }
+
+    // send every queued chunk to the workers, then wait for them and add them to the world (for tests, or when the game has to have them now)
+    pub fn wait_for_loading_chunks(&mut self, render_backend: &dyn RenderBackend, file_system: &mut FileSystem) {
+        loop {
+            self.request_queued_chunks(true);
+
+            while let Some(loaded_chunk) = self.chunk_loader.as_mut().and_then(|chunk_loader| chunk_loader.wait_for_loaded_chunk()) {
+                self.add_worker_chunk(loaded_chunk, render_backend);
+            }
+
+            // any that were corrupted are queued again, so wait for those too
+            if !self.chunk_loader.as_ref().is_some_and(ChunkLoader::has_corrupted_chunks) {
+                break;
+            }
+            self.quarantine_corrupted_chunks(file_system);
+        }
+
+        self.apply_pending_block_writes();
+    }
+
+    /*
+    quarantine the chunks the workers found were corrupted, it is done here since the main thread is the only one that writes to the region files
+    then they are queued to be generated again, they arnt created chunks any more so the workers dont try to read them again
+    (one that couldnt be moved out of the way is still generated again and saved over, the same as on the main thread)
+    */
+    fn quarantine_corrupted_chunks(&mut self, file_system: &mut FileSystem) {
+        let corrupted_chunks: Vec<((i32, i32), GameError)> = match self.chunk_loader.as_mut() {
+            Some(chunk_loader) => chunk_loader.take_corrupted_chunks(),
+            None => return,
+        };
+
+        for (chunk_id, e) in corrupted_chunks {
+            quarantine_corrupted_chunk(file_system, &e, chunk_id, self.chunk_sizes);
+            self.created_chunks.remove(&chunk_id);
+            self.chunk_load_queue.queue_chunk(chunk_id);
+        }
+    }
 
+    fn add_worker_chunk(&mut self, loaded_chunk: LoadedChunk, render_backend: &dyn RenderBackend) {
+        // the decorations that reach into other chunks, and this chunk can now be read from its file next time
+        self.pending_block_writes.merge(loaded_chunk.pending_block_writes);
+        if loaded_chunk.is_generated {
+            self.created_chunks.insert(loaded_chunk.chunk_id);
+        }
+
+        // the worker has already done the touching air pass, so this only makes the buffers
+        let chunk_id: (i32, i32) = loaded_chunk.chunk_id;
+        let mut c: Chunk = Chunk::new(chunk_id.0, chunk_id.1, -1, render_backend);
+        c.fill_from_checked_blocks(loaded_chunk.temp_chunk_vec, loaded_chunk.biomes, render_backend, self.chunk_sizes);
+        self.pending_chunks.insert(chunk_id, c);
+
+        // the chunks next to it could have been loaded, unloaded or changed while the worker had it, so those walls are checked again
+        let chunk_borders: ChunkBorders = self.get_chunk_borders(chunk_id);
+        for side in ChunkSide::all() {
+            if chunk_borders.get_border(side) != loaded_chunk.chunk_borders.get_border(side) {
+                self.update_chunk_edge_touching_air(chunk_id, side);
+            }
+        }
+
+        // blocks from features in other chunks that reached into this one, set block updates the faces around them
+        self.apply_chunk_block_writes(chunk_id);
+
+        self.update_neighbour_edges(chunk_id);
+        self.tick_scheduler.wake_chunk(chunk_id);
+    }
 
-    pub fn update_pending_chunks(&mut self, renderer: &Renderer) {
+    pub fn update_pending_chunks(&mut self, render_backend: &dyn RenderBackend) {
         // go through the pending chunks vec and any that are valid now are put into chunks
         let current_pending_chunks: Vec<(i32, i32)> = self.pending_chunks.keys().cloned().collect();
         for chunk_ids in current_pending_chunks {
@@ -270,10 +1054,11 @@ impl World {
             let chunk: &mut Chunk = self.pending_chunks.get_mut(&chunk_ids).expect("could not get pending chunk");
 
             // call update so it can finish off its copy when ready
-            chunk.update(renderer);
+            chunk.update(render_backend);
 
             // if its capacity is enough remove it form pending and move it to chunks
-            if chunk.instance_capacity > chunk.instance_size {
+            // chunks without a gpu dont have any buffers to wait for
+            if chunk.instance_buffers.is_none() || chunk.instance_capacity > chunk.instance_size {
                 let move_chunk: Chunk = self.pending_chunks.remove(&chunk_ids).expect("could not remove pending chunk");
                 self.chunks.insert((move_chunk.chunk_id_x, move_chunk.chunk_id_z), move_chunk);
             }
@@ -0,0 +1,252 @@
+/*
+The version of the format a world is saved in, so a change to how worlds are saved doesnt silently break the worlds that are already saved
+the version is the "Format Version:" line in WorldInfo.txt, and region files and chunks have their own versions in their headers as well
+
+versions
+    1   every chunk is a Chunks/x_z.txt text file (worlds from before there were versions dont have the line at all)
+    2   chunks are compressed and saved in Regions/r_x_z.region files
+    3   region files have a checksum for each chunk, and chunks are never written over where they were before
+
+when a world is opened it is upgraded to the current version, after copying the old files into Backups/ in case anything goes wrong
+a world saved by a newer version of the game than this one cant be opened, instead of being read wrong or saved over
+*/
+
+use crate::{
+    block::*,
+    block_type::*,
+    chunk::chunk_functions::*,
+    chunk_compression::*,
+    error::*,
+    file_system::*,
+    region_file::*,
+};
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{self, create_dir_all},
+    io,
+    path::{Path, PathBuf},
+};
+
+// the version worlds are saved in by this version of the game
+pub const WORLD_FORMAT_VERSION: u32 = 3;
+
+// the version of worlds saved before the version was written down
+const FIRST_WORLD_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum WorldFormatError {
+    // the world was saved by a newer version of the game, (the worlds version, the newest this game can open)
+    NewerVersion(u32, u32),
+
+    // reading or writing the files while upgrading failed, the backup still has the old files
+    Io(io::Error),
+
+    // the version in WorldInfo.txt cant be read, so nothing is upgraded in case it is already the newest version
+    UnreadableVersion(Box<GameError>),
+}
+
+impl fmt::Display for WorldFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldFormatError::NewerVersion(version, current_version) => {
+                write!(
+                    f,
+                    "World was saved in format version {} by a newer version of the game, this version can only open up to version {}",
+                    version, current_version
+                )
+            }
+            WorldFormatError::Io(e) => {
+                write!(f, "Failed to upgrade world: {}", e)
+            }
+            WorldFormatError::UnreadableVersion(e) => {
+                write!(f, "Can not tell which format version the world is in: {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorldFormatError {}
+
+impl From<io::Error> for WorldFormatError {
+    fn from(e: io::Error) -> WorldFormatError {
+        WorldFormatError::Io(e)
+    }
+}
+
+// what was done to a world to upgrade it
+#[derive(Debug, PartialEq)]
+pub struct WorldUpgrade {
+    pub old_version: u32,
+    pub migrated_chunks: usize,
+    pub upgraded_regions: usize,
+
+    // where the old files were copied to, none if there werent any chunks to upgrade
+    pub backup_directory: Option<PathBuf>,
+}
+
+/*
+check the version of the world and upgrade it if it was saved by an older version, none if it is already the current version
+this is done before anything else is read from the world
+*/
+pub fn upgrade_world_format(
+    file_system: &mut FileSystem,
+    chunk_sizes: (usize, usize, usize),
+) -> Result<Option<WorldUpgrade>, WorldFormatError> {
+    let old_version: u32 = file_system
+        .read_world_format_version()
+        .map_err(|e| WorldFormatError::UnreadableVersion(Box::new(e)))?
+        .unwrap_or(FIRST_WORLD_FORMAT_VERSION);
+
+    if old_version > WORLD_FORMAT_VERSION {
+        return Err(WorldFormatError::NewerVersion(old_version, WORLD_FORMAT_VERSION));
+    }
+    if old_version == WORLD_FORMAT_VERSION {
+        return Ok(None);
+    }
+
+    let text_chunk_ids: Vec<(i32, i32)> = file_system.get_text_chunk_ids()?;
+    let region_ids: Vec<(i32, i32)> = file_system.get_region_ids()?;
+
+    // nothing to lose if there are no chunks yet (like a world that was just made)
+    let backup_directory: Option<PathBuf> = if text_chunk_ids.is_empty() && region_ids.is_empty() {
+        None
+    } else {
+        Some(backup_world(file_system, old_version)?)
+    };
+
+    // each upgrade is done one after another, a version 1 world is upgraded to 2 and then to 3
+    let mut migrated_chunks: usize = 0;
+    if old_version < 2 {
+        migrated_chunks = migrate_text_chunks_to_regions(file_system, &text_chunk_ids, chunk_sizes)?;
+    }
+    let mut upgraded_regions: usize = 0;
+    if old_version < 3 {
+        upgraded_regions = add_region_checksums(file_system, chunk_sizes)?;
+    }
+
+    // only once everything has been upgraded, so if it stops half way it is done again next time
+    file_system.save_world_format_version(WORLD_FORMAT_VERSION)?;
+
+    Ok(Some(WorldUpgrade {
+        old_version,
+        migrated_chunks,
+        upgraded_regions,
+        backup_directory,
+    }))
+}
+
+/*
+copy every file in the world into Backups/format_<version>
+if that backup is already there it is from an upgrade that didnt finish, so it is kept since it has the files from before that upgrade started
+*/
+fn backup_world(file_system: &FileSystem, version: u32) -> io::Result<PathBuf> {
+    let mut backup_directory: PathBuf = file_system.my_world_directory.clone();
+    backup_directory.push("Backups");
+    backup_directory.push(format!("format_{}", version));
+
+    if !backup_directory.exists() {
+        copy_directory(&file_system.my_world_directory, &backup_directory)?;
+    }
+
+    Ok(backup_directory)
+}
+
+// copy a folder and everything in it, apart from the backups
+fn copy_directory(from: &Path, to: &Path) -> io::Result<()> {
+    create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry: fs::DirEntry = entry?;
+        if entry.file_name() == "Backups" {
+            continue;
+        }
+
+        let to_path: PathBuf = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_directory(&entry.path(), &to_path)?;
+        } else {
+            fs::copy(entry.path(), to_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/*
+version 1 to 2, read every x_z.txt chunk and save it compressed into its region file
+the text files are only deleted once every chunk is in a region, gives how many chunks were moved
+*/
+fn migrate_text_chunks_to_regions(
+    file_system: &mut FileSystem,
+    text_chunk_ids: &[(i32, i32)],
+    chunk_sizes: (usize, usize, usize),
+) -> io::Result<usize> {
+    // so each region file is only opened once
+    let mut region_chunk_ids: BTreeMap<(i32, i32), Vec<(i32, i32)>> = BTreeMap::new();
+    for chunk_id in text_chunk_ids.iter() {
+        region_chunk_ids.entry(get_region_id(*chunk_id)).or_default().push(*chunk_id);
+    }
+
+    let mut migrated_chunks: usize = 0;
+    for (region_id, chunk_ids) in region_chunk_ids.iter() {
+        let region_path: PathBuf = file_system.get_region_path(*region_id);
+        create_dir_all(region_path.parent().unwrap())?;
+        let mut region_file: RegionFile = RegionFile::open(&region_path, chunk_sizes)?;
+
+        for chunk_id in chunk_ids.iter() {
+            let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(*chunk_id, chunk_sizes);
+
+            // a corrupted chunk is moved out of the way instead of stopping the upgrade, it is generated again when it is loaded
+            // anything else stops the upgrade, the backup still has the old files
+            match file_system.read_chunk_from_text_file(&mut temp_chunk_vec, chunk_id.0, chunk_id.1, chunk_sizes) {
+                Ok(()) => {}
+                Err(e @ GameError::CorruptedChunk(..)) => {
+                    let quarantine_path: Option<PathBuf> = file_system
+                        .quarantine_chunk(*chunk_id, chunk_sizes)
+                        .map_err(|quarantine_e| io::Error::other(quarantine_e.to_string()))?;
+                    eprintln!("{}, it was moved to {:?} instead of being upgraded", e, quarantine_path);
+                    continue;
+                }
+                Err(GameError::Io(e)) => return Err(e),
+                Err(e) => return Err(io::Error::other(e.to_string())),
+            }
+
+            let block_types: Vec<BlockType> = get_temp_chunk_block_types(&temp_chunk_vec, chunk_sizes);
+            region_file.write_chunk(*chunk_id, &encode_chunk_blocks(&block_types, chunk_sizes))?;
+            migrated_chunks += 1;
+        }
+    }
+
+    for chunk_id in text_chunk_ids.iter() {
+        let mut path: PathBuf = file_system.my_world_directory.clone();
+        path.push("Chunks");
+        path.push(format!("{}_{}.txt", chunk_id.0, chunk_id.1));
+
+        // chunks that were quarantined are already gone
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(migrated_chunks)
+}
+
+/*
+version 2 to 3, rewrite every region file that doesnt have checksums yet
+each one is written to a temp file and then renamed over the old one, so a region is never half upgraded
+regions that are already the newest version (like ones just made from text chunks) are left alone
+*/
+fn add_region_checksums(file_system: &mut FileSystem, chunk_sizes: (usize, usize, usize)) -> io::Result<usize> {
+    let mut upgraded_regions: usize = 0;
+    for region_id in file_system.get_region_ids()? {
+        let mut region_file: RegionFile = RegionFile::open(&file_system.get_region_path(region_id), chunk_sizes)?;
+        if region_file.get_version() < REGION_VERSION {
+            region_file.compact()?;
+            upgraded_regions += 1;
+        }
+    }
+
+    Ok(upgraded_regions)
+}
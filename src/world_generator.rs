@@ -0,0 +1,241 @@
+/*
+The different ways a world can be generated
+each world picks one generator when it is first created and it is saved in WorldInfo.txt so it keeps using the same one
+the actual block placing logic lives in chunk/create_chunks.rs, these just pick which of those to use
+*/
+
+use crate::{
+    biome::*,
+    block::*,
+    block_type::*,
+    chunk::{create_chunks::*, decorate_chunks::*},
+};
+
+// anything that can fill a new chunk with blocks
+// the temp chunk vector passed in is all air with the blocks positions already set
+pub trait WorldGenerator: Send + Sync {
+    fn generate_chunk(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_ids: (i32, i32),
+        chunk_sizes: (usize, usize, usize),
+        world_seed: u64,
+    );
+
+    // add features like trees after the chunk has been generated
+    // any blocks that belong in other chunks are added to the pending block writes instead
+    fn decorate_chunk(
+        &self,
+        _temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        _chunk_ids: (i32, i32),
+        _chunk_sizes: (usize, usize, usize),
+        _world_seed: u64,
+        _pending_block_writes: &mut PendingBlockWrites,
+    ) {
+    }
+
+    // the y index (in the temp chunk vector) of the top block of this column when it is first generated
+    // none if the column is empty
+    fn get_surface_height(
+        &self,
+        world_x: i32,
+        world_z: i32,
+        chunk_sizes: (usize, usize, usize),
+        world_seed: u64,
+    ) -> Option<usize>;
+
+    // which biome this column is in, none if this generator doesnt use biomes
+    fn get_biome(&self, _world_x: i32, _world_z: i32, _world_seed: u64) -> Option<Biome> {
+        None
+    }
+}
+
+// the original flat world
+pub struct FlatGenerator;
+
+impl WorldGenerator for FlatGenerator {
+    fn generate_chunk(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        _chunk_ids: (i32, i32),
+        chunk_sizes: (usize, usize, usize),
+        _world_seed: u64,
+    ) {
+        generate_flat_chunk(temp_chunk_vec, chunk_sizes);
+    }
+
+    fn get_surface_height(&self, _: i32, _: i32, chunk_sizes: (usize, usize, usize), _: u64) -> Option<usize> {
+        Some((chunk_sizes.1 / 2) - 1)
+    }
+}
+
+// a flat world made from a list of layers going from the bottom up, (block type, thickness)
+pub struct SuperflatGenerator {
+    pub layers: Vec<(BlockType, usize)>,
+}
+
+impl WorldGenerator for SuperflatGenerator {
+    fn generate_chunk(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        _chunk_ids: (i32, i32),
+        chunk_sizes: (usize, usize, usize),
+        _world_seed: u64,
+    ) {
+        generate_superflat_chunk(temp_chunk_vec, &self.layers, chunk_sizes);
+    }
+
+    fn get_surface_height(&self, _: i32, _: i32, chunk_sizes: (usize, usize, usize), _: u64) -> Option<usize> {
+        let total_height: usize = self.layers.iter().map(|layer| layer.1).sum();
+        if total_height == 0 {
+            None
+        } else {
+            Some(total_height.min(chunk_sizes.1) - 1)
+        }
+    }
+}
+
+// hills made from the seeded noise height map, with water filling everything below the sea level
+pub struct NoiseGenerator {
+    // the world y of the top of the water
+    pub sea_level: i16,
+}
+
+impl WorldGenerator for NoiseGenerator {
+    fn generate_chunk(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_ids: (i32, i32),
+        chunk_sizes: (usize, usize, usize),
+        world_seed: u64,
+    ) {
+        generate_chunk(temp_chunk_vec, chunk_ids, chunk_sizes, world_seed);
+        fill_water(temp_chunk_vec, chunk_sizes, self.sea_level);
+    }
+
+    fn decorate_chunk(
+        &self,
+        temp_chunk_vec: &mut Vec<Vec<Vec<Block>>>,
+        chunk_ids: (i32, i32),
+        chunk_sizes: (usize, usize, usize),
+        world_seed: u64,
+        pending_block_writes: &mut PendingBlockWrites,
+    ) {
+        decorate_chunk(temp_chunk_vec, chunk_ids, chunk_sizes, world_seed, pending_block_writes);
+    }
+
+    fn get_surface_height(
+        &self,
+        world_x: i32,
+        world_z: i32,
+        chunk_sizes: (usize, usize, usize),
+        world_seed: u64,
+    ) -> Option<usize> {
+        Some(get_surface_height(world_x, world_z, chunk_sizes, world_seed))
+    }
+
+    fn get_biome(&self, world_x: i32, world_z: i32, world_seed: u64) -> Option<Biome> {
+        Some(get_biome(world_x, world_z, world_seed))
+    }
+}
+
+// nothing at all, every chunk is left as air
+pub struct VoidGenerator;
+
+impl WorldGenerator for VoidGenerator {
+    fn generate_chunk(&self, _: &mut Vec<Vec<Vec<Block>>>, _: (i32, i32), _: (usize, usize, usize), _: u64) {}
+
+    fn get_surface_height(&self, _: i32, _: i32, _: (usize, usize, usize), _: u64) -> Option<usize> {
+        None
+    }
+}
+
+
+// which generator a world uses, this is what gets written to and read from WorldInfo.txt
+#[derive(Clone, Debug, PartialEq)]
+pub enum WorldGeneratorType {
+    Flat,
+    Superflat(Vec<(BlockType, usize)>),
+    Noise { sea_level: i16 },
+    Void,
+}
+
+impl WorldGeneratorType {
+    // the default noise world, with the sea at the default sea level
+    pub fn default_noise() -> WorldGeneratorType {
+        WorldGeneratorType::Noise {
+            sea_level: DEFAULT_SEA_LEVEL,
+        }
+    }
+
+    // the default superflat layers, bedrock, then stone, dirt and grass on top
+    pub fn default_superflat() -> WorldGeneratorType {
+        WorldGeneratorType::Superflat(vec![
+            (BlockType::Bedrock, 1),
+            (BlockType::Stone, 3),
+            (BlockType::Dirt, 2),
+            (BlockType::Grass, 1),
+        ])
+    }
+
+    pub fn create_generator(&self) -> Box<dyn WorldGenerator> {
+        match self {
+            WorldGeneratorType::Flat => Box::new(FlatGenerator),
+            WorldGeneratorType::Superflat(layers) => Box::new(SuperflatGenerator {
+                layers: layers.clone(),
+            }),
+            WorldGeneratorType::Noise { sea_level } => Box::new(NoiseGenerator { sea_level: *sea_level }),
+            WorldGeneratorType::Void => Box::new(VoidGenerator),
+        }
+    }
+
+    /*
+    the text saved after "Generator: " in WorldInfo.txt
+    superflat layers are saved as blockidxthickness from the bottom up, eg. "Superflat 1x1 400x3 302x2 301x1"
+    noise worlds save their sea level, eg. "Noise -6"
+    */
+    pub fn to_info_string(&self) -> String {
+        match self {
+            WorldGeneratorType::Flat => "Flat".to_string(),
+            WorldGeneratorType::Superflat(layers) => {
+                let mut info: String = "Superflat".to_string();
+                for (block_type, thickness) in layers.iter() {
+                    info.push_str(&format!(" {}x{}", block_type.to_int(), thickness));
+                }
+                info
+            }
+            WorldGeneratorType::Noise { sea_level } => format!("Noise {}", sea_level),
+            WorldGeneratorType::Void => "Void".to_string(),
+        }
+    }
+
+    // read back what to_info_string wrote, none if it isnt a generator i know about
+    pub fn from_info_string(info: &str) -> Option<WorldGeneratorType> {
+        let mut parts = info.split_whitespace();
+
+        match parts.next()? {
+            "Flat" => Some(WorldGeneratorType::Flat),
+            "Noise" => {
+                // worlds made before the sea level was saved use the default one
+                let sea_level: i16 = match parts.next() {
+                    Some(sea_level) => sea_level.parse::<i16>().ok()?,
+                    None => DEFAULT_SEA_LEVEL,
+                };
+                Some(WorldGeneratorType::Noise { sea_level })
+            }
+            "Void" => Some(WorldGeneratorType::Void),
+            "Superflat" => {
+                let mut layers: Vec<(BlockType, usize)> = Vec::new();
+                for layer in parts {
+                    let (block_id, thickness) = layer.split_once('x')?;
+                    layers.push((
+                        BlockType::from_int(block_id.parse::<u16>().ok()?).ok()?,
+                        thickness.parse::<usize>().ok()?,
+                    ));
+                }
+                Some(WorldGeneratorType::Superflat(layers))
+            }
+            _ => None,
+        }
+    }
+}
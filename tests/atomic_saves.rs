@@ -0,0 +1,249 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, decorate_chunks::*},
+    error::*,
+    file_system::*,
+    main_game_loop::*,
+    region_file::*,
+    render_backend::*,
+    world::*,
+    world_generator::*,
+};
+
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+mod common;
+use common::*;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (16, 64, 16);
+
+fn get_temp_file_names(path: &Path) -> Vec<String> {
+    fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|file_name| file_name.ends_with(".tmp"))
+        .collect()
+}
+
+
+// the checksum is the standard crc32, and changes when any byte does
+#[test]
+fn test_checksum() {
+    assert_eq!(get_checksum(b"123456789"), 0xCBF43926);
+    assert_eq!(get_checksum(&[]), 0);
+    assert_ne!(get_checksum(&[1, 2, 3]), get_checksum(&[1, 2, 4]));
+}
+
+// a chunk with a changed byte is an error instead of the wrong blocks, and the other chunks can still be read
+#[test]
+fn test_corrupted_chunk_is_detected() {
+    let (_, path) = make_test_file_system("rust_craft_test_atomic_saves_corrupted");
+    let region_path: PathBuf = path.join("test.region");
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    region_file.write_chunk((0, 0), &[1; 40]).unwrap();
+    region_file.write_chunk((1, 0), &[2; 40]).unwrap();
+    drop(region_file);
+
+    // the last byte of the file is in chunk (1, 0)
+    let mut region_bytes: Vec<u8> = fs::read(&region_path).unwrap();
+    let last_index: usize = region_bytes.len() - 1;
+    region_bytes[last_index] = 3;
+    fs::write(&region_path, region_bytes).unwrap();
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    let error_message: String = region_file.read_chunk((1, 0)).unwrap_err().to_string();
+    assert!(error_message.contains("corrupted"), "{}", error_message);
+    assert_eq!(region_file.read_chunk((0, 0)).unwrap(), Some(vec![1; 40]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a region file that was cut short is an error for the chunks that are missing their bytes
+#[test]
+fn test_truncated_chunk_is_detected() {
+    let (_, path) = make_test_file_system("rust_craft_test_atomic_saves_truncated");
+    let region_path: PathBuf = path.join("test.region");
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    region_file.write_chunk((0, 0), &[1; 40]).unwrap();
+    region_file.write_chunk((1, 0), &[2; 40]).unwrap();
+    drop(region_file);
+
+    let file_length: u64 = fs::metadata(&region_path).unwrap().len();
+    OpenOptions::new().write(true).open(&region_path).unwrap().set_len(file_length - 10).unwrap();
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    let error_message: String = region_file.read_chunk((1, 0)).unwrap_err().to_string();
+    assert!(error_message.contains("cut short"), "{}", error_message);
+    assert_eq!(region_file.read_chunk((0, 0)).unwrap(), Some(vec![1; 40]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a save that stopped before its offset entry was written leaves the old chunk as it was
+#[test]
+fn test_unfinished_chunk_save_keeps_old_chunk() {
+    let (_, path) = make_test_file_system("rust_craft_test_atomic_saves_unfinished");
+    let region_path: PathBuf = path.join("test.region");
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    region_file.write_chunk((4, 4), &[5; 40]).unwrap();
+    drop(region_file);
+
+    // the new bytes got to the end of the file but nothing points at them
+    OpenOptions::new().append(true).open(&region_path).unwrap().write_all(&[9; 25]).unwrap();
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert_eq!(region_file.read_chunk((4, 4)).unwrap(), Some(vec![5; 40]));
+
+    // and saving again after that still works
+    region_file.write_chunk((4, 4), &[6; 40]).unwrap();
+    assert_eq!(region_file.read_chunk((4, 4)).unwrap(), Some(vec![6; 40]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// reading a corrupted chunk into the world says which chunk it was
+#[test]
+fn test_read_corrupted_chunk_from_world() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_atomic_saves_world_corrupted");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+
+    let region_path: PathBuf = file_system.get_region_path((0, 0));
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    region_file.write_chunk((0, 0), &[1; 40]).unwrap();
+    drop(region_file);
+
+    let mut region_bytes: Vec<u8> = fs::read(&region_path).unwrap();
+    let last_index: usize = region_bytes.len() - 1;
+    region_bytes[last_index] = 0;
+    fs::write(&region_path, region_bytes).unwrap();
+
+    let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), CHUNK_SIZES);
+    match file_system.read_chunks_from_file(&mut temp_chunk_vec, 0, 0, CHUNK_SIZES) {
+        Err(GameError::CorruptedChunk(chunk_id, error_path, message)) => {
+            assert_eq!(chunk_id, (0, 0));
+            assert_eq!(error_path, region_path);
+            assert!(message.contains("corrupted"), "{}", message);
+        }
+        other => panic!("Expected a corrupted chunk error, got {:?}", other),
+    }
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// saving the same chunks over and over doesnt make the region grow forever, and the chunks are kept
+#[test]
+fn test_region_is_compacted() {
+    let (_, path) = make_test_file_system("rust_craft_test_atomic_saves_compact");
+    let region_path: PathBuf = path.join("test.region");
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    region_file.write_chunk((2, 2), &[1; 1000]).unwrap();
+    for save in 0..20 {
+        region_file.write_chunk((3, 3), &vec![save as u8; 100 * 1024]).unwrap();
+    }
+
+    let file_length: u64 = fs::metadata(&region_path).unwrap().len();
+    assert!(file_length < 400 * 1024, "Region is {} bytes", file_length);
+    assert!(get_temp_file_names(&path).is_empty());
+
+    assert_eq!(region_file.read_chunk((3, 3)).unwrap(), Some(vec![19; 100 * 1024]));
+    drop(region_file);
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert_eq!(region_file.read_chunk((2, 2)).unwrap(), Some(vec![1; 1000]));
+    assert_eq!(region_file.read_chunk((3, 3)).unwrap(), Some(vec![19; 100 * 1024]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a bad entry somewhere else in the region doesnt make saving fail, the region is still compacted without it
+#[test]
+fn test_compact_skips_unreadable_chunks() {
+    let (_, path) = make_test_file_system("rust_craft_test_atomic_saves_compact_bad_entry");
+    let region_path: PathBuf = path.join("test.region");
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    region_file.write_chunk((0, 0), &[1; 40]).unwrap();
+    region_file.write_chunk((1, 0), &[2; 1000]).unwrap();
+    drop(region_file);
+
+    // the offset of chunk (0, 0), the first entry after the magic, version and chunk sizes, now points way past the end of the file
+    let mut region_bytes: Vec<u8> = fs::read(&region_path).unwrap();
+    region_bytes[20..28].copy_from_slice(&(1u64 << 40).to_le_bytes());
+    fs::write(&region_path, region_bytes).unwrap();
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    for save in 0..20 {
+        region_file.write_chunk((3, 3), &vec![save as u8; 100 * 1024]).unwrap();
+    }
+
+    let file_length: u64 = fs::metadata(&region_path).unwrap().len();
+    assert!(file_length < 400 * 1024, "Region is {} bytes", file_length);
+
+    assert_eq!(region_file.read_chunk((0, 0)).unwrap(), None);
+    assert_eq!(region_file.read_chunk((1, 0)).unwrap(), Some(vec![2; 1000]));
+    assert_eq!(region_file.read_chunk((3, 3)).unwrap(), Some(vec![19; 100 * 1024]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// the world files are replaced all at once, a temp file left by a crash doesnt change them and no temp files are left after saving
+#[test]
+fn test_world_files_are_replaced_atomically() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_atomic_saves_world_files");
+
+    file_system.save_world_info(7, &WorldGeneratorType::Flat).unwrap();
+    let created_chunks: HashSet<(i32, i32)> = HashSet::from([(0, 0), (1, -1)]);
+    file_system.save_created_chunks_file(CHUNK_SIZES, &created_chunks).unwrap();
+    file_system.save_pending_block_writes(&PendingBlockWrites::new()).unwrap();
+    assert!(get_temp_file_names(&path).is_empty());
+
+    // a save that crashed before the rename
+    fs::write(path.join("WorldInfo.txt.tmp"), "World Seed: 8\n").unwrap();
+    assert_eq!(file_system.read_world_info().unwrap(), Some((7, WorldGeneratorType::Flat)));
+
+    // the next save replaces the temp file as well
+    file_system.save_world_info(9, &WorldGeneratorType::Flat).unwrap();
+    assert_eq!(file_system.read_world_info().unwrap(), Some((9, WorldGeneratorType::Flat)));
+    assert!(get_temp_file_names(&path).is_empty());
+
+    write_file_atomic(&path.join("test.txt"), b"new").unwrap();
+    assert_eq!(fs::read_to_string(path.join("test.txt")).unwrap(), "new");
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// chunks that are still pending when the game closes are saved as well, so the blocks put in them arnt lost
+#[test]
+fn test_pending_chunks_are_saved() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_atomic_saves_pending_chunks");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+
+    let mut world: World = World::new(String::from("pending"), 3, 1, CHUNK_SIZES);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(0, 0)]));
+    assert!(world.chunks.is_empty());
+    assert!(world.pending_chunks.contains_key(&(0, 0)));
+
+    let top_y: i16 = CHUNK_SIZES.1 as i16 / 2 - 1;
+    world.set_block(2, top_y, 3, BlockType::Cobblestone).unwrap();
+    clean_up(&mut world, &mut file_system);
+    assert!(world.pending_chunks.is_empty());
+
+    let mut reopened_world: World = World::new(String::from("pending"), 3, 1, CHUNK_SIZES);
+    reopened_world.load_created_chunks_file(&mut file_system).unwrap();
+    reopened_world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(0, 0)]));
+    assert_eq!(reopened_world.get_block(2, top_y, 3).unwrap(), BlockType::Cobblestone);
+
+    fs::remove_dir_all(&path).unwrap();
+}
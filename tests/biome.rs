@@ -0,0 +1,163 @@
+extern crate rust_craft;
+use rust_craft::{
+    biome::*,
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*},
+    world::*,
+    world_generator::*,
+};
+
+
+// the same seed should always give the same biomes
+#[test]
+fn test_biome_deterministic() {
+    for x in (-2000..2000).step_by(97) {
+        for z in (-2000..2000).step_by(89) {
+            assert_eq!(get_biome(x, z, 5), get_biome(x, z, 5));
+        }
+    }
+}
+
+// a big enough area should have every biome in it
+#[test]
+fn test_biome_all_biomes_exist() {
+    let mut found: Vec<Biome> = Vec::new();
+
+    for x in (-4000..4000).step_by(64) {
+        for z in (-4000..4000).step_by(64) {
+            let biome: Biome = get_biome(x, z, 1);
+            if !found.contains(&biome) {
+                found.push(biome);
+            }
+        }
+    }
+
+    for biome in [Biome::Plains, Biome::Desert, Biome::Mountains, Biome::Ocean] {
+        assert!(found.contains(&biome), "{:?} was not found", biome);
+    }
+}
+
+// find a column in each biome so the tests can check the blocks there
+fn find_biome(biome: Biome, world_seed: u64) -> (i32, i32) {
+    for x in (-4000..4000).step_by(16) {
+        for z in (-4000..4000).step_by(16) {
+            if get_biome(x, z, world_seed) == biome {
+                return (x, z);
+            }
+        }
+    }
+    panic!("No {:?} biome found", biome);
+}
+
+// the top block of the generated column should be the biomes surface block
+fn test_biome_surface_block(biome: Biome, expected_blocks: &[BlockType]) {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let (world_x, world_z) = find_biome(biome, 1);
+    let chunk_ids: (i32, i32) = (world_x.div_euclid(32), world_z.div_euclid(32));
+
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_ids, chunk_sizes);
+    generate_chunk(&mut temp_chunk_vector, chunk_ids, chunk_sizes, 1);
+
+    let surface_height: usize = get_surface_height(world_x, world_z, chunk_sizes, 1);
+    let block_type: BlockType =
+        temp_chunk_vector[world_x.rem_euclid(32) as usize][surface_height][world_z.rem_euclid(32) as usize].block_type;
+
+    // a cave can open up at the surface
+    if block_type != BlockType::Air {
+        assert!(expected_blocks.contains(&block_type), "{:?} had a surface of {:?}", biome, block_type);
+    }
+}
+
+#[test]
+fn test_biome_surface_block_plains() {
+    test_biome_surface_block(Biome::Plains, &[BlockType::Grass]);
+}
+
+#[test]
+fn test_biome_surface_block_desert() {
+    test_biome_surface_block(Biome::Desert, &[BlockType::Sand]);
+}
+
+#[test]
+fn test_biome_surface_block_mountains() {
+    test_biome_surface_block(Biome::Mountains, &[BlockType::Grass, BlockType::Stone]);
+}
+
+#[test]
+fn test_biome_surface_block_ocean() {
+    test_biome_surface_block(Biome::Ocean, &[BlockType::Sand]);
+}
+
+// mountains should be higher than oceans on average
+#[test]
+fn test_biome_heights() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let mut mountain_heights: Vec<usize> = Vec::new();
+    let mut ocean_heights: Vec<usize> = Vec::new();
+
+    for x in (-4000..4000).step_by(40) {
+        for z in (-4000..4000).step_by(40) {
+            match get_biome(x, z, 1) {
+                Biome::Mountains => mountain_heights.push(get_surface_height(x, z, chunk_sizes, 1)),
+                Biome::Ocean => ocean_heights.push(get_surface_height(x, z, chunk_sizes, 1)),
+                _ => {}
+            }
+        }
+    }
+
+    let mountain_average: f64 = mountain_heights.iter().sum::<usize>() as f64 / mountain_heights.len() as f64;
+    let ocean_average: f64 = ocean_heights.iter().sum::<usize>() as f64 / ocean_heights.len() as f64;
+
+    assert!(
+        mountain_average > ocean_average + 16.0,
+        "Mountains ({}) should be well above oceans ({})", mountain_average, ocean_average
+    );
+}
+
+// the heights are blended at biome borders, so neighbouring columns never jump by a cliff
+#[test]
+fn test_biome_borders_are_smooth() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let mut borders_checked: u32 = 0;
+
+    for x in (-2000..2000).step_by(7) {
+        for z in (-2000..2000).step_by(61) {
+            if get_biome(x, z, 3) == get_biome(x + 1, z, 3) {
+                continue;
+            }
+            borders_checked += 1;
+
+            let height: i32 = get_surface_height(x, z, chunk_sizes, 3) as i32;
+            let next_height: i32 = get_surface_height(x + 1, z, chunk_sizes, 3) as i32;
+            assert!(
+                (height - next_height).abs() <= 4,
+                "Cliff of {} blocks at the biome border at ({}, {})", (height - next_height).abs(), x, z
+            );
+        }
+    }
+
+    assert!(borders_checked > 0, "No biome borders were found");
+}
+
+// the world can be asked for the biome anywhere, even where no chunk is loaded
+#[test]
+fn test_world_biome_at() {
+    let mut world: World = World::new("test_world".to_string(), 1, 3, (32, 256, 32));
+
+    world.set_generator(WorldGeneratorType::default_noise());
+    let (world_x, world_z) = find_biome(Biome::Desert, 1);
+    assert_eq!(world.biome_at(world_x, world_z), Some(Biome::Desert));
+
+    // flat worlds dont have biomes
+    world.set_generator(WorldGeneratorType::Flat);
+    assert_eq!(world.biome_at(world_x, world_z), None);
+}
+
+// grass is tinted by its biome but other blocks keep their colour
+#[test]
+fn test_biome_block_colours() {
+    assert_ne!(Biome::Plains.get_block_colour(BlockType::Grass), Biome::Mountains.get_block_colour(BlockType::Grass));
+    assert_eq!(Biome::Plains.get_block_colour(BlockType::Grass), Biome::Plains.grass_colour());
+    assert_eq!(Biome::Desert.get_block_colour(BlockType::Stone), BlockType::Stone.block_colour());
+}
@@ -0,0 +1,177 @@
+extern crate rust_craft;
+use rust_craft::{
+    block_type::*,
+    block_updates::*,
+    chunk::decorate_chunks::BlockWrite,
+    tick_scheduler::*,
+};
+
+use std::collections::{BTreeSet, HashMap};
+
+
+// a small test world, a stone floor at y = 0 with air above it up to y = 20
+// it schedules falling block ticks the same way the world does when a block is set
+struct TestWorld {
+    blocks: HashMap<(i32, i16, i32), BlockType>,
+    tick_scheduler: TickScheduler,
+}
+
+impl TestWorld {
+    fn new() -> TestWorld {
+        let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+        for x in -5..=5 {
+            for z in -5..=5 {
+                blocks.insert((x, 0, z), BlockType::Stone);
+            }
+        }
+
+        TestWorld {
+            blocks,
+            tick_scheduler: TickScheduler::new(),
+        }
+    }
+
+    fn get_block(&self, block_pos: (i32, i16, i32)) -> Option<BlockType> {
+        if block_pos.1 < 0 || block_pos.1 > 20 {
+            return None;
+        }
+        Some(*self.blocks.get(&block_pos).unwrap_or(&BlockType::Air))
+    }
+
+    fn set_block(&mut self, block_pos: (i32, i16, i32), block_type: BlockType) {
+        self.blocks.insert(block_pos, block_type);
+
+        for update_pos in [block_pos, (block_pos.0, block_pos.1 + 1, block_pos.2)] {
+            if self.get_block(update_pos).is_some_and(|block_type| block_type.is_dynamic()) {
+                self.tick_scheduler.schedule_tick(update_pos, TickType::FallingBlock, FALLING_BLOCK_DELAY);
+            }
+        }
+    }
+
+    fn tick(&mut self) -> Vec<BlockWrite> {
+        let due_updates: BTreeSet<(i32, i16, i32)> =
+            self.tick_scheduler.take_due_ticks().into_iter().map(|(block_pos, _)| block_pos).collect();
+        let block_writes: Vec<BlockWrite> = simulate_falling_blocks(&due_updates, |block_pos| self.get_block(block_pos));
+
+        for (block_pos, block_type) in block_writes.iter() {
+            self.set_block(*block_pos, *block_type);
+        }
+        self.tick_scheduler.advance_tick();
+
+        block_writes
+    }
+
+    fn settle(&mut self) -> u32 {
+        for ticks in 0..500 {
+            self.tick();
+            if self.tick_scheduler.is_empty() {
+                return ticks;
+            }
+        }
+        panic!("Blocks never stopped falling");
+    }
+}
+
+
+// sand in the air falls one block each tick until it lands on the floor
+#[test]
+fn test_sand_falls_and_lands() {
+    let mut world: TestWorld = TestWorld::new();
+    world.set_block((0, 5, 0), BlockType::Sand);
+
+    // the update was scheduled for the next tick
+    assert!(world.tick().is_empty());
+
+    world.tick();
+    assert_eq!(world.get_block((0, 5, 0)), Some(BlockType::Air));
+    assert_eq!(world.get_block((0, 4, 0)), Some(BlockType::Sand));
+
+    world.settle();
+    assert_eq!(world.get_block((0, 1, 0)), Some(BlockType::Sand));
+    for y in 2..=5 {
+        assert_eq!(world.get_block((0, y, 0)), Some(BlockType::Air));
+    }
+}
+
+// blocks that arnt dynamic stay where they are
+#[test]
+fn test_non_dynamic_blocks_dont_fall() {
+    let mut world: TestWorld = TestWorld::new();
+    world.set_block((0, 5, 0), BlockType::Stone);
+    world.tick_scheduler.schedule_tick((0, 5, 0), TickType::FallingBlock, 0);
+    world.settle();
+
+    assert_eq!(world.get_block((0, 5, 0)), Some(BlockType::Stone));
+}
+
+// removing the block under a stack of sand makes the whole stack fall, one after the other
+#[test]
+fn test_sand_chain_reaction() {
+    let mut world: TestWorld = TestWorld::new();
+    world.blocks.insert((0, 1, 0), BlockType::Stone);
+    world.blocks.insert((0, 2, 0), BlockType::Stone);
+    for y in 3..8 {
+        world.blocks.insert((0, y, 0), BlockType::Sand);
+    }
+
+    // nothing happens while it is supported
+    world.tick_scheduler.schedule_tick((0, 3, 0), TickType::FallingBlock, 0);
+    world.settle();
+    assert_eq!(world.get_block((0, 3, 0)), Some(BlockType::Sand));
+
+    // break the stone holding it up
+    world.set_block((0, 2, 0), BlockType::Air);
+    world.set_block((0, 1, 0), BlockType::Air);
+    world.settle();
+
+    for y in 1..6 {
+        assert_eq!(world.get_block((0, y, 0)), Some(BlockType::Sand), "No sand at y = {}", y);
+    }
+    for y in 6..8 {
+        assert_eq!(world.get_block((0, y, 0)), Some(BlockType::Air));
+    }
+}
+
+// sand falls through water and replaces it
+#[test]
+fn test_sand_falls_through_water() {
+    let mut world: TestWorld = TestWorld::new();
+    world.blocks.insert((0, 1, 0), BlockType::Water);
+    world.blocks.insert((0, 2, 0), BlockType::FlowingWater(7));
+    world.set_block((0, 3, 0), BlockType::Sand);
+    world.settle();
+
+    assert_eq!(world.get_block((0, 1, 0)), Some(BlockType::Sand));
+}
+
+// nothing falls out of the bottom of the world or into blocks that cant be read
+#[test]
+fn test_falling_block_move_unreadable() {
+    let get_block = |block_pos: (i32, i16, i32)| if block_pos.1 < 0 { None } else { Some(BlockType::Sand) };
+    assert_eq!(get_falling_block_move((0, 0, 0), &get_block), None);
+
+    let get_block = |block_pos: (i32, i16, i32)| if block_pos.1 == 0 { Some(BlockType::Sand) } else { Some(BlockType::Air) };
+    assert_eq!(get_falling_block_move((0, 0, 0), &get_block), Some((0, -1, 0)));
+}
+
+// the same world always falls the same way
+#[test]
+fn test_falling_blocks_deterministic() {
+    let run = || {
+        let mut world: TestWorld = TestWorld::new();
+        for (i, x) in (-3..3).enumerate() {
+            for y in 0..(i as i16 + 2) {
+                world.set_block((x, 6 + y * 2, x * 2 % 5), BlockType::Sand);
+            }
+        }
+
+        let mut all_writes: Vec<Vec<BlockWrite>> = Vec::new();
+        for _ in 0..40 {
+            all_writes.push(world.tick());
+        }
+        all_writes
+    };
+
+    assert_eq!(run(), run());
+}
+
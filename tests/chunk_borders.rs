@@ -0,0 +1,70 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_borders::*, chunk_functions::*},
+};
+
+
+// the sides of a chunk line up with the chunks next to it
+#[test]
+fn test_chunk_sides() {
+    for side in ChunkSide::all() {
+        assert_eq!(side.opposite().opposite(), side);
+        assert_eq!(side.opposite().get_neighbour_id(side.get_neighbour_id((3, -2))), (3, -2));
+    }
+
+    assert_eq!(ChunkSide::Right.get_neighbour_id((0, 0)), (1, 0));
+    assert_eq!(ChunkSide::Front.get_neighbour_id((0, 0)), (0, -1));
+}
+
+// the edge positions are the outside layer of the chunk, in the order a border layer is indexed
+#[test]
+fn test_get_edge_positions() {
+    let chunk_sizes: (usize, usize, usize) = (4, 8, 6);
+
+    let right: Vec<(i32, i16, i32)> = get_edge_positions((-1, 2), ChunkSide::Right, chunk_sizes);
+    assert_eq!(right.len(), chunk_sizes.1 * chunk_sizes.2);
+    assert_eq!(right[0], (-1, -4, 12));
+    assert_eq!(right[chunk_sizes.2 + 1], (-1, -3, 13));
+
+    let front: Vec<(i32, i16, i32)> = get_edge_positions((-1, 2), ChunkSide::Front, chunk_sizes);
+    assert_eq!(front.len(), chunk_sizes.1 * chunk_sizes.0);
+    assert_eq!(front[chunk_sizes.0 * 2 + 3], (-1, -2, 12));
+
+    // every edge position is in the chunk
+    for side in ChunkSide::all() {
+        for block_pos in get_edge_positions((-1, 2), side, chunk_sizes) {
+            assert_eq!(block_pos.0.div_euclid(chunk_sizes.0 as i32), -1);
+            assert_eq!(block_pos.2.div_euclid(chunk_sizes.2 as i32), 2);
+        }
+    }
+}
+
+// the padded chunk has the chunk inside, the borders on the sides and void everywhere else
+#[test]
+fn test_get_padded_block_type() {
+    let chunk_sizes: (usize, usize, usize) = (4, 8, 4);
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+    temp_chunk_vector[0][2][3].block_type = BlockType::Dirt;
+
+    let mut chunk_borders: ChunkBorders = ChunkBorders::new();
+    let mut left: Vec<BlockType> = vec![BlockType::Air; chunk_sizes.1 * chunk_sizes.2];
+    left[2 * chunk_sizes.2 + 3] = BlockType::Stone;
+    chunk_borders.set_border(ChunkSide::Left, left);
+
+    let get = |padded_pos: (usize, usize, usize)| {
+        get_padded_block_type(&temp_chunk_vector, &chunk_borders, padded_pos, chunk_sizes)
+    };
+
+    assert_eq!(get((1, 3, 4)), BlockType::Dirt);
+    assert_eq!(get((0, 3, 4)), BlockType::Stone);
+    assert_eq!(get((0, 3, 3)), BlockType::Air);
+
+    // sides that arnt loaded, above and below the chunk and the corners are void
+    assert_eq!(get((5, 3, 4)), BlockType::Void);
+    assert_eq!(get((1, 3, 0)), BlockType::Void);
+    assert_eq!(get((1, 0, 1)), BlockType::Void);
+    assert_eq!(get((1, 9, 1)), BlockType::Void);
+    assert_eq!(get((0, 3, 0)), BlockType::Void);
+}
@@ -4,68 +4,41 @@ extern crate rust_craft;
 use rust_craft::{
     block::*, 
     block_type::*,
-    chunk::{chunk_functions::*, chunk_gpu_functions::*, create_chunks::*}, 
+    chunk::{chunk_borders::*, chunk_cpu_functions::*, chunk_functions::*, chunk_gpu_functions::*, create_chunks::*}, 
 };
 
 use async_std::task;
-use wgpu::{Device, Queue, ShaderModule};
 
+mod common;
+use common::*;
 
 
-pub async fn get_renderer_variables() -> (Device, Queue, ShaderModule) {
-    
-    let instance = wgpu::Instance::default();
-    let adapter = instance
-        .request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            force_fallback_adapter: false,
-            compatible_surface: None,
-        })
-        .await
-        .unwrap();
-
-    let (device, queue) = adapter
-        .request_device(
-            &wgpu::DeviceDescriptor {
-                label: None,
-                required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits::default(),
-            },
-            None,
-        )
-        .await
-        .unwrap();
 
-    let shader_code =
-        device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("check air compute shader"),
-            source: wgpu::ShaderSource::Wgsl(
-                include_str!("../src/Shaders/check_air_compute.wgsl").into(),
-            ),
-        });
-
-    (device, queue, shader_code)
+// run the touching air pass on the gpu if there is one, otherwise on the cpu
+fn run_check_air(temp_chunk_vector: &mut Vec<Vec<Vec<Block>>>, chunk_borders: &ChunkBorders, chunk_sizes: (usize, usize, usize)) {
+    match task::block_on(get_renderer_variables()) {
+        Some((device, queue, shader_code)) => task::block_on(check_for_touching_air(
+            temp_chunk_vector, 
+            chunk_borders,
+            &device, 
+            &queue, 
+            &shader_code, 
+            chunk_sizes
+        )),
+        None => check_for_touching_air_cpu(temp_chunk_vector, chunk_borders, chunk_sizes),
+    }
 }
 
 // these all assume chunk sizes of (32 x 256 x 32)
 fn test_check_air(chunk_sizes: (usize, usize, usize)) {
 
-    // create the parts of the renderer that i need
-    let (device, queue, shader_code) = task::block_on(get_renderer_variables());
-
     // get the chunk data
     let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
-    generate_chunk(&mut temp_chunk_vector, chunk_sizes);
+    generate_chunk(&mut temp_chunk_vector, (0, 0), chunk_sizes, 1);
     
 
-    // run the check air function
-    task::block_on(check_for_touching_air(
-        &mut temp_chunk_vector, 
-        &device, 
-        &queue, 
-        &shader_code, 
-        chunk_sizes
-    ));
+    // run the check air function, with no chunks around it so the walls are next to void
+    run_check_air(&mut temp_chunk_vector, &ChunkBorders::new(), chunk_sizes);
 
 
     // tests the results
@@ -132,6 +105,22 @@ fn test_check_air(chunk_sizes: (usize, usize, usize)) {
                         assert_eq!(temp_chunk_vector[x][y][z].is_touching_air, true,
                         "After checking all sides this block touched air blocks but is_touching_air is false");
                     }
+
+                    // each face touching air should have its bit set in the visible faces, and no others
+                    let mut expected_faces: u8 = 0;
+                    for face in BlockFace::ALL {
+                        let offset: (i32, i16, i32) = face.offset();
+                        let (nx, ny, nz) = (x as i32 + offset.0, y as i32 + offset.1 as i32, z as i32 + offset.2);
+                        let is_in_chunk: bool = nx >= 0 && nx < chunk_sizes.0 as i32
+                            && ny >= 0 && ny < chunk_sizes.1 as i32
+                            && nz >= 0 && nz < chunk_sizes.2 as i32;
+
+                        if is_in_chunk && temp_chunk_vector[nx as usize][ny as usize][nz as usize].block_type == BlockType::Air {
+                            expected_faces |= face.bit();
+                        }
+                    }
+                    assert_eq!(temp_chunk_vector[x][y][z].visible_faces, expected_faces,
+                    "The visible faces dont match the faces touching air");
                     
                 } else {
                     // if it is an air block it should be not touching air
@@ -144,6 +133,52 @@ fn test_check_air(chunk_sizes: (usize, usize, usize)) {
 }
 
 
+// every face has its own bit, and a block is only touching air if one of them is set
+#[test]
+fn test_visible_faces() {
+    let mut all_faces: u8 = 0;
+    for face in BlockFace::ALL {
+        assert_eq!(all_faces & face.bit(), 0);
+        all_faces |= face.bit();
+    }
+    assert_eq!(all_faces, ALL_FACES_VISIBLE);
+
+    let mut block: Block = Block::new(BlockType::Stone, 0, 0, 0);
+    block.set_visible_faces(BlockFace::Top.bit());
+    assert!(block.is_touching_air);
+    block.set_visible_faces(0);
+    assert!(!block.is_touching_air);
+}
+
+// blocks on the walls of a chunk are checked against the edge of the chunk next to them
+#[test]
+fn test_check_air_borders() {
+    let chunk_sizes: (usize, usize, usize) = (8, 16, 8);
+
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+    generate_flat_chunk(&mut temp_chunk_vector, chunk_sizes);
+
+    // air to the right, stone to the left and back, nothing loaded at the front
+    let mut chunk_borders: ChunkBorders = ChunkBorders::new();
+    chunk_borders.set_border(ChunkSide::Right, vec![BlockType::Air; chunk_sizes.1 * chunk_sizes.2]);
+    chunk_borders.set_border(ChunkSide::Left, vec![BlockType::Stone; chunk_sizes.1 * chunk_sizes.2]);
+    chunk_borders.set_border(ChunkSide::Back, vec![BlockType::Stone; chunk_sizes.1 * chunk_sizes.0]);
+
+    run_check_air(&mut temp_chunk_vector, &chunk_borders, chunk_sizes);
+
+    // a block in the middle of the stone on each wall
+    let y: usize = 3;
+    let middle: usize = chunk_sizes.0 / 2;
+    assert!(temp_chunk_vector[chunk_sizes.0 - 1][y][middle].is_touching_air);
+    assert_eq!(temp_chunk_vector[chunk_sizes.0 - 1][y][middle].visible_faces, BlockFace::Right.bit());
+    assert!(!temp_chunk_vector[0][y][middle].is_touching_air);
+    assert!(!temp_chunk_vector[middle][y][chunk_sizes.2 - 1].is_touching_air);
+    assert!(!temp_chunk_vector[middle][y][0].is_touching_air);
+
+    // the bottom of the world is never drawn
+    assert!(!temp_chunk_vector[middle][0][middle].is_touching_air);
+}
+
 // testing a smaller size
 #[test]
 fn test_check_air_1() {
@@ -182,3 +217,29 @@ fn test_check_air_6() {
     test_check_air((256, 512, 256));
 }
 
+
+// the compute shader is valid without needing a gpu to compile it, and gets which blocks are fluids from a buffer
+#[test]
+fn test_check_air_shader_bindings() {
+    let module: wgpu::naga::Module = wgpu::naga::front::wgsl::parse_str(include_str!("../src/Shaders/check_air_compute.wgsl")).unwrap();
+    wgpu::naga::valid::Validator::new(wgpu::naga::valid::ValidationFlags::all(), wgpu::naga::valid::Capabilities::all())
+        .validate(&module)
+        .unwrap();
+
+    let mut bindings: Vec<(u32, String)> = module
+        .global_variables
+        .iter()
+        .map(|(_, global)| (global.binding.as_ref().unwrap().binding, global.name.clone().unwrap()))
+        .collect();
+    bindings.sort();
+    assert_eq!(
+        bindings,
+        vec![
+            (0, String::from("block_type_buffer")),
+            (1, String::from("block_transparency_buffer")),
+            (2, String::from("block_fluid_buffer")),
+            (3, String::from("dimentions_buffer")),
+            (4, String::from("result_buffer")),
+        ]
+    );
+}
@@ -0,0 +1,163 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_borders::*, chunk_cpu_functions::*, chunk_functions::*, chunk_gpu_functions::*, create_chunks::*},
+};
+
+use async_std::task;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+mod common;
+use common::*;
+
+
+
+// the block types the random chunks are made from, with fluids next to each other and next to other transparent blocks
+const RANDOM_BLOCK_TYPES: [BlockType; 7] = [
+    BlockType::Air,
+    BlockType::Stone,
+    BlockType::Dirt,
+    BlockType::Leaves,
+    BlockType::Water,
+    BlockType::FlowingWater(3),
+    BlockType::FlowingWater(7),
+];
+
+fn get_random_block_type(rng: &mut StdRng) -> BlockType {
+    RANDOM_BLOCK_TYPES[rng.gen_range(0..RANDOM_BLOCK_TYPES.len())]
+}
+
+// a chunk of random blocks and random borders on some of its sides
+fn make_random_chunk(chunk_sizes: (usize, usize, usize), seed: u64) -> (Vec<Vec<Vec<Block>>>, ChunkBorders) {
+    let mut rng: StdRng = StdRng::seed_from_u64(seed);
+
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+    for block in temp_chunk_vector.iter_mut().flatten().flatten() {
+        block.block_type = get_random_block_type(&mut rng);
+    }
+
+    // leave one side unloaded so its wall is next to void
+    let mut chunk_borders: ChunkBorders = ChunkBorders::new();
+    for side in [ChunkSide::Right, ChunkSide::Left, ChunkSide::Back] {
+        let border: Vec<BlockType> = (0..chunk_sizes.1 * side.get_width(chunk_sizes))
+            .map(|_| get_random_block_type(&mut rng))
+            .collect();
+        chunk_borders.set_border(side, border);
+    }
+
+    (temp_chunk_vector, chunk_borders)
+}
+
+// run both passes on the same chunk and check every block got the same faces
+fn compare_with_gpu(temp_chunk_vector: Vec<Vec<Vec<Block>>>, chunk_borders: &ChunkBorders, chunk_sizes: (usize, usize, usize)) {
+    let (device, queue, shader_code) = task::block_on(get_renderer_variables()).expect("There is no adapter to run the compute shader on");
+
+    let mut gpu_chunk_vector: Vec<Vec<Vec<Block>>> = temp_chunk_vector.clone();
+    task::block_on(check_for_touching_air(
+        &mut gpu_chunk_vector,
+        chunk_borders,
+        &device,
+        &queue,
+        &shader_code,
+        chunk_sizes,
+    ));
+
+    let mut cpu_chunk_vector: Vec<Vec<Vec<Block>>> = temp_chunk_vector;
+    check_for_touching_air_cpu(&mut cpu_chunk_vector, chunk_borders, chunk_sizes);
+
+    for x in 0..chunk_sizes.0 {
+        for y in 0..chunk_sizes.1 {
+            for z in 0..chunk_sizes.2 {
+                let (gpu_block, cpu_block) = (gpu_chunk_vector[x][y][z], cpu_chunk_vector[x][y][z]);
+                assert_eq!(cpu_block.visible_faces, gpu_block.visible_faces,
+                "The cpu and gpu visible faces are different for {:?} at ({}, {}, {})", cpu_block.block_type, x, y, z);
+                assert_eq!(cpu_block.is_touching_air, gpu_block.is_touching_air);
+            }
+        }
+    }
+}
+
+
+// random blocks with fluids and borders give the same faces as the compute shader
+#[test]
+#[ignore = "needs a gpu adapter to run the compute shader on"]
+fn test_cpu_matches_gpu_random() {
+    let chunk_sizes: (usize, usize, usize) = (8, 16, 8);
+    for seed in 0..4 {
+        let (temp_chunk_vector, chunk_borders) = make_random_chunk(chunk_sizes, seed);
+        compare_with_gpu(temp_chunk_vector, &chunk_borders, chunk_sizes);
+    }
+}
+
+// chunks wider than 62 blocks need more than one word per row
+#[test]
+#[ignore = "needs a gpu adapter to run the compute shader on"]
+fn test_cpu_matches_gpu_wide() {
+    let chunk_sizes: (usize, usize, usize) = (70, 8, 66);
+    let (temp_chunk_vector, chunk_borders) = make_random_chunk(chunk_sizes, 10);
+    compare_with_gpu(temp_chunk_vector, &chunk_borders, chunk_sizes);
+}
+
+// a generated chunk the size used in the game
+#[test]
+#[ignore = "needs a gpu adapter to run the compute shader on"]
+fn test_cpu_matches_gpu_generated() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+    generate_chunk(&mut temp_chunk_vector, (0, 0), chunk_sizes, 1);
+
+    let mut chunk_borders: ChunkBorders = ChunkBorders::new();
+    chunk_borders.set_border(ChunkSide::Front, vec![BlockType::Air; chunk_sizes.1 * chunk_sizes.0]);
+    compare_with_gpu(temp_chunk_vector, &chunk_borders, chunk_sizes);
+}
+
+// the cpu pass on its own, without needing a gpu
+#[test]
+fn test_cpu_touching_air() {
+    let chunk_sizes: (usize, usize, usize) = (4, 8, 4);
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+
+    // a stone block with water on top of it and water next to that, in the middle of the chunk
+    temp_chunk_vector[1][3][1].block_type = BlockType::Stone;
+    temp_chunk_vector[1][4][1].block_type = BlockType::Water;
+    temp_chunk_vector[2][4][1].block_type = BlockType::FlowingWater(6);
+
+    // and a block on the right wall with stone next to it in the next chunk
+    temp_chunk_vector[3][1][2].block_type = BlockType::Dirt;
+    let mut chunk_borders: ChunkBorders = ChunkBorders::new();
+    chunk_borders.set_border(ChunkSide::Right, vec![BlockType::Stone; chunk_sizes.1 * chunk_sizes.2]);
+
+    check_for_touching_air_cpu(&mut temp_chunk_vector, &chunk_borders, chunk_sizes);
+
+    // the water covers the top of the stone
+    assert_eq!(temp_chunk_vector[1][3][1].visible_faces, ALL_FACES_VISIBLE);
+
+    // the 2 waters dont show the face between them, but the bottom of the water is over the stone so it isnt showing either
+    assert_eq!(temp_chunk_vector[1][4][1].visible_faces, ALL_FACES_VISIBLE & !BlockFace::Right.bit() & !BlockFace::Bottom.bit());
+    assert_eq!(temp_chunk_vector[2][4][1].visible_faces, ALL_FACES_VISIBLE & !BlockFace::Left.bit());
+
+    // the wall of the chunk is covered by the next chunk
+    assert_eq!(temp_chunk_vector[3][1][2].visible_faces, ALL_FACES_VISIBLE & !BlockFace::Right.bit());
+
+    // air is never touching air
+    assert!(!temp_chunk_vector[0][0][0].is_touching_air);
+}
+
+// a software adapter uses the cpu pass
+#[test]
+fn test_touching_air_pass_from_adapter() {
+    let mut adapter_info: wgpu::AdapterInfo = wgpu::AdapterInfo {
+        name: String::from("test adapter"),
+        vendor: 0,
+        device: 0,
+        device_type: wgpu::DeviceType::DiscreteGpu,
+        driver: String::new(),
+        driver_info: String::new(),
+        backend: wgpu::Backend::Vulkan,
+    };
+    assert_eq!(TouchingAirPass::from_adapter_info(&adapter_info), TouchingAirPass::Gpu);
+
+    adapter_info.device_type = wgpu::DeviceType::Cpu;
+    assert_eq!(TouchingAirPass::from_adapter_info(&adapter_info), TouchingAirPass::Cpu);
+}
@@ -0,0 +1,140 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, decorate_chunks::*},
+    chunk_compression::*,
+    file_system::*,
+    world_generator::*,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+
+const RANDOM_BLOCK_TYPES: [BlockType; 8] = [
+    BlockType::Air,
+    BlockType::Stone,
+    BlockType::Dirt,
+    BlockType::Grass,
+    BlockType::Sand,
+    BlockType::Water,
+    BlockType::FlowingWater(5),
+    BlockType::DiamondOre,
+];
+
+// the blocks of a newly generated chunk, in the order they are saved in
+fn get_generated_block_types(chunk_id: (i32, i32), chunk_sizes: (usize, usize, usize)) -> Vec<BlockType> {
+    let generator: Box<dyn WorldGenerator> = WorldGeneratorType::default_noise().create_generator();
+    let temp_chunk_vec: Vec<Vec<Vec<Block>>> = read_or_generate_chunk(
+        &mut FileSystem::new(),
+        chunk_id,
+        false,
+        chunk_sizes,
+        generator.as_ref(),
+        4,
+        &mut PendingBlockWrites::new(),
+    ).unwrap();
+    get_temp_chunk_block_types(&temp_chunk_vec, chunk_sizes)
+}
+
+fn get_random_block_types(block_count: usize, seed: u64) -> Vec<BlockType> {
+    let mut rng: StdRng = StdRng::seed_from_u64(seed);
+    (0..block_count).map(|_| RANDOM_BLOCK_TYPES[rng.gen_range(0..RANDOM_BLOCK_TYPES.len())]).collect()
+}
+
+
+// a generated chunk reads back the same and is a lot smaller than saving every id
+#[test]
+fn test_generated_chunk_round_trip() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    for chunk_id in [(0, 0), (-3, 7)] {
+        let block_types: Vec<BlockType> = get_generated_block_types(chunk_id, chunk_sizes);
+
+        let data: Vec<u8> = encode_chunk_blocks(&block_types, chunk_sizes);
+        assert_eq!(decode_chunk_blocks(&data, chunk_sizes).unwrap(), block_types);
+
+        let raw_data: Vec<u8> = encode_chunk_blocks_raw(&block_types);
+        assert!(data.len() * 10 < raw_data.len(), "Compressed to {} bytes from {}", data.len(), raw_data.len());
+    }
+}
+
+// a chunk of only air is only a few bytes for each section
+#[test]
+fn test_air_chunk() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let block_types: Vec<BlockType> = vec![BlockType::Air; chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2];
+
+    let data: Vec<u8> = encode_chunk_blocks(&block_types, chunk_sizes);
+    assert!(data.len() <= 1 + (chunk_sizes.1 / SECTION_HEIGHT) * 8);
+    assert_eq!(decode_chunk_blocks(&data, chunk_sizes).unwrap(), block_types);
+}
+
+// random blocks (so very short runs), and a height that isnt a whole number of sections
+#[test]
+fn test_random_chunk_round_trip() {
+    for (seed, chunk_sizes) in [(1, (16, 64, 16)), (2, (5, 21, 3)), (3, (1, 1, 1))] {
+        let block_types: Vec<BlockType> = get_random_block_types(chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2, seed);
+
+        let data: Vec<u8> = encode_chunk_blocks(&block_types, chunk_sizes);
+        assert_eq!(decode_chunk_blocks(&data, chunk_sizes).unwrap(), block_types, "Different blocks for {:?}", chunk_sizes);
+    }
+}
+
+// chunks saved before they were compressed can still be read
+#[test]
+fn test_raw_format() {
+    let chunk_sizes: (usize, usize, usize) = (16, 64, 16);
+    let block_types: Vec<BlockType> = get_random_block_types(chunk_sizes.0 * chunk_sizes.1 * chunk_sizes.2, 9);
+
+    let raw_data: Vec<u8> = encode_chunk_blocks_raw(&block_types);
+    assert_eq!(raw_data.len(), block_types.len() * 2 + 1);
+    assert_eq!(decode_chunk_blocks(&raw_data, chunk_sizes).unwrap(), block_types);
+}
+
+// the same varint the run lengths are saved as
+fn push_varint(mut value: usize, data: &mut Vec<u8>) {
+    while value >= 0x80 {
+        data.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+// bytes that dont make a whole chunk are an error instead of a panic or the wrong blocks
+#[test]
+fn test_bad_data() {
+    let chunk_sizes: (usize, usize, usize) = (16, 64, 16);
+    let block_types: Vec<BlockType> = get_generated_block_types((1, 1), chunk_sizes);
+    let data: Vec<u8> = encode_chunk_blocks(&block_types, chunk_sizes);
+
+    // cut short
+    assert!(decode_chunk_blocks(&data[..data.len() - 1], chunk_sizes).is_err());
+    assert!(decode_chunk_blocks(&[], chunk_sizes).is_err());
+
+    // extra bytes on the end
+    let mut long_data: Vec<u8> = data.clone();
+    long_data.push(0);
+    assert!(decode_chunk_blocks(&long_data, chunk_sizes).is_err());
+
+    // unknown format
+    let mut bad_format: Vec<u8> = data.clone();
+    bad_format[0] = 200;
+    assert!(decode_chunk_blocks(&bad_format, chunk_sizes).is_err());
+
+    // saved with other chunk sizes
+    assert!(decode_chunk_blocks(&data, (16, 32, 16)).is_err());
+
+    // a palette of 1 air block with a palette index outside of it, and a run longer than the section
+    let section_size: usize = chunk_sizes.0 * chunk_sizes.2 * SECTION_HEIGHT;
+    assert!(decode_chunk_blocks(&[1, 1, 0, 0, 0, 1, 1], chunk_sizes).is_err());
+    let mut long_run: Vec<u8> = vec![1, 1, 0, 0, 0, 0];
+    push_varint(section_size + 1, &mut long_run);
+    assert!(decode_chunk_blocks(&long_run, chunk_sizes).is_err());
+
+    // a run so long that adding it to the blocks already read would overflow
+    for run_length in [usize::MAX, usize::MAX - 1, usize::MAX - section_size] {
+        let mut huge_run: Vec<u8> = vec![1, 1, 0, 0, 0, 0, 1, 0];
+        push_varint(run_length, &mut huge_run);
+        assert!(decode_chunk_blocks(&huge_run, chunk_sizes).is_err(), "Run of {} was read", run_length);
+    }
+}
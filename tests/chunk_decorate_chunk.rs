@@ -0,0 +1,227 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*, decorate_chunks::*},
+};
+
+use std::{collections::HashMap, fs};
+
+mod common;
+use common::*;
+
+
+// generate and decorate a chunk, then put in any writes already waiting for it, the same as load chunk does
+fn generate_decorated_chunk(
+    chunk_ids: (i32, i32),
+    chunk_sizes: (usize, usize, usize),
+    world_seed: u64,
+    pending_block_writes: &mut PendingBlockWrites,
+) -> Vec<Vec<Vec<Block>>> {
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_ids, chunk_sizes);
+    generate_chunk(&mut temp_chunk_vector, chunk_ids, chunk_sizes, world_seed);
+    decorate_chunk(&mut temp_chunk_vector, chunk_ids, chunk_sizes, world_seed, pending_block_writes);
+
+    let block_writes = pending_block_writes.take_chunk_writes(chunk_ids);
+    apply_block_writes(&mut temp_chunk_vector, &block_writes, chunk_sizes);
+
+    temp_chunk_vector
+}
+
+fn count_blocks(temp_chunk_vector: &Vec<Vec<Vec<Block>>>, block_type: BlockType) -> u32 {
+    let mut count: u32 = 0;
+    for x_vec in temp_chunk_vector.iter() {
+        for y_vec in x_vec.iter() {
+            for block in y_vec.iter() {
+                if block.block_type == block_type {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+// generate a square of chunks in the order given and keep them by id
+fn generate_chunks_in_order(
+    chunk_order: &[(i32, i32)],
+    chunk_sizes: (usize, usize, usize),
+    world_seed: u64,
+) -> (HashMap<(i32, i32), Vec<Vec<Vec<Block>>>>, PendingBlockWrites) {
+    let mut pending_block_writes: PendingBlockWrites = PendingBlockWrites::new();
+    let mut chunks: HashMap<(i32, i32), Vec<Vec<Vec<Block>>>> = HashMap::new();
+
+    for chunk_ids in chunk_order.iter() {
+        let chunk = generate_decorated_chunk(*chunk_ids, chunk_sizes, world_seed, &mut pending_block_writes);
+        chunks.insert(*chunk_ids, chunk);
+    }
+
+    // like a neighbour being loaded again after it was generated, writes for already made chunks are put in now
+    for (chunk_ids, chunk) in chunks.iter_mut() {
+        let block_writes = pending_block_writes.take_chunk_writes(*chunk_ids);
+        apply_block_writes(chunk, &block_writes, chunk_sizes);
+    }
+
+    (chunks, pending_block_writes)
+}
+
+fn get_chunk_square() -> Vec<(i32, i32)> {
+    let mut chunk_order: Vec<(i32, i32)> = Vec::new();
+    for chunk_x in -3..3 {
+        for chunk_z in -3..3 {
+            chunk_order.push((chunk_x, chunk_z));
+        }
+    }
+    chunk_order
+}
+
+
+// there should be trees in the plains and mountains
+#[test]
+fn test_decorate_chunk_has_trees() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let (chunks, _) = generate_chunks_in_order(&get_chunk_square(), chunk_sizes, 1);
+
+    let logs: u32 = chunks.values().map(|chunk| count_blocks(chunk, BlockType::Log)).sum();
+    let leaves: u32 = chunks.values().map(|chunk| count_blocks(chunk, BlockType::Leaves)).sum();
+
+    assert!(logs > 0, "No logs in 36 chunks");
+    assert!(leaves > logs, "Trees should have more leaves ({}) than logs ({})", leaves, logs);
+}
+
+// the same seed always gives the same trees
+#[test]
+fn test_decorate_chunk_deterministic() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+
+    for chunk_ids in [(0, 0), (-2, 3), (5, -1)] {
+        let first = generate_decorated_chunk(chunk_ids, chunk_sizes, 9, &mut PendingBlockWrites::new());
+        let second = generate_decorated_chunk(chunk_ids, chunk_sizes, 9, &mut PendingBlockWrites::new());
+
+        for x in 0..chunk_sizes.0 {
+            for y in 0..chunk_sizes.1 {
+                for z in 0..chunk_sizes.2 {
+                    assert_eq!(first[x][y][z].block_type, second[x][y][z].block_type);
+                }
+            }
+        }
+    }
+}
+
+// it shouldnt matter which order the chunks are generated in, trees crossing borders end up the same
+#[test]
+fn test_decorate_chunk_order_independent() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let chunk_order: Vec<(i32, i32)> = get_chunk_square();
+    let mut reversed_order: Vec<(i32, i32)> = chunk_order.clone();
+    reversed_order.reverse();
+
+    let (first_chunks, _) = generate_chunks_in_order(&chunk_order, chunk_sizes, 1);
+    let (second_chunks, _) = generate_chunks_in_order(&reversed_order, chunk_sizes, 1);
+
+    for chunk_ids in chunk_order.iter() {
+        let first = &first_chunks[chunk_ids];
+        let second = &second_chunks[chunk_ids];
+
+        for x in 0..chunk_sizes.0 {
+            for y in 0..chunk_sizes.1 {
+                for z in 0..chunk_sizes.2 {
+                    assert_eq!(
+                        first[x][y][z].block_type, second[x][y][z].block_type,
+                        "Block at ({}, {}, {}) depends on the generation order",
+                        first[x][y][z].position.x, first[x][y][z].position.y, first[x][y][z].position.z
+                    );
+                }
+            }
+        }
+    }
+}
+
+// trees near the edge of the square reach into chunks that havent been made, so they are left pending for them
+#[test]
+fn test_decorate_chunk_pending_writes_for_neighbours() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let chunk_order: Vec<(i32, i32)> = get_chunk_square();
+    let (_, pending_block_writes) = generate_chunks_in_order(&chunk_order, chunk_sizes, 1);
+
+    for chunk_ids in pending_block_writes.get_chunk_ids() {
+        assert!(!chunk_order.contains(&chunk_ids), "Chunk {:?} was generated but still has pending writes", chunk_ids);
+
+        for (block_pos, _) in pending_block_writes.writes[&chunk_ids].iter() {
+            assert_eq!(block_pos.0.div_euclid(32), chunk_ids.0);
+            assert_eq!(block_pos.2.div_euclid(32), chunk_ids.1);
+        }
+    }
+}
+
+// a tree right on the edge of a chunk puts its leaves into the neighbouring chunk
+#[test]
+fn test_place_tree_across_border() {
+    let chunk_sizes: (usize, usize, usize) = (8, 32, 8);
+    let mut pending_block_writes: PendingBlockWrites = PendingBlockWrites::new();
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+
+    place_tree(&mut temp_chunk_vector, (0, 0), chunk_sizes, (7, 0, 3), 5, 1, &mut pending_block_writes);
+
+    // the trunk is all in this chunk
+    for y in 0..5 {
+        assert_eq!(temp_chunk_vector[7][16 + y][3].block_type, BlockType::Log);
+    }
+
+    // the leaves to the right are in chunk (1, 0)
+    assert!(pending_block_writes.has_chunk_writes((1, 0)));
+    assert!(!pending_block_writes.has_chunk_writes((-1, 0)));
+
+    let mut neighbour: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((1, 0), chunk_sizes);
+    let block_writes = pending_block_writes.take_chunk_writes((1, 0));
+    apply_block_writes(&mut neighbour, &block_writes, chunk_sizes);
+
+    assert_eq!(neighbour[0][16 + 4][3].block_type, BlockType::Leaves);
+    assert!(!pending_block_writes.has_chunk_writes((1, 0)));
+}
+
+// decorations only go into air, and logs win over leaves
+#[test]
+fn test_apply_block_writes_rules() {
+    let chunk_sizes: (usize, usize, usize) = (8, 16, 8);
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+    temp_chunk_vector[1][8][1].block_type = BlockType::Stone;
+    temp_chunk_vector[2][8][2].block_type = BlockType::Leaves;
+    temp_chunk_vector[3][8][3].block_type = BlockType::Log;
+
+    apply_block_writes(
+        &mut temp_chunk_vector,
+        &[
+            ((1, 0, 1), BlockType::Leaves),
+            ((2, 0, 2), BlockType::Log),
+            ((3, 0, 3), BlockType::Leaves),
+            ((4, 0, 4), BlockType::Leaves),
+            ((4, 100, 4), BlockType::Leaves), // above the chunk, ignored
+        ],
+        chunk_sizes,
+    );
+
+    assert_eq!(temp_chunk_vector[1][8][1].block_type, BlockType::Stone);
+    assert_eq!(temp_chunk_vector[2][8][2].block_type, BlockType::Log);
+    assert_eq!(temp_chunk_vector[3][8][3].block_type, BlockType::Log);
+    assert_eq!(temp_chunk_vector[4][8][4].block_type, BlockType::Leaves);
+}
+
+// pending writes are saved when the world closes and read back when it opens
+#[test]
+fn test_pending_block_writes_round_trip() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_pending_block_writes");
+
+    let mut pending_block_writes: PendingBlockWrites = PendingBlockWrites::new();
+    pending_block_writes.add_block_write((-1, 5, 40), BlockType::Leaves, chunk_sizes);
+    pending_block_writes.add_block_write((64, -3, -1), BlockType::Log, chunk_sizes);
+    file_system.save_pending_block_writes(&pending_block_writes).unwrap();
+
+    let mut read_writes: PendingBlockWrites = file_system.read_pending_block_writes(chunk_sizes).unwrap();
+    assert_eq!(read_writes.take_chunk_writes((-1, 1)), vec![((-1, 5, 40), BlockType::Leaves)]);
+    assert_eq!(read_writes.take_chunk_writes((2, -1)), vec![((64, -3, -1), BlockType::Log)]);
+    assert!(read_writes.is_empty());
+
+    fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,240 @@
+extern crate rust_craft;
+use rust_craft::{
+    biome::*,
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*},
+};
+
+
+fn generate_test_chunk(chunk_ids: (i32, i32), chunk_sizes: (usize, usize, usize), world_seed: u64) -> Vec<Vec<Vec<Block>>> {
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_ids, chunk_sizes);
+    generate_chunk(&mut temp_chunk_vector, chunk_ids, chunk_sizes, world_seed);
+
+    temp_chunk_vector
+}
+
+// get just the block ids so chunks can be compared
+fn chunk_block_ids(temp_chunk_vector: &Vec<Vec<Vec<Block>>>) -> Vec<u16> {
+    let mut ids: Vec<u16> = Vec::new();
+    for x_vec in temp_chunk_vector.iter() {
+        for y_vec in x_vec.iter() {
+            for block in y_vec.iter() {
+                ids.push(block.block_type.to_int());
+            }
+        }
+    }
+    ids
+}
+
+
+// the same seed should always make exactly the same chunk
+fn test_generate_chunk_deterministic(chunk_ids: (i32, i32), chunk_sizes: (usize, usize, usize), world_seed: u64) {
+    let first: Vec<u16> = chunk_block_ids(&generate_test_chunk(chunk_ids, chunk_sizes, world_seed));
+    let second: Vec<u16> = chunk_block_ids(&generate_test_chunk(chunk_ids, chunk_sizes, world_seed));
+
+    assert_eq!(first, second, "Chunk {:?} with seed {} was different the second time", chunk_ids, world_seed);
+}
+
+#[test]
+fn test_generate_chunk_deterministic_1() {
+    test_generate_chunk_deterministic((0, 0), (32, 256, 32), 1);
+}
+
+#[test]
+fn test_generate_chunk_deterministic_2() {
+    test_generate_chunk_deterministic((-3, 7), (32, 256, 32), 123456789);
+}
+
+#[test]
+fn test_generate_chunk_deterministic_3() {
+    test_generate_chunk_deterministic((2, -1), (8, 16, 8), u64::MAX);
+}
+
+
+#[test]
+fn test_generate_chunk_different_seeds() {
+    let first: Vec<u16> = chunk_block_ids(&generate_test_chunk((0, 0), (32, 256, 32), 1));
+    let second: Vec<u16> = chunk_block_ids(&generate_test_chunk((0, 0), (32, 256, 32), 2));
+
+    assert_ne!(first, second, "Different seeds made the same chunk");
+}
+
+
+// one big chunk should have exactly the same blocks as 4 small chunks covering the same area
+// since the terrain only depends on world coordinates, this means chunk edges line up
+fn test_generate_chunk_seamless(world_seed: u64) {
+    let big_chunk: Vec<Vec<Vec<Block>>> = generate_test_chunk((-1, -1), (64, 256, 64), world_seed);
+
+    for (chunk_x, chunk_z) in [(-2, -2), (-1, -2), (-2, -1), (-1, -1)] {
+        let small_chunk: Vec<Vec<Vec<Block>>> = generate_test_chunk((chunk_x, chunk_z), (32, 256, 32), world_seed);
+
+        for x in 0..32 {
+            for y in 0..256 {
+                for z in 0..32 {
+                    let small_block: &Block = &small_chunk[x][y][z];
+                    let big_x: usize = (small_block.position.x + 64) as usize;
+                    let big_z: usize = (small_block.position.z + 64) as usize;
+                    let big_block: &Block = &big_chunk[big_x][y][big_z];
+
+                    assert_eq!(big_block.position.x, small_block.position.x);
+                    assert_eq!(big_block.position.z, small_block.position.z);
+                    assert_eq!(
+                        big_block.block_type, small_block.block_type,
+                        "Block at ({}, {}, {}) does not match between chunk sizes",
+                        small_block.position.x, small_block.position.y, small_block.position.z
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generate_chunk_seamless_1() {
+    test_generate_chunk_seamless(1);
+}
+
+#[test]
+fn test_generate_chunk_seamless_2() {
+    test_generate_chunk_seamless(987654321);
+}
+
+
+// every column should be bedrock at the bottom, then stone, then the biomes filler, then its surface block at the surface height with air above
+// anything under the surface can also be air if a cave was carved there
+fn test_generate_chunk_layers(chunk_ids: (i32, i32), chunk_sizes: (usize, usize, usize), world_seed: u64) {
+    let chunk: Vec<Vec<Vec<Block>>> = generate_test_chunk(chunk_ids, chunk_sizes, world_seed);
+
+    for x in 0..chunk_sizes.0 {
+        for z in 0..chunk_sizes.2 {
+            let block: &Block = &chunk[x][0][z];
+            let surface_height: usize = get_surface_height(block.position.x, block.position.z, chunk_sizes, world_seed);
+            let (surface_block, filler_block) =
+                get_biome(block.position.x, block.position.z, world_seed).get_surface_blocks(surface_height, chunk_sizes);
+
+            assert_eq!(chunk[x][0][z].block_type, BlockType::Bedrock);
+
+            for y in 1..chunk_sizes.1 {
+                let expected: BlockType = if y > surface_height {
+                    BlockType::Air
+                } else if y == surface_height {
+                    surface_block
+                } else if y >= surface_height - 2 {
+                    filler_block
+                } else {
+                    BlockType::Stone
+                };
+
+                let block_type: BlockType = chunk[x][y][z].block_type;
+                let is_cave: bool = is_cave(block.position.x, y as i32 - (chunk_sizes.1 as i32 / 2), block.position.z, world_seed);
+
+                if is_cave {
+                    assert_eq!(block_type, BlockType::Air);
+                } else if expected == BlockType::Stone {
+                    // ore veins replace some of the stone
+                    assert!(
+                        block_type == BlockType::Stone || ORE_SETTINGS.iter().any(|ore| ore.block_type == block_type),
+                        "Expected stone or ore but got {:?}", block_type
+                    );
+                } else {
+                    assert_eq!(block_type, expected);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generate_chunk_layers_1() {
+    test_generate_chunk_layers((0, 0), (32, 256, 32), 1);
+}
+
+#[test]
+fn test_generate_chunk_layers_2() {
+    test_generate_chunk_layers((5, -5), (8, 16, 8), 42);
+}
+
+
+// caves should never carve through the bedrock floor
+#[test]
+fn test_generate_chunk_bedrock_floor() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+
+    for chunk_x in -3..3 {
+        for chunk_z in -3..3 {
+            let chunk: Vec<Vec<Vec<Block>>> = generate_test_chunk((chunk_x, chunk_z), chunk_sizes, 7);
+
+            for x in 0..chunk_sizes.0 {
+                for z in 0..chunk_sizes.2 {
+                    assert_eq!(chunk[x][0][z].block_type, BlockType::Bedrock);
+                }
+            }
+        }
+    }
+}
+
+// there should be some caves underground
+#[test]
+fn test_generate_chunk_has_caves() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let mut cave_blocks: u32 = 0;
+
+    for chunk_x in -2..2 {
+        for chunk_z in -2..2 {
+            let chunk: Vec<Vec<Vec<Block>>> = generate_test_chunk((chunk_x, chunk_z), chunk_sizes, 1);
+
+            for x in 0..chunk_sizes.0 {
+                for z in 0..chunk_sizes.2 {
+                    let surface_height: usize = get_surface_height(
+                        chunk[x][0][z].position.x,
+                        chunk[x][0][z].position.z,
+                        chunk_sizes,
+                        1,
+                    );
+
+                    for y in 1..surface_height {
+                        if chunk[x][y][z].block_type == BlockType::Air {
+                            cave_blocks += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    assert!(cave_blocks > 0, "No caves were carved in 16 chunks");
+}
+
+// very short chunks still get a surface inside the chunk instead of a panic
+#[test]
+fn test_surface_height_short_chunks() {
+    for chunk_height in 1..5 {
+        for world_x in -20..20 {
+            let surface_height: usize = get_surface_height(world_x, world_x * 3, (16, chunk_height, 16), 7);
+            assert!(surface_height < chunk_height, "Surface {} is outside a chunk {} high", surface_height, chunk_height);
+        }
+    }
+}
+
+// very short chunks generate without a panic, and the layers above the bedrock never write over it
+#[test]
+fn test_generate_short_chunks() {
+    for chunk_height in 1..5 {
+        let chunk_sizes: (usize, usize, usize) = (4, chunk_height, 4);
+        for chunk_x in -3..3 {
+            let chunk: Vec<Vec<Vec<Block>>> = generate_test_chunk((chunk_x, chunk_x * 2), chunk_sizes, 7);
+
+            for x_vec in chunk.iter() {
+                for block in x_vec[0].iter() {
+                    assert_eq!(block.block_type, BlockType::Bedrock, "No bedrock in a chunk {} high", chunk_height);
+                }
+                for (y, y_vec) in x_vec.iter().enumerate().skip(1) {
+                    for block in y_vec.iter() {
+                        assert_ne!(block.block_type, BlockType::Bedrock, "Bedrock at y {} in a chunk {} high", y, chunk_height);
+                    }
+                }
+            }
+        }
+    }
+}
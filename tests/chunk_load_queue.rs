@@ -0,0 +1,240 @@
+extern crate rust_craft;
+use rust_craft::{
+    chunk::chunk_borders::*,
+    chunk_load_queue::*,
+    chunk_loader::*,
+    render_backend::*,
+    world::*,
+    world_generator::*,
+};
+
+use std::{
+    collections::HashSet,
+    fs,
+    time::{Duration, Instant},
+};
+
+mod common;
+use common::*;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (16, 64, 16);
+const WORLD_SEED: u64 = 3;
+
+fn get_chunk_ids_around(centre: (i32, i32), radius: i32) -> HashSet<(i32, i32)> {
+    let mut chunk_ids: HashSet<(i32, i32)> = HashSet::new();
+    for x in -radius..=radius {
+        for z in -radius..=radius {
+            chunk_ids.insert((centre.0 + x, centre.1 + z));
+        }
+    }
+    chunk_ids
+}
+
+fn get_distance(chunk_id: (i32, i32), centre: (i32, i32)) -> f32 {
+    (((chunk_id.0 - centre.0).pow(2) + (chunk_id.1 - centre.1).pow(2)) as f32).sqrt()
+}
+
+
+// closer chunks come first, and in front comes before behind at the same distance
+#[test]
+fn test_chunk_load_priority() {
+    let centre: (i32, i32) = (4, -2);
+    assert_eq!(get_chunk_load_priority(centre, centre, None), 0.0);
+    assert_eq!(get_chunk_load_priority(centre, centre, Some((1.0, 0.0))), 0.0);
+    assert_eq!(get_chunk_load_priority((7, 2), centre, None), 5.0);
+
+    // looking along +x
+    let look_direction: Option<(f32, f32)> = Some((1.0, 0.0));
+    let in_front: f32 = get_chunk_load_priority((6, -2), centre, look_direction);
+    let to_the_side: f32 = get_chunk_load_priority((4, 0), centre, look_direction);
+    let behind: f32 = get_chunk_load_priority((2, -2), centre, look_direction);
+    assert_eq!(in_front, 2.0);
+    assert!(in_front < to_the_side && to_the_side < behind);
+    assert_eq!(behind, 3.0);
+
+    // a chunk right next to the character is still before one far away in front
+    assert!(get_chunk_load_priority((3, -2), centre, look_direction) < get_chunk_load_priority((6, -2), centre, look_direction));
+
+    // looking straight down doesnt prefer any way
+    assert_eq!(get_chunk_load_priority((2, -2), centre, Some((0.0, 0.0))), 2.0);
+}
+
+// the queue gives back the nearest chunks first, and ones out of range can be cancelled
+#[test]
+fn test_queue_order_and_cancel() {
+    let mut queue: ChunkLoadQueue = ChunkLoadQueue::new(ChunkLoadBudget::unlimited());
+    queue.set_centre((10, 10), None);
+
+    let chunk_ids: HashSet<(i32, i32)> = get_chunk_ids_around((10, 10), 2);
+    for chunk_id in chunk_ids.iter() {
+        queue.queue_chunk(*chunk_id);
+    }
+    // queueing a chunk again doesnt add it twice
+    queue.queue_chunk((10, 10));
+    assert_eq!(queue.get_queue_depth(), 25);
+
+    assert_eq!(queue.pop_next_chunk(), Some((10, 10)));
+
+    // cancel everything outside radius 1
+    let in_range: HashSet<(i32, i32)> = get_chunk_ids_around((10, 10), 1);
+    assert_eq!(queue.cancel_chunks_not_in(&in_range), 16);
+    assert!(!queue.is_queued((12, 12)));
+
+    let mut last_distance: f32 = 0.0;
+    let mut popped: HashSet<(i32, i32)> = HashSet::new();
+    while let Some(chunk_id) = queue.pop_next_chunk() {
+        let distance: f32 = get_distance(chunk_id, (10, 10));
+        assert!(distance >= last_distance);
+        last_distance = distance;
+        popped.insert(chunk_id);
+    }
+    assert_eq!(popped.len(), 8);
+    assert!(popped.iter().all(|chunk_id| in_range.contains(chunk_id)));
+
+    let metrics: ChunkLoadMetrics = queue.get_metrics(0);
+    assert_eq!(metrics.queue_depth, 0);
+    assert_eq!(metrics.max_queue_depth, 25);
+    assert_eq!(metrics.total_cancelled, 16);
+}
+
+// a budget stops after its chunks or its time, but always lets at least 1 chunk through
+#[test]
+fn test_budget() {
+    let frame_start: Instant = Instant::now();
+    assert!(!ChunkLoadBudget::unlimited().is_spent(1000, frame_start));
+
+    let chunk_budget: ChunkLoadBudget = ChunkLoadBudget::new(Some(3), None);
+    assert!(!chunk_budget.is_spent(2, frame_start));
+    assert!(chunk_budget.is_spent(3, frame_start));
+
+    let time_budget: ChunkLoadBudget = ChunkLoadBudget::new(None, Some(Duration::ZERO));
+    assert!(!time_budget.is_spent(0, frame_start));
+    assert!(time_budget.is_spent(1, frame_start));
+}
+
+// with a budget the world only loads a few chunks a frame, nearest first, until they are all loaded
+#[test]
+fn test_world_loads_within_budget() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_load_queue_budget");
+    let mut world: World = World::new(String::from("queue"), WORLD_SEED, 1, CHUNK_SIZES);
+    world.chunk_load_queue.budget = ChunkLoadBudget::new(Some(2), None);
+
+    let chunks_to_load: HashSet<(i32, i32)> = get_chunk_ids_around((0, 0), 1);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load.clone());
+
+    // the chunk in the middle is always first
+    assert_eq!(world.pending_chunks.len(), 2);
+    assert!(world.pending_chunks.contains_key(&(0, 0)));
+    let metrics: ChunkLoadMetrics = world.get_chunk_load_metrics();
+    assert_eq!(metrics.queue_depth, 7);
+    assert_eq!(metrics.loaded_last_frame, 2);
+
+    // the next frames load the sides before the corners
+    world.load_queued_chunks(&HeadlessBackend, &mut file_system);
+    world.update_pending_chunks(&HeadlessBackend);
+    assert_eq!(world.chunks.len(), 4);
+    assert!(world.chunks.keys().all(|chunk_id| get_distance(*chunk_id, (0, 0)) <= 1.0));
+
+    let mut frames: usize = 2;
+    while world.get_chunk_load_metrics().queue_depth > 0 {
+        world.load_queued_chunks(&HeadlessBackend, &mut file_system);
+        frames += 1;
+    }
+    world.update_pending_chunks(&HeadlessBackend);
+
+    assert_eq!(frames, 5);
+    assert_eq!(world.chunks.keys().cloned().collect::<HashSet<(i32, i32)>>(), chunks_to_load);
+    assert_eq!(world.get_chunk_load_metrics().total_loaded, 9);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// chunks that go out of range before they are loaded are never loaded
+#[test]
+fn test_world_cancels_out_of_range_chunks() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_load_queue_cancel");
+    let mut world: World = World::new(String::from("queue"), WORLD_SEED, 2, CHUNK_SIZES);
+    world.chunk_load_queue.budget = ChunkLoadBudget::new(Some(1), None);
+
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, get_chunk_ids_around((0, 0), 2));
+    assert_eq!(world.get_chunk_load_metrics().queue_depth, 24);
+
+    // the character moves away before most of them are loaded
+    world.chunk_load_queue.set_centre((5, 0), None);
+    let chunks_to_load: HashSet<(i32, i32)> = get_chunk_ids_around((5, 0), 2);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load.clone());
+
+    // none of the old chunks are in range any more, so only the new ones are left (less the one loaded this frame)
+    let metrics: ChunkLoadMetrics = world.get_chunk_load_metrics();
+    assert_eq!(metrics.total_cancelled, 24);
+    assert_eq!(metrics.queue_depth, 24);
+
+    while world.get_chunk_load_metrics().queue_depth > 0 {
+        world.load_queued_chunks(&HeadlessBackend, &mut file_system);
+    }
+    world.update_pending_chunks(&HeadlessBackend);
+
+    // the chunk at (0, 0) was loaded before the character moved, it is unloaded next time the chunks around the character change
+    assert!(world.created_chunks.iter().all(|chunk_id| chunks_to_load.contains(chunk_id) || *chunk_id == (0, 0)));
+    assert!(chunks_to_load.iter().all(|chunk_id| world.chunks.contains_key(chunk_id)));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// chunks cancelled while a worker has them are thrown away when they come back, unless they are wanted again
+#[test]
+fn test_chunk_loader_cancel() {
+    let (file_system, path) = make_test_file_system("rust_craft_test_chunk_load_queue_loader");
+    let mut chunk_loader: ChunkLoader = ChunkLoader::new(1, &file_system, &WorldGeneratorType::Flat, WORLD_SEED, CHUNK_SIZES);
+    assert!(chunk_loader.has_room_for_requests());
+
+    chunk_loader.request_chunk((0, 0), false, ChunkBorders::new());
+    chunk_loader.request_chunk((1, 0), false, ChunkBorders::new());
+    chunk_loader.request_chunk((2, 0), false, ChunkBorders::new());
+
+    assert_eq!(chunk_loader.cancel_chunks_not_in(&HashSet::from([(0, 0)])), 2);
+    assert_eq!(chunk_loader.get_loading_count(), 1);
+    assert!(!chunk_loader.is_loading((1, 0)));
+
+    // wanted again before it came back
+    chunk_loader.request_chunk((2, 0), false, ChunkBorders::new());
+    assert!(chunk_loader.is_loading((2, 0)));
+
+    let mut loaded_ids: HashSet<(i32, i32)> = HashSet::new();
+    while let Some(loaded_chunk) = chunk_loader.wait_for_loaded_chunk() {
+        loaded_ids.insert(loaded_chunk.chunk_id);
+    }
+    assert_eq!(loaded_ids, HashSet::from([(0, 0), (2, 0)]));
+    assert_eq!(chunk_loader.get_loading_count(), 0);
+
+    // only the chunk that was never wanted again was thrown away
+    assert_eq!(chunk_loader.get_thrown_away_count(), 1);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// with workers the nearest chunks are sent first and only a few at a time
+#[test]
+fn test_world_sends_nearest_chunks_to_workers() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_load_queue_workers");
+    let mut world: World = World::new(String::from("queue"), WORLD_SEED, 3, CHUNK_SIZES);
+    world.start_chunk_loader(&file_system, 1);
+
+    let chunks_to_load: HashSet<(i32, i32)> = get_chunk_ids_around((0, 0), 3);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load.clone());
+
+    // the rest wait in the queue
+    let metrics: ChunkLoadMetrics = world.get_chunk_load_metrics();
+    assert!(metrics.loading_count + metrics.queue_depth + world.pending_chunks.len() == chunks_to_load.len());
+    assert!(metrics.queue_depth > 0);
+    assert!(!world.chunk_load_queue.is_queued((0, 0)));
+
+    world.wait_for_loading_chunks(&HeadlessBackend, &mut file_system);
+    world.update_pending_chunks(&HeadlessBackend);
+    assert_eq!(world.chunks.keys().cloned().collect::<HashSet<(i32, i32)>>(), chunks_to_load);
+    assert_eq!(world.get_chunk_load_metrics().queue_depth, 0);
+
+    world.stop_chunk_loader();
+    fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,286 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_borders::*, chunk_cpu_functions::*, chunk_functions::*, decorate_chunks::*, *},
+    chunk_loader::*,
+    error::*,
+    file_system::*,
+    region_file::*,
+    render_backend::*,
+    world::*,
+    world_generator::*,
+};
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+};
+
+mod common;
+use common::*;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (16, 64, 16);
+const WORLD_SEED: u64 = 7;
+
+fn get_chunk_ids_around_origin(radius: i32) -> HashSet<(i32, i32)> {
+    let mut chunk_ids: HashSet<(i32, i32)> = HashSet::new();
+    for x in -radius..=radius {
+        for z in -radius..=radius {
+            chunk_ids.insert((x, z));
+        }
+    }
+    chunk_ids
+}
+
+// every block and its visible faces are the same in both worlds
+fn assert_worlds_match(world_a: &World, world_b: &World) {
+    assert_eq!(
+        world_a.chunks.keys().cloned().collect::<HashSet<(i32, i32)>>(),
+        world_b.chunks.keys().cloned().collect::<HashSet<(i32, i32)>>()
+    );
+
+    for (chunk_id, chunk_a) in world_a.chunks.iter() {
+        let chunk_b = &world_b.chunks[chunk_id];
+        assert_eq!(chunk_a.chunk_blocks.len(), chunk_b.chunk_blocks.len(), "Chunk {:?} has a different number of blocks", chunk_id);
+
+        for (block_pos, block_a) in chunk_a.chunk_blocks.iter() {
+            let block_b: &Block = &chunk_b.chunk_blocks[block_pos];
+            assert_eq!(block_a.block_type, block_b.block_type, "Different block at {:?}", block_pos);
+            assert_eq!(block_a.visible_faces, block_b.visible_faces, "Different visible faces at {:?}", block_pos);
+        }
+    }
+}
+
+
+// a worker gives back the same chunk as generating it on the main thread
+#[test]
+fn test_worker_generates_same_chunk() {
+    let (file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_generate");
+    let generator_type: WorldGeneratorType = WorldGeneratorType::default_noise();
+    let mut chunk_loader: ChunkLoader = ChunkLoader::new(2, &file_system, &generator_type, WORLD_SEED, CHUNK_SIZES);
+
+    chunk_loader.request_chunk((2, -1), false, ChunkBorders::new());
+    assert!(chunk_loader.is_loading((2, -1)));
+
+    let loaded_chunk: LoadedChunk = chunk_loader.wait_for_loaded_chunk().unwrap();
+    assert_eq!(loaded_chunk.chunk_id, (2, -1));
+    assert!(loaded_chunk.is_generated);
+    assert!(!chunk_loader.is_loading((2, -1)));
+    assert!(chunk_loader.wait_for_loaded_chunk().is_none());
+
+    let mut pending_block_writes: PendingBlockWrites = PendingBlockWrites::new();
+    let temp_chunk_vec: Vec<Vec<Vec<Block>>> = read_or_generate_chunk(
+        &mut file_system.clone(),
+        (2, -1),
+        false,
+        CHUNK_SIZES,
+        generator_type.create_generator().as_ref(),
+        WORLD_SEED,
+        &mut pending_block_writes,
+    ).unwrap();
+
+    for (worker_block, block) in loaded_chunk.temp_chunk_vec.iter().flatten().flatten().zip(temp_chunk_vec.iter().flatten().flatten()) {
+        assert_eq!(worker_block.block_type, block.block_type);
+    }
+    assert_eq!(loaded_chunk.pending_block_writes.writes, pending_block_writes.writes);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// the worker runs the touching air pass against the borders it was sent, so the main thread doesnt have to
+#[test]
+fn test_worker_checks_touching_air() {
+    let (file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_touching_air");
+    let generator_type: WorldGeneratorType = WorldGeneratorType::default_noise();
+    let mut chunk_loader: ChunkLoader = ChunkLoader::new(1, &file_system, &generator_type, WORLD_SEED, CHUNK_SIZES);
+
+    let make_borders = || -> ChunkBorders {
+        let mut chunk_borders: ChunkBorders = ChunkBorders::new();
+        chunk_borders.set_border(ChunkSide::Right, vec![BlockType::Air; CHUNK_SIZES.1 * CHUNK_SIZES.2]);
+        chunk_borders.set_border(ChunkSide::Front, vec![BlockType::Stone; CHUNK_SIZES.1 * CHUNK_SIZES.0]);
+        chunk_borders
+    };
+    chunk_loader.request_chunk((1, 3), false, make_borders());
+    let loaded_chunk: LoadedChunk = chunk_loader.wait_for_loaded_chunk().unwrap();
+    assert_eq!(loaded_chunk.chunk_borders.get_border(ChunkSide::Right), make_borders().get_border(ChunkSide::Right));
+
+    let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = read_or_generate_chunk(
+        &mut file_system.clone(),
+        (1, 3),
+        false,
+        CHUNK_SIZES,
+        generator_type.create_generator().as_ref(),
+        WORLD_SEED,
+        &mut PendingBlockWrites::new(),
+    ).unwrap();
+    check_for_touching_air_cpu(&mut temp_chunk_vec, &make_borders(), CHUNK_SIZES);
+
+    assert!(loaded_chunk.temp_chunk_vec.iter().flatten().flatten().any(|block| block.is_touching_air));
+    for (worker_block, block) in loaded_chunk.temp_chunk_vec.iter().flatten().flatten().zip(temp_chunk_vec.iter().flatten().flatten()) {
+        assert_eq!(worker_block.visible_faces, block.visible_faces);
+    }
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// asking for a chunk that is already loading doesnt load it twice
+#[test]
+fn test_duplicate_requests() {
+    let (file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_duplicate");
+    let mut chunk_loader: ChunkLoader = ChunkLoader::new(1, &file_system, &WorldGeneratorType::Flat, WORLD_SEED, CHUNK_SIZES);
+
+    chunk_loader.request_chunk((0, 0), false, ChunkBorders::new());
+    chunk_loader.request_chunk((0, 0), false, ChunkBorders::new());
+    chunk_loader.request_chunk((1, 0), false, ChunkBorders::new());
+    assert_eq!(chunk_loader.get_loading_count(), 2);
+
+    let mut loaded_ids: HashSet<(i32, i32)> = HashSet::new();
+    while let Some(loaded_chunk) = chunk_loader.wait_for_loaded_chunk() {
+        assert!(loaded_ids.insert(loaded_chunk.chunk_id));
+    }
+    assert_eq!(loaded_ids, HashSet::from([(0, 0), (1, 0)]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// loading on the workers ends up with the same world as loading on the main thread, whatever order the chunks come back in
+#[test]
+fn test_world_loads_same_as_main_thread() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_world");
+    let chunks_to_load: HashSet<(i32, i32)> = get_chunk_ids_around_origin(2);
+
+    let mut main_thread_world: World = World::new(String::from("main thread"), WORLD_SEED, 2, CHUNK_SIZES);
+    main_thread_world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load.clone());
+    main_thread_world.update_pending_chunks(&HeadlessBackend);
+
+    let mut worker_world: World = World::new(String::from("workers"), WORLD_SEED, 2, CHUNK_SIZES);
+    worker_world.start_chunk_loader(&file_system, 4);
+    worker_world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load.clone());
+
+    // nothing is in the world until it comes back from the workers
+    assert!(worker_world.chunks.is_empty() && worker_world.pending_chunks.is_empty());
+
+    worker_world.wait_for_loading_chunks(&HeadlessBackend, &mut file_system);
+    worker_world.update_pending_chunks(&HeadlessBackend);
+
+    assert_eq!(worker_world.created_chunks, chunks_to_load);
+    assert_worlds_match(&main_thread_world, &worker_world);
+    assert_eq!(main_thread_world.pending_block_writes.writes.len(), worker_world.pending_block_writes.writes.len());
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// chunks that were saved are read back from their files by the workers
+#[test]
+fn test_worker_reads_saved_chunks() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_read");
+    let chunks_to_load: HashSet<(i32, i32)> = get_chunk_ids_around_origin(1);
+
+    let mut world: World = World::new(String::from("workers"), WORLD_SEED, 1, CHUNK_SIZES);
+    world.start_chunk_loader(&file_system, 2);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load.clone());
+    world.wait_for_loading_chunks(&HeadlessBackend, &mut file_system);
+    world.update_pending_chunks(&HeadlessBackend);
+
+    let top_y: i16 = CHUNK_SIZES.1 as i16 / 2 - 1;
+    world.set_block(3, top_y, -5, BlockType::Cobblestone).unwrap();
+
+    // unload them all and load them again
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::new());
+    assert!(world.chunks.is_empty());
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load);
+    world.wait_for_loading_chunks(&HeadlessBackend, &mut file_system);
+    world.update_pending_chunks(&HeadlessBackend);
+
+    assert_eq!(world.get_block(3, top_y, -5).unwrap(), BlockType::Cobblestone);
+
+    world.stop_chunk_loader();
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// stopping the loader with chunks still waiting doesnt hang, and they are never added to the created chunks
+#[test]
+fn test_stop_chunk_loader() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_stop");
+
+    let mut world: World = World::new(String::from("workers"), WORLD_SEED, 4, CHUNK_SIZES);
+    world.start_chunk_loader(&file_system, 1);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, get_chunk_ids_around_origin(4));
+    world.stop_chunk_loader();
+
+    assert!(world.created_chunks.is_empty());
+    assert!(world.chunks.is_empty());
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// change a byte of the first chunk saved in the region (0, 0), so its checksum doesnt match
+fn corrupt_first_chunk(file_system: &FileSystem) {
+    let region_path: PathBuf = file_system.get_region_path((0, 0));
+    let mut region_bytes: Vec<u8> = fs::read(&region_path).unwrap();
+    region_bytes[20 + 256 * 16 + 5] ^= 0xFF;
+    fs::write(&region_path, region_bytes).unwrap();
+}
+
+// a worker gives a corrupted chunk back as an error without touching the region, only the main thread changes the region files
+#[test]
+fn test_worker_doesnt_quarantine() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_corrupted_worker");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+    let chunk: Chunk = make_chunk(&mut file_system, (1, 1), CHUNK_SIZES, WORLD_SEED);
+    file_system.save_chunk_to_file(&chunk, CHUNK_SIZES).unwrap();
+    corrupt_first_chunk(&file_system);
+
+    let mut chunk_loader: ChunkLoader = ChunkLoader::new(1, &file_system, &WorldGeneratorType::default_noise(), WORLD_SEED, CHUNK_SIZES);
+    chunk_loader.request_chunk((1, 1), true, ChunkBorders::new());
+    assert!(chunk_loader.wait_for_loaded_chunk().is_none());
+    assert!(!chunk_loader.is_loading((1, 1)));
+
+    assert!(chunk_loader.has_corrupted_chunks());
+    let corrupted_chunks: Vec<((i32, i32), GameError)> = chunk_loader.take_corrupted_chunks();
+    assert_eq!(corrupted_chunks.len(), 1);
+    assert!(matches!(corrupted_chunks[0], ((1, 1), GameError::CorruptedChunk(..))));
+    assert!(!chunk_loader.has_corrupted_chunks());
+
+    assert!(!path.join("Quarantine").exists());
+    let region_file: RegionFile = RegionFile::open(&file_system.get_region_path((0, 0)), CHUNK_SIZES).unwrap();
+    assert!(region_file.has_chunk((1, 1)));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a corrupted chunk found by the workers is quarantined on the main thread and generated again
+#[test]
+fn test_world_quarantines_corrupted_worker_chunk() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_chunk_loader_corrupted_world");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+
+    let mut world: World = World::new(String::from("workers"), WORLD_SEED, 1, CHUNK_SIZES);
+    world.start_chunk_loader(&file_system, 2);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(0, 0)]));
+    world.wait_for_loading_chunks(&HeadlessBackend, &mut file_system);
+    world.update_pending_chunks(&HeadlessBackend);
+    let generated_block: BlockType = world.get_block(3, 0, 4).unwrap();
+
+    let top_y: i16 = CHUNK_SIZES.1 as i16 / 2 - 1;
+    world.set_block(3, top_y, 4, BlockType::Cobblestone).unwrap();
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::new());
+    corrupt_first_chunk(&file_system);
+
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(0, 0)]));
+    world.wait_for_loading_chunks(&HeadlessBackend, &mut file_system);
+    world.update_pending_chunks(&HeadlessBackend);
+
+    // it is back in the world as it was generated, without the block that was set
+    assert!(world.chunks.contains_key(&(0, 0)));
+    assert!(world.created_chunks.contains(&(0, 0)));
+    assert_eq!(world.get_block(3, 0, 4).unwrap(), generated_block);
+    assert_ne!(world.get_block(3, top_y, 4).unwrap(), BlockType::Cobblestone);
+    assert!(path.join("Quarantine").join("0_0.chunk").exists());
+
+    world.stop_chunk_loader();
+    fs::remove_dir_all(&path).unwrap();
+}
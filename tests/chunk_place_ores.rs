@@ -0,0 +1,130 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*},
+};
+
+
+// make a chunk that is all stone (above the bedrock) and put the ores in it, so no ore is hidden by caves or dirt
+fn create_stone_chunk_with_ores(chunk_ids: (i32, i32), chunk_sizes: (usize, usize, usize), world_seed: u64) -> Vec<Vec<Vec<Block>>> {
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_ids, chunk_sizes);
+    generate_superflat_chunk(
+        &mut temp_chunk_vector,
+        &[(BlockType::Bedrock, 1), (BlockType::Stone, chunk_sizes.1)],
+        chunk_sizes,
+    );
+    place_ores(&mut temp_chunk_vector, chunk_ids, chunk_sizes, world_seed);
+
+    temp_chunk_vector
+}
+
+// how many of each ore (in the same order as ORE_SETTINGS) are in the chunk
+fn count_ores(temp_chunk_vector: &Vec<Vec<Vec<Block>>>) -> [u32; 4] {
+    let mut counts: [u32; 4] = [0; 4];
+    for x_vec in temp_chunk_vector.iter() {
+        for y_vec in x_vec.iter() {
+            for block in y_vec.iter() {
+                for (ore_index, ore) in ORE_SETTINGS.iter().enumerate() {
+                    if block.block_type == ore.block_type {
+                        counts[ore_index] += 1;
+                    }
+                }
+            }
+        }
+    }
+    counts
+}
+
+
+// ores should only ever be inside of their height range
+fn test_ore_heights(chunk_ids: (i32, i32), chunk_sizes: (usize, usize, usize), world_seed: u64) {
+    let chunk: Vec<Vec<Vec<Block>>> = create_stone_chunk_with_ores(chunk_ids, chunk_sizes, world_seed);
+
+    for x in 0..chunk_sizes.0 {
+        for y in 0..chunk_sizes.1 {
+            for z in 0..chunk_sizes.2 {
+                for ore in ORE_SETTINGS.iter() {
+                    if chunk[x][y][z].block_type == ore.block_type {
+                        assert!(
+                            y >= ore.min_height && y <= ore.max_height,
+                            "{:?} at height {} is outside of its range {}..={}",
+                            ore.block_type, y, ore.min_height, ore.max_height
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // the bedrock is never replaced
+    for x in 0..chunk_sizes.0 {
+        for z in 0..chunk_sizes.2 {
+            assert_eq!(chunk[x][0][z].block_type, BlockType::Bedrock);
+        }
+    }
+}
+
+#[test]
+fn test_ore_heights_1() {
+    test_ore_heights((0, 0), (32, 256, 32), 1);
+}
+
+#[test]
+fn test_ore_heights_2() {
+    test_ore_heights((-4, 9), (32, 256, 32), 555);
+}
+
+// small chunks dont go all the way up the ranges, so the veins have to be squashed into the chunk
+#[test]
+fn test_ore_heights_small_chunk() {
+    test_ore_heights((1, 1), (8, 16, 8), 3);
+}
+
+
+// the same seed should always place exactly the same ore, and a different seed different ore
+#[test]
+fn test_ore_deterministic() {
+    let first: [u32; 4] = count_ores(&create_stone_chunk_with_ores((2, 3), (32, 256, 32), 10));
+    let second: [u32; 4] = count_ores(&create_stone_chunk_with_ores((2, 3), (32, 256, 32), 10));
+    assert_eq!(first, second);
+
+    let first_ids: Vec<u16> = create_stone_chunk_with_ores((2, 3), (32, 256, 32), 10)
+        .iter().flatten().flatten().map(|block| block.block_type.to_int()).collect();
+    let other_seed_ids: Vec<u16> = create_stone_chunk_with_ores((2, 3), (32, 256, 32), 11)
+        .iter().flatten().flatten().map(|block| block.block_type.to_int()).collect();
+    assert_ne!(first_ids, other_seed_ids);
+}
+
+
+// across a group of chunks every ore should show up, and the rarer ores should be rarer
+#[test]
+fn test_ore_counts() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let mut total_counts: [u32; 4] = [0; 4];
+
+    for chunk_x in -2..2 {
+        for chunk_z in -2..2 {
+            let counts: [u32; 4] = count_ores(&create_stone_chunk_with_ores((chunk_x, chunk_z), chunk_sizes, 1));
+            for ore_index in 0..4 {
+                total_counts[ore_index] += counts[ore_index];
+            }
+        }
+    }
+
+    for (ore_index, ore) in ORE_SETTINGS.iter().enumerate() {
+        assert!(total_counts[ore_index] > 0, "No {:?} in 16 chunks", ore.block_type);
+    }
+
+    assert!(total_counts[0] > total_counts[1], "Coal should be more common than iron: {:?}", total_counts);
+    assert!(total_counts[1] > total_counts[3], "Iron should be more common than diamond: {:?}", total_counts);
+}
+
+// the ore block types save and load as the same block
+#[test]
+fn test_ore_block_ids() {
+    for ore in ORE_SETTINGS.iter() {
+        assert_eq!(BlockType::from_int(ore.block_type.to_int()).unwrap(), ore.block_type);
+        assert!(!ore.block_type.is_transparent());
+    }
+}
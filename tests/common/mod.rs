@@ -0,0 +1,80 @@
+// helpers shared by the integration tests, each test file only uses some of them
+#![allow(dead_code)]
+
+extern crate rust_craft;
+use rust_craft::{
+    chunk::{chunk_borders::*, chunk_functions::*, decorate_chunks::*, *},
+    file_system::*,
+    render_backend::*,
+    world_generator::*,
+};
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+};
+use wgpu::{Device, Queue, ShaderModule};
+
+
+// none if there isnt an adapter (like on ci)
+pub async fn get_renderer_variables() -> Option<(Device, Queue, ShaderModule)> {
+    let instance = wgpu::Instance::default();
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        })
+        .await?;
+
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default(),
+            },
+            None,
+        )
+        .await
+        .ok()?;
+
+    let shader_code = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("check air compute shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("../../src/Shaders/check_air_compute.wgsl").into()),
+    });
+
+    Some((device, queue, shader_code))
+}
+
+// a file system in the temp folder so chunks can be saved without the assets folder
+pub fn make_test_file_system(name: &str) -> (FileSystem, PathBuf) {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(path.join("Chunks")).unwrap();
+
+    let mut file_system: FileSystem = FileSystem::new();
+    file_system.my_world_directory = path.clone();
+    (file_system, path)
+}
+
+// a generated chunk with trees and ores, so it has lots of different blocks
+pub fn make_chunk(file_system: &mut FileSystem, chunk_id: (i32, i32), chunk_sizes: (usize, usize, usize), world_seed: u64) -> Chunk {
+    let generator: Box<dyn WorldGenerator> = WorldGeneratorType::default_noise().create_generator();
+    let mut chunk: Chunk = Chunk::new(chunk_id.0, chunk_id.1, -1, &HeadlessBackend);
+    chunk.load_chunk(
+        file_system,
+        &HeadlessBackend,
+        chunk_sizes,
+        &mut HashSet::new(),
+        ChunkGenerationContext {
+            generator: generator.as_ref(),
+            world_seed,
+            pending_block_writes: &mut PendingBlockWrites::new(),
+            chunk_borders: &ChunkBorders::new(),
+        },
+    ).unwrap();
+    chunk
+}
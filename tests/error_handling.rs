@@ -0,0 +1,294 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, decorate_chunks::*},
+    chunk_compression::*,
+    error::*,
+    file_system::*,
+    region_file::*,
+    render_backend::*,
+    world::*,
+    world_generator::*,
+};
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+};
+
+mod common;
+use common::*;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (16, 64, 16);
+const WORLD_SEED: u64 = 5;
+
+// generate a chunk, or read it if it has been created
+fn try_read_or_generate(file_system: &mut FileSystem, chunk_id: (i32, i32), is_created: bool) -> Result<Vec<BlockType>, GameError> {
+    let generator: Box<dyn WorldGenerator> = WorldGeneratorType::default_noise().create_generator();
+    let temp_chunk_vec: Vec<Vec<Vec<Block>>> = read_or_generate_chunk(
+        file_system,
+        chunk_id,
+        is_created,
+        CHUNK_SIZES,
+        generator.as_ref(),
+        WORLD_SEED,
+        &mut PendingBlockWrites::new(),
+    )?;
+    Ok(get_temp_chunk_block_types(&temp_chunk_vec, CHUNK_SIZES))
+}
+
+fn read_or_generate(file_system: &mut FileSystem, chunk_id: (i32, i32), is_created: bool) -> Vec<BlockType> {
+    try_read_or_generate(file_system, chunk_id, is_created).unwrap()
+}
+
+// save a generated chunk into its region file
+fn save_chunk(file_system: &mut FileSystem, chunk_id: (i32, i32)) -> Vec<BlockType> {
+    let block_types: Vec<BlockType> = read_or_generate(file_system, chunk_id, false);
+    let mut region_file: RegionFile = RegionFile::open(&file_system.get_region_path(get_region_id(chunk_id)), CHUNK_SIZES).unwrap();
+    region_file.write_chunk(chunk_id, &encode_chunk_blocks(&block_types, CHUNK_SIZES)).unwrap();
+    block_types
+}
+
+
+// an unknown block id is an error instead of a panic, wherever it is read from
+#[test]
+fn test_unknown_block_type() {
+    assert_eq!(BlockType::from_int(400).unwrap(), BlockType::Stone);
+    match BlockType::from_int(9999) {
+        Err(GameError::UnknownBlockType(id)) => assert_eq!(id, 9999),
+        other => panic!("Expected an unknown block type error, got {:?}", other),
+    }
+    assert!(BlockType::from_int(9999).unwrap_err().to_string().contains("9999"));
+
+    // a raw chunk with one block id that doesnt exist
+    let mut data: Vec<u8> = encode_chunk_blocks_raw(&vec![BlockType::Air; CHUNK_SIZES.0 * CHUNK_SIZES.1 * CHUNK_SIZES.2]);
+    data[1..3].copy_from_slice(&9999u16.to_le_bytes());
+    let error_message: String = decode_chunk_blocks(&data, CHUNK_SIZES).unwrap_err().to_string();
+    assert!(error_message.contains("9999"), "{}", error_message);
+}
+
+// a chunk with no file, or a text file that is cut short or has a bad id, gives an error saying which chunk it was
+#[test]
+fn test_read_chunk_errors() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_error_handling_read");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+    let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((3, 4), CHUNK_SIZES);
+
+    match file_system.read_chunks_from_file(&mut temp_chunk_vec, 3, 4, CHUNK_SIZES) {
+        Err(GameError::ChunkFileMissing(chunk_id, missing_path)) => {
+            assert_eq!(chunk_id, (3, 4));
+            assert_eq!(missing_path, path.join("Chunks").join("3_4.txt"));
+        }
+        other => panic!("Expected a missing chunk error, got {:?}", other),
+    }
+
+    // only the first 2 layers
+    let block_types: Vec<BlockType> = vec![BlockType::Stone; CHUNK_SIZES.0 * CHUNK_SIZES.1 * CHUNK_SIZES.2];
+    let text: String = encode_chunk_blocks_text(&block_types, CHUNK_SIZES);
+    let layer_length: usize = text.len() / CHUNK_SIZES.1;
+    fs::write(path.join("Chunks").join("3_4.txt"), &text[..layer_length * 2]).unwrap();
+    match file_system.read_chunks_from_file(&mut temp_chunk_vec, 3, 4, CHUNK_SIZES) {
+        Err(GameError::CorruptedChunk(chunk_id, _, message)) => {
+            assert_eq!(chunk_id, (3, 4));
+            assert!(message.contains("2 of 64 layers"), "{}", message);
+        }
+        other => panic!("Expected a corrupted chunk error, got {:?}", other),
+    }
+
+    fs::write(path.join("Chunks").join("3_4.txt"), text.replacen("400", "9999", 1)).unwrap();
+    let error_message: String = file_system.read_chunks_from_file(&mut temp_chunk_vec, 3, 4, CHUNK_SIZES).unwrap_err().to_string();
+    assert!(error_message.contains("\"9999\" on line 1"), "{}", error_message);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a corrupted chunk is moved into Quarantine and generated again, the other chunks in its region are kept
+#[test]
+fn test_corrupted_chunk_is_quarantined_and_regenerated() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_error_handling_quarantine");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+    let generated_block_types: Vec<BlockType> = save_chunk(&mut file_system, (1, 1));
+    let other_block_types: Vec<BlockType> = save_chunk(&mut file_system, (2, 1));
+
+    // the bytes of chunk (1, 1) are first in the file after the header, so change one of them
+    let region_path: PathBuf = file_system.get_region_path((0, 0));
+    let mut region_bytes: Vec<u8> = fs::read(&region_path).unwrap();
+    region_bytes[20 + 256 * 16 + 5] ^= 0xFF;
+    fs::write(&region_path, region_bytes).unwrap();
+
+    assert_eq!(read_or_generate(&mut file_system, (1, 1), true), generated_block_types);
+
+    let quarantine_path: PathBuf = path.join("Quarantine").join("1_1.chunk");
+    assert!(quarantine_path.exists());
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert!(!region_file.has_chunk((1, 1)));
+    assert!(region_file.read_chunk((2, 1)).unwrap().is_some());
+    assert_eq!(read_or_generate(&mut file_system, (2, 1), true), other_block_types);
+
+    // a created chunk with nothing saved at all is generated again too
+    assert_eq!(read_or_generate(&mut file_system, (1, 1), true), generated_block_types);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a region file with a broken header is moved into Quarantine as a whole
+#[test]
+fn test_broken_region_is_quarantined() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_error_handling_broken_region");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+    let generated_block_types: Vec<BlockType> = save_chunk(&mut file_system, (-1, -1));
+
+    let region_path: PathBuf = file_system.get_region_path((-1, -1));
+    fs::write(&region_path, "not a region").unwrap();
+
+    assert_eq!(read_or_generate(&mut file_system, (-1, -1), true), generated_block_types);
+    assert!(!region_path.exists());
+    assert_eq!(fs::read_to_string(path.join("Quarantine").join("r_-1_-1.region")).unwrap(), "not a region");
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a region or chunk from a newer version of the game is an error, it isnt quarantined or generated again so it isnt saved over
+#[test]
+fn test_newer_region_is_not_quarantined() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_error_handling_newer_region");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+    save_chunk(&mut file_system, (3, 3));
+
+    // the version is the u32 after "RCRG"
+    let region_path: PathBuf = file_system.get_region_path((0, 0));
+    let mut region_bytes: Vec<u8> = fs::read(&region_path).unwrap();
+    region_bytes[4] = 200;
+    fs::write(&region_path, &region_bytes).unwrap();
+
+    match try_read_or_generate(&mut file_system, (3, 3), true) {
+        Err(GameError::NewerChunkVersion(chunk_id, error_path, message)) => {
+            assert_eq!(chunk_id, (3, 3));
+            assert_eq!(error_path, region_path);
+            assert!(message.contains("newer version"), "{}", message);
+        }
+        other => panic!("Expected a newer version error, got {:?}", other.map(|_| ())),
+    }
+    assert_eq!(fs::read(&region_path).unwrap(), region_bytes);
+    assert!(!path.join("Quarantine").exists());
+
+    // the world leaves it out instead of generating it
+    let mut world: World = World::new(String::from("errors"), WORLD_SEED, 1, CHUNK_SIZES);
+    world.created_chunks.insert((3, 3));
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(3, 3)]));
+    world.update_pending_chunks(&HeadlessBackend);
+    assert!(world.get_chunk((3, 3)).is_none());
+    assert_eq!(fs::read(&region_path).unwrap(), region_bytes);
+
+    // a chunk saved in a newer compression format is the same
+    save_chunk(&mut file_system, (-5, 2));
+    let mut region_file: RegionFile = RegionFile::open(&file_system.get_region_path((-1, 0)), CHUNK_SIZES).unwrap();
+    region_file.write_chunk((-5, 2), &[200]).unwrap();
+    drop(region_file);
+    assert!(matches!(try_read_or_generate(&mut file_system, (-5, 2), true), Err(GameError::NewerChunkVersion(..))));
+    let mut region_file: RegionFile = RegionFile::open(&file_system.get_region_path((-1, 0)), CHUNK_SIZES).unwrap();
+    assert_eq!(region_file.read_chunk((-5, 2)).unwrap(), Some(vec![200]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a chunk that cant be saved is an error instead of a panic, and the world keeps it loaded so it isnt lost
+#[test]
+fn test_failed_save_keeps_chunk() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_error_handling_failed_save");
+    // the regions folder cant be made when there is a file in the way
+    fs::write(path.join("Regions"), "not a folder").unwrap();
+
+    let mut world: World = World::new(String::from("errors"), WORLD_SEED, 1, CHUNK_SIZES);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(0, 0)]));
+    world.update_pending_chunks(&HeadlessBackend);
+    assert!(world.chunks.contains_key(&(0, 0)));
+
+    match world.remove_chunk((0, 0), &mut file_system) {
+        Err(GameError::SaveFailed(error_path, _)) => assert_eq!(error_path, path.join("Regions")),
+        other => panic!("Expected a save error, got {:?}", other),
+    }
+    assert!(world.chunks.contains_key(&(0, 0)));
+
+    // going out of range doesnt throw it away either
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(4, 4)]));
+    assert!(world.chunks.contains_key(&(0, 0)));
+
+    // once the file is out of the way it can be saved
+    fs::remove_file(path.join("Regions")).unwrap();
+    world.remove_chunk((0, 0), &mut file_system).unwrap();
+    assert!(!world.chunks.contains_key(&(0, 0)));
+    assert!(file_system.get_region_path((0, 0)).exists());
+
+    // the world files give errors too, a temp file cant be renamed over a folder
+    fs::create_dir_all(path.join("ScheduledTicks.txt")).unwrap();
+    match file_system.save_scheduled_ticks(&world.tick_scheduler) {
+        Err(GameError::SaveFailed(error_path, _)) => assert_eq!(error_path, path.join("ScheduledTicks.txt")),
+        other => panic!("Expected a save error, got {:?}", other),
+    }
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a broken ChunksCreated.txt says which line is wrong, and the created chunks can be found from the saved chunks instead
+#[test]
+fn test_malformed_created_chunks_file() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_error_handling_created_chunks");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+    let mut world: World = World::new(String::from("errors"), WORLD_SEED, 1, CHUNK_SIZES);
+
+    let created_chunks: HashSet<(i32, i32)> = HashSet::from([(0, 0)]);
+    file_system.save_created_chunks_file(CHUNK_SIZES, &created_chunks).unwrap();
+    world.load_created_chunks_file(&mut file_system).unwrap();
+    assert_eq!(world.created_chunks, HashSet::from([(0, 0)]));
+
+    world.created_chunks.clear();
+    fs::write(path.join("ChunksCreated.txt"), "Total Chunks Created : 3\nChunk Sizes: (16, 64, 16)\nCreated Chunks: \n0 0\n1 x\n").unwrap();
+    match world.load_created_chunks_file(&mut file_system) {
+        Err(GameError::MalformedFile(error_path, line_number, message)) => {
+            assert_eq!(error_path, path.join("ChunksCreated.txt"));
+            assert_eq!(line_number, 5);
+            assert!(message.contains("\"x\""), "{}", message);
+        }
+        other => panic!("Expected a malformed file error, got {:?}", other),
+    }
+    assert!(world.created_chunks.is_empty());
+
+    fs::write(path.join("ChunksCreated.txt"), "Total Chunks Created : 3\nChunk Sizes: (16, 64, 16)\nCreated Chunks: \n0 0\n").unwrap();
+    let error_message: String = world.load_created_chunks_file(&mut file_system).unwrap_err().to_string();
+    assert!(error_message.contains("after 1 of 3 chunks"), "{}", error_message);
+
+    // work them out again from the region and text files
+    save_chunk(&mut file_system, (0, 0));
+    save_chunk(&mut file_system, (17, -3));
+    fs::write(path.join("Chunks").join("-40_2.txt"), "").unwrap();
+    world.rebuild_created_chunks(&file_system).unwrap();
+    assert_eq!(world.created_chunks, HashSet::from([(0, 0), (17, -3), (-40, 2)]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a bad line in the pending block writes or scheduled ticks is an error with its line number
+#[test]
+fn test_malformed_world_files() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_error_handling_world_files");
+    fs::create_dir_all(path.join("Regions")).unwrap();
+
+    fs::write(path.join("PendingBlockWrites.txt"), "1 2 3 400\n1 2 3 9999\n").unwrap();
+    match file_system.read_pending_block_writes(CHUNK_SIZES) {
+        Err(GameError::MalformedFile(_, line_number, message)) => {
+            assert_eq!(line_number, 2);
+            assert!(message.contains("9999"), "{}", message);
+        }
+        other => panic!("Expected a malformed file error, got {:?}", other.map(|_| ())),
+    }
+
+    fs::write(path.join("ScheduledTicks.txt"), "Current Tick: soon\n").unwrap();
+    let error_message: String = file_system.read_scheduled_ticks().err().unwrap().to_string();
+    assert!(error_message.contains("Line 1"), "{}", error_message);
+
+    fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,249 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*, decorate_chunks::BlockWrite},
+    fluid::*,
+};
+
+use std::collections::{HashMap, HashSet};
+
+
+// a small test world, a stone floor at y = 0 with air above it, from -10 to 10 in x and z and up to y = 10
+// anything outside of it cant be read, like an unloaded chunk
+struct TestWorld {
+    blocks: HashMap<(i32, i16, i32), BlockType>,
+    fluid_updates: HashSet<(i32, i16, i32)>,
+}
+
+impl TestWorld {
+    fn new() -> TestWorld {
+        let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+        for x in -10..=10 {
+            for z in -10..=10 {
+                blocks.insert((x, 0, z), BlockType::Stone);
+            }
+        }
+
+        TestWorld {
+            blocks,
+            fluid_updates: HashSet::new(),
+        }
+    }
+
+    fn get_block(&self, block_pos: (i32, i16, i32)) -> Option<BlockType> {
+        if block_pos.0.abs() > 10 || block_pos.2.abs() > 10 || block_pos.1 < 0 || block_pos.1 > 10 {
+            return None;
+        }
+        Some(*self.blocks.get(&block_pos).unwrap_or(&BlockType::Air))
+    }
+
+    fn set_block(&mut self, block_pos: (i32, i16, i32), block_type: BlockType) {
+        self.blocks.insert(block_pos, block_type);
+        self.fluid_updates.extend(get_positions_to_update(&[(block_pos, block_type)]));
+    }
+
+    // run one tick, the same way the world does, and return what changed
+    fn tick(&mut self) -> Vec<BlockWrite> {
+        let positions: HashSet<(i32, i16, i32)> = std::mem::take(&mut self.fluid_updates);
+        let block_writes: Vec<BlockWrite> = simulate_fluid_tick(&positions, |block_pos| self.get_block(block_pos));
+
+        for (block_pos, block_type) in block_writes.iter() {
+            self.set_block(*block_pos, *block_type);
+        }
+        block_writes
+    }
+
+    // tick until nothing changes, panic if it never settles
+    fn settle(&mut self) -> u32 {
+        for ticks in 0..200 {
+            if self.tick().is_empty() {
+                return ticks;
+            }
+        }
+        panic!("Water never stopped flowing");
+    }
+}
+
+
+// a source on the floor spreads out sideways, one level lower each block, until it runs out
+#[test]
+fn test_fluid_spreads_with_decreasing_level() {
+    let mut world: TestWorld = TestWorld::new();
+    world.set_block((0, 1, 0), BlockType::Water);
+    world.settle();
+
+    assert_eq!(world.get_block((0, 1, 0)), Some(BlockType::Water));
+    for distance in 1..=7 {
+        let expected: BlockType = BlockType::FlowingWater(8 - distance as u8);
+        assert_eq!(world.get_block((distance, 1, 0)), Some(expected));
+        assert_eq!(world.get_block((0, 1, -distance)), Some(expected));
+    }
+
+    // it runs out after 7 blocks
+    assert_eq!(world.get_block((8, 1, 0)), Some(BlockType::Air));
+
+    // the level goes down with the distance around corners too
+    assert_eq!(world.get_block((2, 1, 3)), Some(BlockType::FlowingWater(3)));
+
+    // it doesnt go up
+    assert_eq!(world.get_block((0, 2, 0)), Some(BlockType::Air));
+}
+
+// water in the air falls straight down before it spreads, then spreads from where it lands
+#[test]
+fn test_fluid_falls_down() {
+    let mut world: TestWorld = TestWorld::new();
+    world.set_block((0, 6, 0), BlockType::Water);
+
+    world.tick();
+    assert_eq!(world.get_block((0, 5, 0)), Some(BlockType::FlowingWater(MAX_FLOW_LEVEL)));
+    assert_eq!(world.get_block((1, 6, 0)), Some(BlockType::Air), "Water spread sideways when it could fall");
+
+    world.settle();
+
+    for y in 1..6 {
+        assert_eq!(world.get_block((0, y, 0)), Some(BlockType::FlowingWater(MAX_FLOW_LEVEL)));
+    }
+    assert_eq!(world.get_block((1, 6, 0)), Some(BlockType::Air));
+    assert_eq!(world.get_block((1, 1, 0)), Some(BlockType::FlowingWater(6)));
+    assert_eq!(world.get_block((6, 1, 0)), Some(BlockType::FlowingWater(1)));
+    assert_eq!(world.get_block((7, 1, 0)), Some(BlockType::Air));
+}
+
+// solid blocks stop the water, and it flows around them
+#[test]
+fn test_fluid_blocked_by_solid() {
+    let mut world: TestWorld = TestWorld::new();
+    world.set_block((1, 1, 0), BlockType::Stone);
+    world.set_block((0, 1, 0), BlockType::Water);
+    world.settle();
+
+    assert_eq!(world.get_block((1, 1, 0)), Some(BlockType::Stone));
+    assert_eq!(world.get_block((2, 1, 0)), Some(BlockType::FlowingWater(4)));
+}
+
+// taking the source away makes all of the flowing water dry up
+#[test]
+fn test_fluid_dries_up() {
+    let mut world: TestWorld = TestWorld::new();
+    world.set_block((0, 4, 0), BlockType::Water);
+    world.settle();
+    assert_ne!(world.get_block((3, 1, 0)), Some(BlockType::Air));
+
+    world.set_block((0, 4, 0), BlockType::Air);
+    world.settle();
+
+    for (block_pos, block_type) in world.blocks.iter() {
+        assert!(!block_type.is_fluid(), "Water left at {:?}", block_pos);
+    }
+}
+
+// the same starting world always flows in exactly the same way
+#[test]
+fn test_fluid_deterministic() {
+    let run = || {
+        let mut world: TestWorld = TestWorld::new();
+        world.set_block((2, 1, 2), BlockType::Stone);
+        world.set_block((-3, 1, 1), BlockType::Stone);
+        world.set_block((0, 5, 0), BlockType::Water);
+        world.set_block((4, 3, -2), BlockType::Water);
+
+        let mut all_writes: Vec<Vec<BlockWrite>> = Vec::new();
+        for _ in 0..30 {
+            all_writes.push(world.tick());
+        }
+        all_writes
+    };
+
+    assert_eq!(run(), run());
+}
+
+// unreadable blocks (unloaded chunks) are treated like walls
+#[test]
+fn test_fluid_stops_at_unloaded_blocks() {
+    let mut world: TestWorld = TestWorld::new();
+    world.set_block((10, 1, 0), BlockType::Water);
+    world.settle();
+
+    assert_eq!(world.get_block((9, 1, 0)), Some(BlockType::FlowingWater(7)));
+    assert_eq!(world.get_block((11, 1, 0)), None);
+}
+
+#[test]
+fn test_fluid_next_state() {
+    let blocks: HashMap<(i32, i16, i32), BlockType> =
+        HashMap::from([((0, 0, 0), BlockType::Stone), ((1, 1, 0), BlockType::Water), ((0, 2, 0), BlockType::Air)]);
+    let get_block = |block_pos: (i32, i16, i32)| Some(*blocks.get(&block_pos).unwrap_or(&BlockType::Air));
+
+    // sources and solid blocks never change
+    assert_eq!(get_next_fluid_state((1, 1, 0), &get_block), None);
+    assert_eq!(get_next_fluid_state((0, 0, 0), &get_block), None);
+
+    // next to a source sitting on air, so it is falling not spreading
+    assert_eq!(get_next_fluid_state((0, 1, 0), &get_block), Some(BlockType::Air));
+}
+
+
+// the sea fills down from the sea level to the ground, but not into caves that arnt open to it
+#[test]
+fn test_fill_water() {
+    let chunk_sizes: (usize, usize, usize) = (4, 32, 4);
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+
+    for x in 0..4 {
+        for z in 0..4 {
+            for y in 0..10 {
+                temp_chunk_vector[x][y][z].block_type = BlockType::Stone;
+            }
+            temp_chunk_vector[x][10][z].block_type = BlockType::Grass;
+            // a cave under the ground
+            temp_chunk_vector[x][5][z].block_type = BlockType::Air;
+        }
+    }
+    // one column is lower
+    temp_chunk_vector[0][10][0].block_type = BlockType::Air;
+    temp_chunk_vector[0][9][0].block_type = BlockType::Grass;
+
+    // sea level index 13
+    fill_water(&mut temp_chunk_vector, chunk_sizes, -3);
+
+    for x in 0..4 {
+        for z in 0..4 {
+            for y in 11..=13 {
+                assert_eq!(temp_chunk_vector[x][y][z].block_type, BlockType::Water);
+            }
+            assert_eq!(temp_chunk_vector[x][14][z].block_type, BlockType::Air);
+            assert_eq!(temp_chunk_vector[x][5][z].block_type, BlockType::Air, "The cave was flooded");
+        }
+    }
+
+    assert_eq!(temp_chunk_vector[0][10][0].block_type, BlockType::Water);
+    assert_eq!(temp_chunk_vector[0][9][0].block_type, BlockType::Dirt);
+    assert_eq!(temp_chunk_vector[1][10][1].block_type, BlockType::Dirt);
+}
+
+// water ids are saved and read back with their level
+#[test]
+fn test_water_block_ids() {
+    assert_eq!(BlockType::from_int(BlockType::Water.to_int()).unwrap(), BlockType::Water);
+    for level in 1..=MAX_FLOW_LEVEL {
+        let block_type: BlockType = BlockType::FlowingWater(level);
+        assert_eq!(BlockType::from_int(block_type.to_int()).unwrap(), block_type);
+    }
+}
+
+// water is see through and can be moved through, and the inside of a lake isnt drawn
+#[test]
+fn test_water_properties() {
+    assert!(BlockType::Water.is_transparent());
+    assert!(!BlockType::Water.is_solid());
+    assert!(!BlockType::FlowingWater(3).is_solid());
+    assert!(BlockType::Stone.is_solid());
+
+    assert!(BlockType::Stone.is_face_visible(BlockType::Water));
+    assert!(BlockType::Water.is_face_visible(BlockType::Air));
+    assert!(!BlockType::Water.is_face_visible(BlockType::Water));
+    assert!(!BlockType::Water.is_face_visible(BlockType::FlowingWater(4)));
+    assert!(!BlockType::Water.is_face_visible(BlockType::Stone));
+}
@@ -0,0 +1,152 @@
+extern crate rust_craft;
+use rust_craft::{
+    block_type::*,
+    chunk::decorate_chunks::BlockWrite,
+    grass::*,
+};
+
+use std::collections::HashMap;
+
+
+// a small test world, a dirt floor at y = 0 with air above it, blocks outside of x and z -5 to 5 cant be read
+struct TestWorld {
+    blocks: HashMap<(i32, i16, i32), BlockType>,
+}
+
+impl TestWorld {
+    fn new() -> TestWorld {
+        let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+        for x in -5..=5 {
+            for z in -5..=5 {
+                blocks.insert((x, 0, z), BlockType::Dirt);
+            }
+        }
+
+        TestWorld { blocks }
+    }
+
+    fn get_block(&self, block_pos: (i32, i16, i32)) -> Option<BlockType> {
+        if block_pos.0.abs() > 5 || block_pos.2.abs() > 5 || block_pos.1 < -5 || block_pos.1 > 10 {
+            return None;
+        }
+        Some(*self.blocks.get(&block_pos).unwrap_or(&BlockType::Air))
+    }
+
+    // random tick every block in the world once, the same way the world does
+    fn tick(&mut self, world_seed: u64, tick: u64) -> Vec<BlockWrite> {
+        let mut positions: Vec<(i32, i16, i32)> = self.blocks.keys().cloned().collect();
+        positions.sort();
+
+        let mut all_writes: Vec<BlockWrite> = Vec::new();
+        for block_pos in positions {
+            let block_writes: Vec<BlockWrite> =
+                get_grass_random_tick(block_pos, world_seed, tick, |block_pos| self.get_block(block_pos));
+            for (block_pos, block_type) in block_writes.iter() {
+                self.blocks.insert(*block_pos, *block_type);
+            }
+            all_writes.extend(block_writes);
+        }
+        all_writes
+    }
+
+    fn count_grass(&self) -> usize {
+        self.blocks.values().filter(|block_type| **block_type == BlockType::Grass).count()
+    }
+}
+
+
+// grass with a solid block on top of it turns back into dirt, things you can see through dont kill it
+#[test]
+fn test_grass_decays_when_covered() {
+    let mut world: TestWorld = TestWorld::new();
+    world.blocks.insert((0, 0, 0), BlockType::Grass);
+    world.blocks.insert((0, 1, 0), BlockType::Stone);
+    assert_eq!(
+        get_grass_random_tick((0, 0, 0), 1, 0, |block_pos| world.get_block(block_pos)),
+        vec![((0, 0, 0), BlockType::Dirt)]
+    );
+
+    world.blocks.insert((0, 1, 0), BlockType::Water);
+    assert!(!is_grass_covered((0, 0, 0), &|block_pos| world.get_block(block_pos)));
+
+    // the top of the world doesnt count as being covered
+    assert!(!is_grass_covered((0, 10, 0), &|block_pos| world.get_block(block_pos)));
+}
+
+// grass spreads over an open dirt floor
+#[test]
+fn test_grass_spreads_to_dirt() {
+    let mut world: TestWorld = TestWorld::new();
+    world.blocks.insert((0, 0, 0), BlockType::Grass);
+
+    for tick in 0..50 {
+        world.tick(3, tick);
+    }
+
+    assert!(world.count_grass() > 10, "Grass only spread to {} blocks", world.count_grass());
+    for (block_pos, block_type) in world.blocks.iter() {
+        assert!(
+            *block_type == BlockType::Grass || *block_type == BlockType::Dirt,
+            "{:?} at {:?}",
+            block_type,
+            block_pos
+        );
+    }
+}
+
+// grass cant spread onto dirt that is covered or onto anything that isnt dirt
+#[test]
+fn test_grass_spread_targets() {
+    let mut world: TestWorld = TestWorld::new();
+    world.blocks.insert((1, 0, 0), BlockType::Stone);
+    world.blocks.insert((0, 1, 1), BlockType::Log);
+    world.blocks.insert((-1, 1, 0), BlockType::Water);
+
+    let get_block = |block_pos: (i32, i16, i32)| world.get_block(block_pos);
+    assert!(can_grass_spread_to((0, 0, -1), &get_block));
+    assert!(!can_grass_spread_to((1, 0, 0), &get_block));
+    assert!(!can_grass_spread_to((0, 0, 1), &get_block));
+    assert!(!can_grass_spread_to((-1, 0, 0), &get_block));
+    assert!(!can_grass_spread_to((0, 1, 0), &get_block));
+
+    // blocks that cant be read (like unloaded chunks) are never spread to
+    assert!(!can_grass_spread_to((6, 0, 0), &get_block));
+}
+
+// grass surrounded by covered dirt never spreads, and only dirt ever turns into grass
+#[test]
+fn test_grass_doesnt_spread_under_blocks() {
+    let mut world: TestWorld = TestWorld::new();
+    for x in -5..=5 {
+        for z in -5..=5 {
+            world.blocks.insert((x, 1, z), BlockType::Stone);
+        }
+    }
+    world.blocks.insert((0, 1, 0), BlockType::Air);
+    world.blocks.insert((0, 0, 0), BlockType::Grass);
+
+    for tick in 0..50 {
+        world.tick(3, tick);
+    }
+
+    assert_eq!(world.count_grass(), 1);
+}
+
+// the same world always grows the same way, and a different seed grows differently
+#[test]
+fn test_grass_deterministic() {
+    let run = |world_seed: u64| {
+        let mut world: TestWorld = TestWorld::new();
+        world.blocks.insert((2, 0, -1), BlockType::Grass);
+        world.blocks.insert((2, 1, 0), BlockType::Stone);
+
+        let mut all_writes: Vec<Vec<BlockWrite>> = Vec::new();
+        for tick in 0..20 {
+            all_writes.push(world.tick(world_seed, tick));
+        }
+        all_writes
+    };
+
+    assert_eq!(run(1), run(1));
+    assert_ne!(run(1), run(2));
+}
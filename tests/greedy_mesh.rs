@@ -0,0 +1,200 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, create_chunks::*},
+    greedy_mesh::*,
+};
+
+use std::collections::HashMap;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (8, 16, 8);
+
+// make the blocks of a chunk from a list of block positions, with their visible faces set to the faces touching air
+// (anything outside of the blocks is air, so the walls of the chunk are showing too)
+fn make_chunk_blocks(blocks: &[((i32, i16, i32), BlockType)]) -> HashMap<(i32, i16, i32), Block> {
+    let mut chunk_blocks: HashMap<(i32, i16, i32), Block> = HashMap::new();
+    for (block_pos, block_type) in blocks.iter() {
+        chunk_blocks.insert(*block_pos, Block::new(*block_type, block_pos.0, block_pos.1, block_pos.2));
+    }
+
+    set_air_faces(&mut chunk_blocks);
+    chunk_blocks
+}
+
+fn set_air_faces(chunk_blocks: &mut HashMap<(i32, i16, i32), Block>) {
+    let block_positions: Vec<(i32, i16, i32)> = chunk_blocks.keys().cloned().collect();
+    for block_pos in block_positions {
+        let mut visible_faces: u8 = 0;
+        for face in BlockFace::ALL {
+            let offset: (i32, i16, i32) = face.offset();
+            if !chunk_blocks.contains_key(&(block_pos.0 + offset.0, block_pos.1 + offset.1, block_pos.2 + offset.2)) {
+                visible_faces |= face.bit();
+            }
+        }
+        chunk_blocks.get_mut(&block_pos).unwrap().set_visible_faces(visible_faces);
+    }
+}
+
+// the total area of all of the quads in the mesh, every quad is a rectangle so it is the length of 2 sides multiplied
+fn get_mesh_area(mesh: &ChunkMesh) -> f32 {
+    let mut area: f32 = 0.0;
+    for quad in mesh.vertices.chunks(4) {
+        let side_a: f32 = (0..3).map(|i| (quad[1].position[i] - quad[0].position[i]).abs()).sum();
+        let side_b: f32 = (0..3).map(|i| (quad[2].position[i] - quad[0].position[i]).abs()).sum();
+        area += side_a * side_b;
+    }
+    area
+}
+
+fn block_colour(block: &Block) -> [f32; 4] {
+    block.block_type.block_colour()
+}
+
+
+// a single block has one quad for each of its faces
+#[test]
+fn test_single_block_mesh() {
+    let chunk_blocks = make_chunk_blocks(&[((1, 2, 3), BlockType::Stone)]);
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, block_colour);
+
+    assert_eq!(mesh.get_quad_count(), 6);
+    assert_eq!(mesh.vertices.len(), 24);
+    assert_eq!(mesh.indices.len(), 36);
+
+    // every vertex is a corner of the block
+    for vertex in mesh.vertices.iter() {
+        assert!(vertex.position[0] == 1.0 || vertex.position[0] == 2.0);
+        assert!(vertex.position[1] == 2.0 || vertex.position[1] == 3.0);
+        assert!(vertex.position[2] == 3.0 || vertex.position[2] == 4.0);
+    }
+}
+
+// the triangles wind the same way as the cube faces used for instancing, so the same faces are culled
+// for the cube (a, b, c) of each triangle, (b - a) x (c - a) points into the block
+#[test]
+fn test_mesh_winding() {
+    let chunk_blocks = make_chunk_blocks(&[((0, 0, 0), BlockType::Stone)]);
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, block_colour);
+
+    for triangle in mesh.indices.chunks(3) {
+        let a: [f32; 3] = mesh.vertices[triangle[0] as usize].position;
+        let b: [f32; 3] = mesh.vertices[triangle[1] as usize].position;
+        let c: [f32; 3] = mesh.vertices[triangle[2] as usize].position;
+
+        let ab: [f32; 3] = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let ac: [f32; 3] = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let cross: [f32; 3] = [
+            ab[1] * ac[2] - ab[2] * ac[1],
+            ab[2] * ac[0] - ab[0] * ac[2],
+            ab[0] * ac[1] - ab[1] * ac[0],
+        ];
+
+        // from the middle of the block out to the middle of the triangle
+        let outwards: [f32; 3] = [0, 1, 2].map(|i| (a[i] + b[i] + c[i]) / 3.0 - 0.5);
+        let dot: f32 = (0..3).map(|i| cross[i] * outwards[i]).sum();
+        assert!(dot < 0.0, "Triangle {:?} {:?} {:?} is facing the wrong way", a, b, c);
+    }
+}
+
+// a flat floor of the same block is one quad for each side
+#[test]
+fn test_flat_floor_merges() {
+    let mut blocks: Vec<((i32, i16, i32), BlockType)> = Vec::new();
+    for x in 0..8 {
+        for z in 0..8 {
+            blocks.push(((x, 0, z), BlockType::Grass));
+        }
+    }
+    let chunk_blocks = make_chunk_blocks(&blocks);
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, block_colour);
+
+    assert_eq!(mesh.get_quad_count(), 6);
+    assert_eq!(get_mesh_area(&mesh), 64.0 * 2.0 + 8.0 * 4.0);
+}
+
+// faces only merge with faces of the same block type and colour
+#[test]
+fn test_different_blocks_dont_merge() {
+    let chunk_blocks = make_chunk_blocks(&[((0, 0, 0), BlockType::Stone), ((1, 0, 0), BlockType::Dirt)]);
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, block_colour);
+
+    // 5 faces of each block are showing, none can be merged
+    assert_eq!(mesh.get_quad_count(), 10);
+
+    // the same blocks with different colours dont merge either
+    let chunk_blocks = make_chunk_blocks(&[((0, 0, 0), BlockType::Grass), ((1, 0, 0), BlockType::Grass)]);
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, |block: &Block| {
+        [block.position.x as f32, 0.0, 0.0, 1.0]
+    });
+    assert_eq!(mesh.get_quad_count(), 10);
+
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, block_colour);
+    assert_eq!(mesh.get_quad_count(), 6);
+}
+
+// only the visible faces are meshed
+#[test]
+fn test_only_visible_faces() {
+    let mut chunk_blocks = make_chunk_blocks(&[((0, 0, 0), BlockType::Stone)]);
+    chunk_blocks.get_mut(&(0, 0, 0)).unwrap().set_visible_faces(BlockFace::Top.bit());
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, block_colour);
+    assert_eq!(mesh.get_quad_count(), 1);
+    for vertex in mesh.vertices.iter() {
+        assert_eq!(vertex.position[1], 1.0);
+    }
+
+    chunk_blocks.get_mut(&(0, 0, 0)).unwrap().set_visible_faces(0);
+    assert!(build_greedy_mesh(&chunk_blocks, (0, 0), CHUNK_SIZES, block_colour).is_empty());
+}
+
+// chunks that arnt at the origin have their mesh in the right place in the world
+#[test]
+fn test_mesh_world_position() {
+    let chunk_blocks = make_chunk_blocks(&[((-8, -8, 17), BlockType::Stone)]);
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (-1, 2), CHUNK_SIZES, block_colour);
+
+    for vertex in mesh.vertices.iter() {
+        assert!(vertex.position[0] == -8.0 || vertex.position[0] == -7.0);
+        assert!(vertex.position[1] == -8.0 || vertex.position[1] == -7.0);
+        assert!(vertex.position[2] == 17.0 || vertex.position[2] == 18.0);
+    }
+}
+
+// a generated chunk covers exactly the same faces as the instances would, with a lot fewer quads
+#[test]
+fn test_generated_chunk_mesh() {
+    let chunk_sizes: (usize, usize, usize) = (16, 64, 16);
+    let mut temp_chunk_vector = create_temp_chunk_vector((0, 0), chunk_sizes);
+    generate_chunk(&mut temp_chunk_vector, (0, 0), chunk_sizes, 5);
+
+    let mut chunk_blocks: HashMap<(i32, i16, i32), Block> = HashMap::new();
+    for block in temp_chunk_vector.iter().flatten().flatten() {
+        if block.block_type != BlockType::Air {
+            chunk_blocks.insert((block.position.x, block.position.y, block.position.z), *block);
+        }
+    }
+    set_air_faces(&mut chunk_blocks);
+
+    let visible_face_count: u32 = chunk_blocks.values().map(|block| block.visible_faces.count_ones()).sum();
+    let mesh: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), chunk_sizes, block_colour);
+
+    assert_eq!(get_mesh_area(&mesh), visible_face_count as f32);
+    assert!(mesh.get_quad_count() < visible_face_count as usize / 2);
+    assert_eq!(mesh.indices.len(), mesh.get_quad_count() * 6);
+
+    // the same blocks always make the same mesh
+    let mesh_again: ChunkMesh = build_greedy_mesh(&chunk_blocks, (0, 0), chunk_sizes, block_colour);
+    assert_eq!(mesh.vertices, mesh_again.vertices);
+    assert_eq!(mesh.indices, mesh_again.indices);
+}
+
+// the meshing mode is picked from the command line
+#[test]
+fn test_meshing_mode_from_args() {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+
+    assert_eq!(MeshingMode::from_args(args(&["rust_craft"])), MeshingMode::Instanced);
+    assert_eq!(MeshingMode::from_args(args(&["rust_craft", "--greedy-meshing"])), MeshingMode::Greedy);
+}
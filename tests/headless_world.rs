@@ -0,0 +1,96 @@
+extern crate rust_craft;
+use rust_craft::{
+    block_type::*,
+    chunk::{chunk_borders::*, chunk_functions::*, decorate_chunks::*, *},
+    render_backend::*,
+    world::*,
+    world_generator::*,
+};
+
+use std::{
+    collections::HashSet,
+    fs,
+};
+
+mod common;
+use common::*;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (16, 64, 16);
+
+
+// a chunk can be made and loaded without a gpu, it just has no buffers
+#[test]
+fn test_headless_chunk() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_headless_chunk");
+
+    let mut chunk: Chunk = Chunk::new(0, 0, -1, &HeadlessBackend);
+    assert!(chunk.instance_buffers.is_none());
+
+    let generator: Box<dyn WorldGenerator> = WorldGeneratorType::default_noise().create_generator();
+    let mut created_chunks: HashSet<(i32, i32)> = HashSet::new();
+    chunk.load_chunk(
+        &mut file_system,
+        &HeadlessBackend,
+        CHUNK_SIZES,
+        &mut created_chunks,
+        ChunkGenerationContext {
+            generator: generator.as_ref(),
+            world_seed: 5,
+            pending_block_writes: &mut PendingBlockWrites::new(),
+            chunk_borders: &ChunkBorders::new(),
+        },
+    ).unwrap();
+
+    assert!(created_chunks.contains(&(0, 0)));
+    assert!(!chunk.chunk_blocks.is_empty());
+    assert_eq!(chunk.alive_blocks as usize, chunk.chunk_blocks.len());
+
+    // the touching air pass still runs, so only the blocks touching air have instances
+    assert!(!chunk.instances_to_render.is_empty());
+    assert!(chunk.instances_to_render.len() < chunk.chunk_blocks.len());
+    for block_pos in chunk.instances_to_render.keys() {
+        assert!(chunk.chunk_blocks[block_pos].is_touching_air);
+    }
+
+    // updating does nothing without a gpu
+    chunk.update(&HeadlessBackend);
+    chunk.update_mesh(&HeadlessBackend, CHUNK_SIZES);
+    assert!(chunk.mesh_vertex_buffer.is_none());
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// the world can load chunks, change them, save them and load them again without a gpu
+#[test]
+fn test_headless_world_save_and_load() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_headless_world");
+    let mut world: World = World::new(String::from("headless"), 5, 1, CHUNK_SIZES);
+
+    let chunks_to_load: HashSet<(i32, i32)> = HashSet::from([(0, 0), (1, 0)]);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load.clone());
+
+    // chunks without buffers dont have to wait in pending
+    world.update_pending_chunks(&HeadlessBackend);
+    assert!(world.pending_chunks.is_empty());
+    assert_eq!(world.chunks.keys().cloned().collect::<HashSet<(i32, i32)>>(), chunks_to_load);
+
+    // put a block at the top of the world, across the wall between the 2 chunks
+    let top_y: i16 = CHUNK_SIZES.1 as i16 / 2 - 1;
+    world.set_block(15, top_y, 3, BlockType::Stone).unwrap();
+    world.set_block(16, top_y, 3, BlockType::Cobblestone).unwrap();
+    assert_eq!(world.get_block(15, top_y, 3).unwrap(), BlockType::Stone);
+
+    // unloading everything saves the chunks to their region file
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::new());
+    assert!(world.chunks.is_empty());
+    assert!(path.join("Regions").join("r_0_0.region").exists());
+
+    // and loading them again reads them back from the files
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, chunks_to_load);
+    world.update_pending_chunks(&HeadlessBackend);
+    assert_eq!(world.get_block(15, top_y, 3).unwrap(), BlockType::Stone);
+    assert_eq!(world.get_block(16, top_y, 3).unwrap(), BlockType::Cobblestone);
+
+    fs::remove_dir_all(&path).unwrap();
+}
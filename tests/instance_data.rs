@@ -0,0 +1,65 @@
+extern crate rust_craft;
+use rust_craft::types::*;
+
+use std::mem::{offset_of, size_of};
+
+
+// every instance attribute starts at the field it is for, so the shader reads the right bytes
+#[test]
+fn test_instance_attribute_offsets() {
+    let field_offsets: [usize; 6] = [
+        offset_of!(InstanceData, model_matrix),
+        offset_of!(InstanceData, model_matrix) + size_of::<[f32; 4]>(),
+        offset_of!(InstanceData, model_matrix) + size_of::<[f32; 4]>() * 2,
+        offset_of!(InstanceData, model_matrix) + size_of::<[f32; 4]>() * 3,
+        offset_of!(InstanceData, colour),
+        offset_of!(InstanceData, visible_faces),
+    ];
+    let field_sizes: [usize; 6] = [
+        size_of::<[f32; 4]>(),
+        size_of::<[f32; 4]>(),
+        size_of::<[f32; 4]>(),
+        size_of::<[f32; 4]>(),
+        size_of::<[f32; 4]>(),
+        size_of::<u32>(),
+    ];
+
+    for ((attribute, field_offset), field_size) in InstanceData::ATTRIBUTES.iter().zip(field_offsets).zip(field_sizes) {
+        assert_eq!(attribute.offset as usize, field_offset, "Location {} is at the wrong offset", attribute.shader_location);
+        assert_eq!(attribute.format.size() as usize, field_size, "Location {} is the wrong size", attribute.shader_location);
+    }
+
+    let last_attribute: &wgpu::VertexAttribute = InstanceData::ATTRIBUTES.last().unwrap();
+    assert!(last_attribute.offset + last_attribute.format.size() <= size_of::<InstanceData>() as u64);
+}
+
+// the attribute formats are the same types the vertex shader reads them as
+#[test]
+fn test_instance_attributes_match_vertex_shader() {
+    let module: wgpu::naga::Module = wgpu::naga::front::wgsl::parse_str(include_str!("../src/Shaders/vertex.wgsl")).unwrap();
+
+    let instance_type: &wgpu::naga::Type = module
+        .types
+        .iter()
+        .map(|(_, shader_type)| shader_type)
+        .find(|shader_type| shader_type.name.as_deref() == Some("Instance"))
+        .unwrap();
+    let members = match &instance_type.inner {
+        wgpu::naga::TypeInner::Struct { members, .. } => members,
+        other => panic!("Instance is not a struct: {:?}", other),
+    };
+
+    for attribute in InstanceData::ATTRIBUTES.iter() {
+        let member = members
+            .iter()
+            .find(|member| matches!(member.binding, Some(wgpu::naga::Binding::Location { location, .. }) if location == attribute.shader_location))
+            .unwrap_or_else(|| panic!("The shader has no location {}", attribute.shader_location));
+
+        let shader_size: u32 = match &module.types[member.ty].inner {
+            wgpu::naga::TypeInner::Scalar(scalar) => scalar.width as u32,
+            wgpu::naga::TypeInner::Vector { size, scalar } => *size as u32 * scalar.width as u32,
+            other => panic!("Unexpected shader type {:?}", other),
+        };
+        assert_eq!(attribute.format.size(), shader_size as u64, "Location {} is a different size in the shader", attribute.shader_location);
+    }
+}
@@ -0,0 +1,180 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    raycast::*,
+    render_backend::*,
+    types::*,
+    world::*,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+mod common;
+use common::*;
+
+// a small world of blocks, anything not in the hashmap is air and anything outside of -50..50 isnt loaded
+fn raycast_test_world(
+    origin: FPosition,
+    direction: FPosition,
+    reach: f32,
+    blocks: &HashMap<(i32, i16, i32), BlockType>,
+) -> Option<RaycastHit> {
+    raycast_blocks(origin, direction, reach, |pos: (i32, i16, i32)| {
+        if pos.0.abs() > 50 || pos.2.abs() > 50 {
+            return None;
+        }
+        Some(*blocks.get(&pos).unwrap_or(&BlockType::Air))
+    })
+}
+
+#[test]
+fn test_raycast_straight_down() {
+    let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+    blocks.insert((0, -1, 0), BlockType::Grass);
+
+    let hit: RaycastHit = raycast_test_world(
+        FPosition::new(0.5, 2.5, 0.5),
+        FPosition::new(0.0, -1.0, 0.0),
+        5.0,
+        &blocks,
+    )
+    .expect("should hit the grass block");
+
+    assert_eq!(hit.block_position, (0, -1, 0));
+    assert_eq!(hit.block_type, BlockType::Grass);
+    assert_eq!(hit.face, BlockFace::Top);
+    assert_eq!(hit.adjacent_position, (0, 0, 0));
+    assert!((hit.distance - 2.5).abs() < 0.0001);
+}
+
+#[test]
+fn test_raycast_negative_x() {
+    let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+    blocks.insert((-3, 0, 0), BlockType::Stone);
+
+    let hit: RaycastHit = raycast_test_world(
+        FPosition::new(0.5, 0.5, 0.5),
+        FPosition::new(-1.0, 0.0, 0.0),
+        5.0,
+        &blocks,
+    )
+    .expect("should hit the stone block");
+
+    assert_eq!(hit.block_position, (-3, 0, 0));
+    assert_eq!(hit.face, BlockFace::Right);
+    assert_eq!(hit.adjacent_position, (-2, 0, 0));
+}
+
+#[test]
+fn test_raycast_returns_closest_block() {
+    let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+    blocks.insert((0, 0, 4), BlockType::Stone);
+    blocks.insert((0, 0, 2), BlockType::Dirt);
+
+    let hit: RaycastHit = raycast_test_world(
+        FPosition::new(0.5, 0.5, 0.5),
+        FPosition::new(0.0, 0.0, 1.0),
+        5.0,
+        &blocks,
+    )
+    .expect("should hit the dirt block");
+
+    assert_eq!(hit.block_position, (0, 0, 2));
+    assert_eq!(hit.face, BlockFace::Front);
+    assert_eq!(hit.adjacent_position, (0, 0, 1));
+}
+
+#[test]
+fn test_raycast_out_of_reach() {
+    let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+    blocks.insert((0, 0, 10), BlockType::Stone);
+
+    let hit: Option<RaycastHit> = raycast_test_world(
+        FPosition::new(0.5, 0.5, 0.5),
+        FPosition::new(0.0, 0.0, 1.0),
+        5.0,
+        &blocks,
+    );
+
+    assert_eq!(hit, None);
+}
+
+#[test]
+fn test_raycast_diagonal() {
+    let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+    blocks.insert((2, -2, 0), BlockType::Stone);
+
+    let hit: RaycastHit = raycast_test_world(
+        FPosition::new(0.5, 0.6, 0.5),
+        FPosition::new(1.0, -1.0, 0.0),
+        10.0,
+        &blocks,
+    )
+    .expect("should hit the stone block");
+
+    assert_eq!(hit.block_position, (2, -2, 0));
+    assert_eq!(hit.face, BlockFace::Top);
+    assert_eq!(hit.adjacent_position, (2, -1, 0));
+}
+
+// transparent blocks are passed through
+#[test]
+fn test_raycast_ignores_transparent_blocks() {
+    let mut blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+    blocks.insert((1, 0, 0), BlockType::Air);
+    blocks.insert((3, 0, 0), BlockType::Cobblestone);
+
+    let hit: RaycastHit = raycast_test_world(
+        FPosition::new(0.5, 0.5, 0.5),
+        FPosition::new(1.0, 0.0, 0.0),
+        5.0,
+        &blocks,
+    )
+    .expect("should hit the cobblestone block");
+
+    assert_eq!(hit.block_position, (3, 0, 0));
+    assert_eq!(hit.face, BlockFace::Left);
+}
+
+// crossing into a chunk that isnt loaded stops the ray
+#[test]
+fn test_raycast_stops_at_unloaded() {
+    let blocks: HashMap<(i32, i16, i32), BlockType> = HashMap::new();
+
+    let hit: Option<RaycastHit> = raycast_test_world(
+        FPosition::new(49.5, 0.5, 0.5),
+        FPosition::new(1.0, 0.0, 0.0),
+        100.0,
+        &blocks,
+    );
+
+    assert_eq!(hit, None);
+}
+
+// above the top of the world is air, so looking down from up there still hits the ground
+#[test]
+fn test_raycast_from_above_world() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_raycast_above_world");
+    let chunk_sizes: (usize, usize, usize) = (16, 64, 16);
+    let mut world: World = World::new(String::from("raycast"), 3, 1, chunk_sizes);
+    world.update_chunks_around_character(&HeadlessBackend, &mut file_system, HashSet::from([(0, 0)]));
+    world.update_pending_chunks(&HeadlessBackend);
+
+    let top_y: i16 = chunk_sizes.1 as i16 / 2 - 1;
+    world.set_block(3, top_y, 4, BlockType::Cobblestone).unwrap();
+
+    let hit: RaycastHit = world
+        .raycast(FPosition::new(3.5, top_y as f32 + 20.5, 4.5), FPosition::new(0.0, -1.0, 0.0), 30.0)
+        .expect("should hit the block at the top of the world");
+    assert_eq!(hit.block_position, (3, top_y, 4));
+    assert_eq!(hit.face, BlockFace::Top);
+
+    // a chunk that isnt loaded still stops it
+    assert_eq!(world.raycast(FPosition::new(20.5, top_y as f32 + 20.5, 4.5), FPosition::new(0.0, -1.0, 0.0), 100.0), None);
+
+    fs::remove_dir_all(&path).unwrap();
+}
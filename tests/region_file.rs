@@ -0,0 +1,147 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, *},
+    file_system::*,
+    region_file::*,
+};
+
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+mod common;
+use common::*;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (16, 64, 16);
+const WORLD_SEED: u64 = 11;
+
+fn get_block_types(temp_chunk_vec: &[Vec<Vec<Block>>]) -> Vec<BlockType> {
+    temp_chunk_vec.iter().flatten().flatten().map(|block| block.block_type).collect()
+}
+
+
+// chunks are put in the right region, and negative chunks arnt put in region 0
+#[test]
+fn test_region_ids() {
+    assert_eq!(get_region_id((0, 0)), (0, 0));
+    assert_eq!(get_region_id((15, 15)), (0, 0));
+    assert_eq!(get_region_id((16, -1)), (1, -1));
+    assert_eq!(get_region_id((-16, -17)), (-1, -2));
+    assert_eq!(get_region_file_name((-1, 2)), "r_-1_2.region");
+}
+
+// chunks can be written into a region, read back after it is opened again, and made bigger
+#[test]
+fn test_region_file_read_write() {
+    let (_, path) = make_test_file_system("rust_craft_test_region_file_read_write");
+    let region_path: PathBuf = path.join("test.region");
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert!(!region_file.has_chunk((0, 0)));
+    assert_eq!(region_file.read_chunk((0, 0)).unwrap(), None);
+
+    region_file.write_chunk((0, 0), &[1, 2, 3]).unwrap();
+    region_file.write_chunk((-1, -1), &[4, 5]).unwrap();
+    region_file.write_chunk((5, 9), &[6; 100]).unwrap();
+    drop(region_file);
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert_eq!(region_file.read_chunk((0, 0)).unwrap(), Some(vec![1, 2, 3]));
+    assert_eq!(region_file.read_chunk((15, 15)).unwrap(), Some(vec![4, 5]));
+    assert_eq!(region_file.read_chunk((5, 9)).unwrap(), Some(vec![6; 100]));
+    assert!(!region_file.has_chunk((1, 0)));
+
+    // a chunk saved again goes at the end instead of over the old one, and doesnt change the other chunks
+    let file_length: u64 = fs::metadata(&region_path).unwrap().len();
+    region_file.write_chunk((5, 9), &[7; 50]).unwrap();
+    assert_eq!(fs::metadata(&region_path).unwrap().len(), file_length + 50);
+    region_file.write_chunk((0, 0), &[8; 10]).unwrap();
+    assert_eq!(fs::metadata(&region_path).unwrap().len(), file_length + 60);
+    drop(region_file);
+
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert_eq!(region_file.read_chunk((0, 0)).unwrap(), Some(vec![8; 10]));
+    assert_eq!(region_file.read_chunk((-1, -1)).unwrap(), Some(vec![4, 5]));
+    assert_eq!(region_file.read_chunk((5, 9)).unwrap(), Some(vec![7; 50]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a region made with other chunk sizes, or a file that isnt a region at all, cant be opened
+#[test]
+fn test_region_file_bad_header() {
+    let (_, path) = make_test_file_system("rust_craft_test_region_file_bad_header");
+    let region_path: PathBuf = path.join("test.region");
+
+    RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert!(RegionFile::open(&region_path, (32, 256, 32)).is_err());
+
+    let not_a_region_path: PathBuf = path.join("not_a_region.region");
+    fs::write(&not_a_region_path, "1 2 3 4\n").unwrap();
+    assert!(RegionFile::open(&not_a_region_path, CHUNK_SIZES).is_err());
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a chunk saved to a region file reads back the same as one saved to a text file
+#[test]
+fn test_region_matches_text_format() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_region_file_round_trip");
+
+    // some of these are in the same region and some arnt
+    let chunk_ids: [(i32, i32); 4] = [(0, 0), (-1, -3), (15, 16), (3, 0)];
+    for chunk_id in chunk_ids {
+        let chunk: Chunk = make_chunk(&mut file_system, chunk_id, CHUNK_SIZES, WORLD_SEED);
+        let block_types: Vec<BlockType> = get_chunk_block_types(&chunk, CHUNK_SIZES);
+
+        file_system.save_chunk_to_text_file(&chunk, CHUNK_SIZES).unwrap();
+        file_system.save_chunk_to_file(&chunk, CHUNK_SIZES).unwrap();
+
+        let mut text_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_id, CHUNK_SIZES);
+        file_system.read_chunk_from_text_file(&mut text_chunk_vec, chunk_id.0, chunk_id.1, CHUNK_SIZES).unwrap();
+
+        let mut region_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_id, CHUNK_SIZES);
+        file_system.read_chunks_from_file(&mut region_chunk_vec, chunk_id.0, chunk_id.1, CHUNK_SIZES).unwrap();
+
+        assert_eq!(get_block_types(&text_chunk_vec), get_block_types(&region_chunk_vec), "Chunk {:?} is different", chunk_id);
+
+        // and both are the same as the chunk that was saved
+        let mut saved_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_id, CHUNK_SIZES);
+        set_chunk_block_types(&mut saved_chunk_vec, &block_types, CHUNK_SIZES);
+        assert_eq!(get_block_types(&saved_chunk_vec), get_block_types(&region_chunk_vec));
+    }
+
+    // the region with 2 compressed chunks in it (and its offset table) is still smaller than 1 text file
+    let text_size: u64 = fs::metadata(path.join("Chunks").join("0_0.txt")).unwrap().len();
+    let region_size: u64 = fs::metadata(file_system.get_region_path((0, 0))).unwrap().len();
+    assert!(region_size < text_size);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a chunk from before there were region files is still read from its text file
+#[test]
+fn test_read_old_text_chunk() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_region_file_old_text");
+
+    let chunk: Chunk = make_chunk(&mut file_system, (2, -2), CHUNK_SIZES, WORLD_SEED);
+    let block_types: Vec<BlockType> = get_chunk_block_types(&chunk, CHUNK_SIZES);
+    file_system.save_chunk_to_text_file(&chunk, CHUNK_SIZES).unwrap();
+
+    // another chunk in the same region has been saved since
+    let other_chunk: Chunk = make_chunk(&mut file_system, (3, -2), CHUNK_SIZES, WORLD_SEED);
+    file_system.save_chunk_to_file(&other_chunk, CHUNK_SIZES).unwrap();
+
+    let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((2, -2), CHUNK_SIZES);
+    file_system.read_chunks_from_file(&mut temp_chunk_vec, 2, -2, CHUNK_SIZES).unwrap();
+
+    let mut saved_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((2, -2), CHUNK_SIZES);
+    set_chunk_block_types(&mut saved_chunk_vec, &block_types, CHUNK_SIZES);
+    assert_eq!(get_block_types(&saved_chunk_vec), get_block_types(&temp_chunk_vec));
+
+    fs::remove_dir_all(&path).unwrap();
+}
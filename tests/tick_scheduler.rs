@@ -0,0 +1,134 @@
+extern crate rust_craft;
+use rust_craft::tick_scheduler::*;
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    time::Duration,
+};
+
+mod common;
+use common::*;
+
+
+// the number of ticks only depends on how much time has passed, not how often it is checked
+#[test]
+fn test_fixed_tick_rate() {
+    let tick_time: Duration = TickScheduler::get_tick_time();
+    assert_eq!(tick_time * TICKS_PER_SECOND, Duration::from_secs(1));
+
+    // lots of small frames add up to the same ticks as one big one
+    let mut scheduler: TickScheduler = TickScheduler::new();
+    let mut total_ticks: u32 = 0;
+    for _ in 0..50 {
+        total_ticks += scheduler.add_elapsed_time(tick_time / 5);
+    }
+    assert_eq!(total_ticks, 10);
+
+    // the leftover time is kept for the next frame
+    let mut scheduler: TickScheduler = TickScheduler::new();
+    assert_eq!(scheduler.add_elapsed_time(tick_time / 2), 0);
+    assert_eq!(scheduler.add_elapsed_time(tick_time / 2), 1);
+}
+
+// a long freeze doesnt make the world try and run hundreds of ticks at once
+#[test]
+fn test_max_ticks_per_update() {
+    let mut scheduler: TickScheduler = TickScheduler::new();
+    assert_eq!(scheduler.add_elapsed_time(Duration::from_secs(60)), MAX_TICKS_PER_UPDATE);
+}
+
+// ticks come out on the tick they were scheduled for and not before, and the same tick is only run once
+#[test]
+fn test_scheduled_ticks_due() {
+    let mut scheduler: TickScheduler = TickScheduler::new();
+    scheduler.schedule_tick((1, 1, 1), TickType::Fluid, 2);
+    scheduler.schedule_tick((0, 0, 0), TickType::FallingBlock, 0);
+    scheduler.schedule_tick((0, 0, 0), TickType::FallingBlock, 0);
+    scheduler.schedule_tick((0, 0, 0), TickType::Fluid, 0);
+    assert_eq!(scheduler.len(), 3);
+
+    assert_eq!(
+        scheduler.take_due_ticks(),
+        BTreeSet::from([((0, 0, 0), TickType::FallingBlock), ((0, 0, 0), TickType::Fluid)])
+    );
+    scheduler.advance_tick();
+    assert!(scheduler.take_due_ticks().is_empty());
+    scheduler.advance_tick();
+    assert_eq!(scheduler.take_due_ticks(), BTreeSet::from([((1, 1, 1), TickType::Fluid)]));
+    assert!(scheduler.is_empty());
+}
+
+// ticks for unloaded chunks wait until the chunk is loaded, then run on the next tick
+#[test]
+fn test_waiting_ticks() {
+    let mut scheduler: TickScheduler = TickScheduler::new();
+    scheduler.add_waiting_tick((2, -1), ((70, 5, -3), TickType::Fluid));
+    assert_eq!(scheduler.len(), 1);
+    assert!(scheduler.take_due_ticks().is_empty());
+
+    // a different chunk loading doesnt wake it
+    scheduler.wake_chunk((0, 0));
+    assert!(scheduler.take_due_ticks().is_empty());
+
+    scheduler.wake_chunk((2, -1));
+    assert!(scheduler.take_due_ticks().is_empty());
+    scheduler.advance_tick();
+    assert_eq!(scheduler.take_due_ticks(), BTreeSet::from([((70, 5, -3), TickType::Fluid)]));
+    assert!(scheduler.is_empty());
+}
+
+// random ticks are the same every run, inside of the chunk, and spread over every section
+#[test]
+fn test_random_tick_positions() {
+    let chunk_sizes: (usize, usize, usize) = (32, 256, 32);
+    let half_chunk_y: i32 = chunk_sizes.1 as i32 / 2;
+
+    let positions: Vec<(i32, i16, i32)> = get_random_tick_positions(7, 100, (-2, 3), chunk_sizes);
+    assert_eq!(positions, get_random_tick_positions(7, 100, (-2, 3), chunk_sizes));
+    assert_ne!(positions, get_random_tick_positions(7, 101, (-2, 3), chunk_sizes));
+    assert_ne!(positions, get_random_tick_positions(8, 100, (-2, 3), chunk_sizes));
+
+    let sections: usize = chunk_sizes.1 / CHUNK_SECTION_HEIGHT;
+    assert_eq!(positions.len(), sections * RANDOM_TICKS_PER_SECTION as usize);
+
+    let mut section_counts: Vec<u32> = vec![0; sections];
+    for block_pos in positions.iter() {
+        assert_eq!(block_pos.0.div_euclid(chunk_sizes.0 as i32), -2);
+        assert_eq!(block_pos.2.div_euclid(chunk_sizes.2 as i32), 3);
+
+        let y: i32 = block_pos.1 as i32 + half_chunk_y;
+        assert!(y >= 0 && y < chunk_sizes.1 as i32);
+        section_counts[y as usize / CHUNK_SECTION_HEIGHT] += 1;
+    }
+
+    for count in section_counts {
+        assert_eq!(count, RANDOM_TICKS_PER_SECTION);
+    }
+}
+
+// scheduled ticks are saved when the world closes and read back when it opens
+#[test]
+fn test_scheduled_ticks_round_trip() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_scheduled_ticks");
+
+    let mut scheduler: TickScheduler = TickScheduler::new();
+    scheduler.current_tick = 500;
+    scheduler.schedule_tick((1, -4, 2), TickType::Fluid, 5);
+    scheduler.schedule_tick((-40, 10, 3), TickType::FallingBlock, 1);
+    scheduler.add_waiting_tick((3, 3), ((100, 0, 100), TickType::Fluid));
+    file_system.save_scheduled_ticks(&scheduler).unwrap();
+
+    let mut read_scheduler: TickScheduler = file_system.read_scheduled_ticks().unwrap();
+    assert_eq!(read_scheduler.current_tick, 500);
+    assert_eq!(read_scheduler.len(), 3);
+
+    // the waiting tick is due straight away
+    assert_eq!(read_scheduler.take_due_ticks(), BTreeSet::from([((100, 0, 100), TickType::Fluid)]));
+    read_scheduler.advance_tick();
+    assert_eq!(read_scheduler.take_due_ticks(), BTreeSet::from([((-40, 10, 3), TickType::FallingBlock)]));
+    read_scheduler.current_tick = 505;
+    assert_eq!(read_scheduler.take_due_ticks(), BTreeSet::from([((1, -4, 2), TickType::Fluid)]));
+
+    fs::remove_dir_all(&path).unwrap();
+}
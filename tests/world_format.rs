@@ -0,0 +1,241 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::{chunk_functions::*, *},
+    error::*,
+    file_system::*,
+    region_file::*,
+    world_format::*,
+    world_generator::*,
+};
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+mod common;
+use common::*;
+
+
+const CHUNK_SIZES: (usize, usize, usize) = (16, 64, 16);
+const WORLD_SEED: u64 = 21;
+
+// read a chunk back however it is saved
+fn read_block_types(file_system: &mut FileSystem, chunk_id: (i32, i32)) -> Vec<BlockType> {
+    let mut temp_chunk_vec: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector(chunk_id, CHUNK_SIZES);
+    file_system.read_chunks_from_file(&mut temp_chunk_vec, chunk_id.0, chunk_id.1, CHUNK_SIZES).unwrap();
+    get_temp_chunk_block_types(&temp_chunk_vec, CHUNK_SIZES)
+}
+
+// a world saved before there were versions, with its info and text chunks
+fn make_old_world(file_system: &mut FileSystem, path: &Path, chunk_ids: &[(i32, i32)]) -> Vec<Vec<BlockType>> {
+    fs::write(path.join("WorldInfo.txt"), format!("World Seed: {}\nGenerator: Noise\n", WORLD_SEED)).unwrap();
+
+    let mut saved_block_types: Vec<Vec<BlockType>> = Vec::new();
+    for chunk_id in chunk_ids.iter() {
+        let chunk: Chunk = make_chunk(file_system, *chunk_id, CHUNK_SIZES, WORLD_SEED);
+        file_system.save_chunk_to_text_file(&chunk, CHUNK_SIZES).unwrap();
+        saved_block_types.push(get_chunk_block_types(&chunk, CHUNK_SIZES));
+    }
+    saved_block_types
+}
+
+
+// an old world has its text chunks moved into region files, with a backup of the old files
+#[test]
+fn test_upgrade_text_world() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_format_upgrade");
+    let chunk_ids: [(i32, i32); 3] = [(0, 0), (-1, 5), (20, -20)];
+    let saved_block_types: Vec<Vec<BlockType>> = make_old_world(&mut file_system, &path, &chunk_ids);
+    let old_world_info: (u64, WorldGeneratorType) = file_system.read_world_info().unwrap().unwrap();
+    assert_eq!(file_system.read_world_format_version().unwrap(), None);
+    assert_eq!(file_system.get_text_chunk_ids().unwrap().len(), 3);
+
+    let world_upgrade: WorldUpgrade = upgrade_world_format(&mut file_system, CHUNK_SIZES).unwrap().unwrap();
+    assert_eq!(world_upgrade.old_version, 1);
+    assert_eq!(world_upgrade.migrated_chunks, 3);
+
+    // the text files are gone and the chunks are the same in their regions
+    assert!(file_system.get_text_chunk_ids().unwrap().is_empty());
+    for (chunk_id, block_types) in chunk_ids.iter().zip(saved_block_types.iter()) {
+        assert!(file_system.get_region_path(get_region_id(*chunk_id)).exists());
+        assert_eq!(read_block_types(&mut file_system, *chunk_id), *block_types, "Chunk {:?} changed", chunk_id);
+    }
+
+    // the version is saved and the rest of the world info is kept
+    assert_eq!(file_system.read_world_format_version().unwrap(), Some(WORLD_FORMAT_VERSION));
+    assert_eq!(file_system.read_world_info().unwrap().unwrap(), old_world_info);
+
+    // the backup has the old files
+    let backup_directory: PathBuf = world_upgrade.backup_directory.unwrap();
+    assert_eq!(backup_directory, path.join("Backups").join("format_1"));
+    assert!(backup_directory.join("Chunks").join("-1_5.txt").exists());
+    assert_eq!(
+        fs::read_to_string(backup_directory.join("WorldInfo.txt")).unwrap(),
+        format!("World Seed: {}\nGenerator: Noise\n", WORLD_SEED)
+    );
+
+    // there is nothing to do the next time it is opened
+    assert_eq!(upgrade_world_format(&mut file_system, CHUNK_SIZES).unwrap(), None);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a world that was just made has nothing to move so it isnt backed up
+#[test]
+fn test_upgrade_new_world() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_format_new");
+    fs::write(path.join("WorldInfo.txt"), "Important Info Goes Here: \n").unwrap();
+
+    let world_upgrade: WorldUpgrade = upgrade_world_format(&mut file_system, CHUNK_SIZES).unwrap().unwrap();
+    assert_eq!(world_upgrade.migrated_chunks, 0);
+    assert_eq!(world_upgrade.backup_directory, None);
+    assert!(!path.join("Backups").exists());
+    assert_eq!(file_system.read_world_format_version().unwrap(), Some(WORLD_FORMAT_VERSION));
+
+    // saving the world info keeps the version
+    file_system.save_world_info(WORLD_SEED, &WorldGeneratorType::Flat).unwrap();
+    assert_eq!(file_system.read_world_format_version().unwrap(), Some(WORLD_FORMAT_VERSION));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// an upgrade that stopped half way is done again, and the backup from the first try is kept
+#[test]
+fn test_upgrade_after_interrupted_upgrade() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_format_interrupted");
+    let saved_block_types: Vec<Vec<BlockType>> = make_old_world(&mut file_system, &path, &[(2, 3)]);
+
+    // the first try made a backup but stopped before the chunks were moved
+    let backup_directory: PathBuf = path.join("Backups").join("format_1");
+    fs::create_dir_all(&backup_directory).unwrap();
+    fs::write(backup_directory.join("WorldInfo.txt"), "from the first try").unwrap();
+
+    let world_upgrade: WorldUpgrade = upgrade_world_format(&mut file_system, CHUNK_SIZES).unwrap().unwrap();
+    assert_eq!(world_upgrade.migrated_chunks, 1);
+    assert_eq!(fs::read_to_string(backup_directory.join("WorldInfo.txt")).unwrap(), "from the first try");
+    assert_eq!(read_block_types(&mut file_system, (2, 3)), saved_block_types[0]);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a region file saved before chunks had checksums is rewritten with them, with a backup of the old one
+#[test]
+fn test_upgrade_region_checksums() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_format_region_checksums");
+    fs::write(path.join("WorldInfo.txt"), format!("Format Version: 2\nWorld Seed: {}\nGenerator: Noise\n", WORLD_SEED)).unwrap();
+    fs::create_dir_all(path.join("Regions")).unwrap();
+
+    // a version 1 region, its offset table has a u64 offset and u32 length for each chunk and no checksums
+    let chunk: Chunk = make_chunk(&mut file_system, (1, 2), CHUNK_SIZES, WORLD_SEED);
+    let block_types: Vec<BlockType> = get_chunk_block_types(&chunk, CHUNK_SIZES);
+    let chunk_data: Vec<u8> = rust_craft::chunk_compression::encode_chunk_blocks(&block_types, CHUNK_SIZES);
+    let header_size: usize = 20 + 256 * 12;
+    let mut region_bytes: Vec<u8> = b"RCRG".to_vec();
+    for value in [1, CHUNK_SIZES.0 as u32, CHUNK_SIZES.1 as u32, CHUNK_SIZES.2 as u32] {
+        region_bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    for index in 0..256 {
+        let (offset, length): (u64, u32) = if index == 1 + 2 * 16 { (header_size as u64, chunk_data.len() as u32) } else { (0, 0) };
+        region_bytes.extend_from_slice(&offset.to_le_bytes());
+        region_bytes.extend_from_slice(&length.to_le_bytes());
+    }
+    region_bytes.extend_from_slice(&chunk_data);
+    let region_path: PathBuf = file_system.get_region_path((0, 0));
+    fs::write(&region_path, &region_bytes).unwrap();
+
+    // it can be read but not saved to
+    let mut region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert_eq!(region_file.get_version(), 1);
+    assert_eq!(region_file.read_chunk((1, 2)).unwrap(), Some(chunk_data.clone()));
+    assert!(region_file.write_chunk((1, 2), &chunk_data).is_err());
+    drop(region_file);
+
+    let world_upgrade: WorldUpgrade = upgrade_world_format(&mut file_system, CHUNK_SIZES).unwrap().unwrap();
+    assert_eq!(world_upgrade.old_version, 2);
+    assert_eq!(world_upgrade.migrated_chunks, 0);
+    assert_eq!(world_upgrade.upgraded_regions, 1);
+
+    let region_file: RegionFile = RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    assert_eq!(region_file.get_version(), REGION_VERSION);
+    assert_eq!(region_file.get_chunk_ids((0, 0)), vec![(1, 2)]);
+    assert_eq!(read_block_types(&mut file_system, (1, 2)), block_types);
+
+    let backup_directory: PathBuf = world_upgrade.backup_directory.unwrap();
+    assert_eq!(fs::read(backup_directory.join("Regions").join("r_0_0.region")).unwrap(), region_bytes);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a world from a newer version of the game isnt opened or changed
+#[test]
+fn test_newer_world_version() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_format_newer");
+    make_old_world(&mut file_system, &path, &[(0, 0)]);
+    file_system.save_world_format_version(WORLD_FORMAT_VERSION + 1).unwrap();
+
+    match upgrade_world_format(&mut file_system, CHUNK_SIZES) {
+        Err(WorldFormatError::NewerVersion(version, current_version)) => {
+            assert_eq!(version, WORLD_FORMAT_VERSION + 1);
+            assert_eq!(current_version, WORLD_FORMAT_VERSION);
+        }
+        other => panic!("Expected a newer version error, got {:?}", other),
+    }
+    let error_message: String = upgrade_world_format(&mut file_system, CHUNK_SIZES).unwrap_err().to_string();
+    assert!(error_message.contains("newer version"));
+
+    // nothing was moved
+    assert_eq!(file_system.get_text_chunk_ids().unwrap(), vec![(0, 0)]);
+    assert!(!path.join("Backups").exists());
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a format version that cant be read is an error, instead of treating the world as the first version and upgrading it again
+#[test]
+fn test_unreadable_world_version() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_format_unreadable");
+    make_old_world(&mut file_system, &path, &[(0, 0)]);
+    let world_info: String = format!("Format Version: three\nWorld Seed: {}\nGenerator: Noise\n", WORLD_SEED);
+    fs::write(path.join("WorldInfo.txt"), &world_info).unwrap();
+
+    match upgrade_world_format(&mut file_system, CHUNK_SIZES) {
+        Err(WorldFormatError::UnreadableVersion(e)) => match *e {
+            GameError::MalformedFile(error_path, line_number, _) => {
+                assert_eq!(error_path, path.join("WorldInfo.txt"));
+                assert_eq!(line_number, 1);
+            }
+            other => panic!("Expected a malformed file error, got {:?}", other),
+        },
+        other => panic!("Expected an unreadable version error, got {:?}", other),
+    }
+
+    // nothing was moved or saved over
+    assert_eq!(file_system.get_text_chunk_ids().unwrap(), vec![(0, 0)]);
+    assert!(!path.join("Backups").exists());
+    assert_eq!(fs::read_to_string(path.join("WorldInfo.txt")).unwrap(), world_info);
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// region files and chunks from a newer version give an error saying so
+#[test]
+fn test_newer_region_and_chunk_versions() {
+    let (_, path) = make_test_file_system("rust_craft_test_world_format_newer_region");
+    let region_path: PathBuf = path.join("newer.region");
+
+    RegionFile::open(&region_path, CHUNK_SIZES).unwrap();
+    let mut region_bytes: Vec<u8> = fs::read(&region_path).unwrap();
+    region_bytes[4] = 200;
+    fs::write(&region_path, region_bytes).unwrap();
+
+    let error_message: String = RegionFile::open(&region_path, CHUNK_SIZES).err().unwrap().to_string();
+    assert!(error_message.contains("newer version"), "{}", error_message);
+
+    let error_message: String = rust_craft::chunk_compression::decode_chunk_blocks(&[200], CHUNK_SIZES).unwrap_err().to_string();
+    assert!(error_message.contains("newer version"), "{}", error_message);
+
+    fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,187 @@
+extern crate rust_craft;
+use rust_craft::{
+    block::*,
+    block_type::*,
+    chunk::chunk_functions::*,
+    error::*,
+    world::*,
+    world_generator::*,
+};
+
+use std::fs;
+
+mod common;
+use common::*;
+
+
+fn generate_test_chunk(generator_type: &WorldGeneratorType, chunk_sizes: (usize, usize, usize)) -> Vec<Vec<Vec<Block>>> {
+    let mut temp_chunk_vector: Vec<Vec<Vec<Block>>> = create_temp_chunk_vector((0, 0), chunk_sizes);
+    generator_type
+        .create_generator()
+        .generate_chunk(&mut temp_chunk_vector, (0, 0), chunk_sizes, 1);
+
+    temp_chunk_vector
+}
+
+
+// the info string saved in WorldInfo.txt should read back as the same generator
+fn test_generator_info_string(generator_type: WorldGeneratorType) {
+    let info: String = generator_type.to_info_string();
+    assert_eq!(WorldGeneratorType::from_info_string(&info), Some(generator_type), "Info string: {}", info);
+}
+
+#[test]
+fn test_generator_info_string_flat() {
+    test_generator_info_string(WorldGeneratorType::Flat);
+}
+
+#[test]
+fn test_generator_info_string_noise() {
+    test_generator_info_string(WorldGeneratorType::default_noise());
+    test_generator_info_string(WorldGeneratorType::Noise { sea_level: -40 });
+}
+
+#[test]
+fn test_generator_info_string_void() {
+    test_generator_info_string(WorldGeneratorType::Void);
+}
+
+#[test]
+fn test_generator_info_string_superflat() {
+    test_generator_info_string(WorldGeneratorType::default_superflat());
+    test_generator_info_string(WorldGeneratorType::Superflat(vec![(BlockType::Sand, 10), (BlockType::Cobblestone, 2)]));
+}
+
+#[test]
+fn test_generator_info_string_unknown() {
+    assert_eq!(WorldGeneratorType::from_info_string("Amplified"), None);
+    assert_eq!(WorldGeneratorType::from_info_string("Superflat 400"), None);
+}
+
+
+#[test]
+fn test_superflat_layers() {
+    let chunk_sizes: (usize, usize, usize) = (8, 16, 8);
+    let chunk = generate_test_chunk(
+        &WorldGeneratorType::Superflat(vec![(BlockType::Bedrock, 1), (BlockType::Stone, 2), (BlockType::Sand, 1)]),
+        chunk_sizes,
+    );
+
+    for x in 0..chunk_sizes.0 {
+        for z in 0..chunk_sizes.2 {
+            assert_eq!(chunk[x][0][z].block_type, BlockType::Bedrock);
+            assert_eq!(chunk[x][1][z].block_type, BlockType::Stone);
+            assert_eq!(chunk[x][2][z].block_type, BlockType::Stone);
+            assert_eq!(chunk[x][3][z].block_type, BlockType::Sand);
+            for y in 4..chunk_sizes.1 {
+                assert_eq!(chunk[x][y][z].block_type, BlockType::Air);
+            }
+        }
+    }
+}
+
+// layers taller than the chunk are cut off instead of going out of bounds
+#[test]
+fn test_superflat_too_tall() {
+    let chunk_sizes: (usize, usize, usize) = (8, 16, 8);
+    let chunk = generate_test_chunk(&WorldGeneratorType::Superflat(vec![(BlockType::Stone, 100)]), chunk_sizes);
+
+    assert_eq!(chunk[0][chunk_sizes.1 - 1][0].block_type, BlockType::Stone);
+}
+
+#[test]
+fn test_void_is_empty() {
+    let chunk_sizes: (usize, usize, usize) = (8, 16, 8);
+    let chunk = generate_test_chunk(&WorldGeneratorType::Void, chunk_sizes);
+
+    for x_vec in chunk.iter() {
+        for y_vec in x_vec.iter() {
+            for block in y_vec.iter() {
+                assert_eq!(block.block_type, BlockType::Air);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_flat_surface_height() {
+    let chunk_sizes: (usize, usize, usize) = (8, 16, 8);
+    let chunk = generate_test_chunk(&WorldGeneratorType::Flat, chunk_sizes);
+    let surface_height: usize = WorldGeneratorType::Flat
+        .create_generator()
+        .get_surface_height(0, 0, chunk_sizes, 1)
+        .unwrap();
+
+    assert_eq!(chunk[0][surface_height][0].block_type, BlockType::Grass);
+    assert_eq!(chunk[0][surface_height + 1][0].block_type, BlockType::Air);
+}
+
+
+// the generator saved in WorldInfo.txt is read back the same
+#[test]
+fn test_world_info_round_trip() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_info");
+
+    let generator_type: WorldGeneratorType = WorldGeneratorType::default_superflat();
+    file_system.save_world_info(42, &generator_type).unwrap();
+
+    assert_eq!(file_system.read_world_info().unwrap(), Some((42, generator_type)));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a world made before the generator was saved was flat, so it keeps being flat, but a new world keeps the default
+#[test]
+fn test_old_world_without_generator_is_flat() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_info_old_world");
+
+    fs::write(path.join("WorldInfo.txt"), "Important Info Goes Here: \n").unwrap();
+    fs::write(path.join("ChunksCreated.txt"), "Total Chunks Created : 0\nChunk Sizes: (16, 64, 16)\nCreated Chunks:\n").unwrap();
+    let mut new_world: World = World::new(String::from("new"), 42, 1, (16, 64, 16));
+    new_world.load_world_info(&mut file_system).unwrap();
+    assert_eq!(new_world.generator_type, WorldGeneratorType::default_noise());
+
+    fs::write(path.join("WorldInfo.txt"), "Important Info Goes Here: \n").unwrap();
+    fs::write(path.join("ChunksCreated.txt"), "Total Chunks Created : 1\nChunk Sizes: (16, 64, 16)\nCreated Chunks:\n0 0\n").unwrap();
+    let mut old_world: World = World::new(String::from("old"), 42, 1, (16, 64, 16));
+    old_world.load_world_info(&mut file_system).unwrap();
+    assert_eq!(old_world.generator_type, WorldGeneratorType::Flat);
+
+    // and it is saved so it stays flat
+    assert_eq!(file_system.read_world_info().unwrap(), Some((42, WorldGeneratorType::Flat)));
+
+    fs::remove_dir_all(&path).unwrap();
+}
+
+// a world info line that is there but cant be read is an error, and the file is left alone so the seed and generator arent lost
+#[test]
+fn test_bad_world_info_is_not_saved_over() {
+    let (mut file_system, path) = make_test_file_system("rust_craft_test_world_info_bad_line");
+    fs::write(path.join("ChunksCreated.txt"), "Total Chunks Created : 1\nChunk Sizes: (16, 64, 16)\nCreated Chunks:\n0 0\n").unwrap();
+
+    let bad_world_infos: [&str; 4] = [
+        "Format Version: 3\nWorld Seed: 42\nGenerator: Amplified\n",
+        "Format Version: 3\nWorld Seed: 42\nGenerator: Superflat 400\n",
+        "Format Version: 3\nWorld Seed: forty two\nGenerator: Flat\n",
+        "Format Version: 3\nWorld Seed: 42\n",
+    ];
+    for world_info in bad_world_infos {
+        fs::write(path.join("WorldInfo.txt"), world_info).unwrap();
+
+        let mut world: World = World::new(String::from("bad"), 7, 1, (16, 64, 16));
+        match world.load_world_info(&mut file_system) {
+            Err(GameError::MalformedFile(error_path, _, _)) => assert_eq!(error_path, path.join("WorldInfo.txt")),
+            other => panic!("Expected a malformed file error for {:?}, got {:?}", world_info, other),
+        }
+        assert_eq!(fs::read_to_string(path.join("WorldInfo.txt")).unwrap(), world_info);
+    }
+
+    // the line number is the line that is wrong
+    fs::write(path.join("WorldInfo.txt"), "Format Version: 3\nWorld Seed: 42\nGenerator: Amplified\n").unwrap();
+    match file_system.read_world_info() {
+        Err(GameError::MalformedFile(_, line_number, _)) => assert_eq!(line_number, 3),
+        other => panic!("Expected a malformed file error, got {:?}", other),
+    }
+
+    fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,116 @@
+extern crate rust_craft;
+use rust_craft::{
+    block_type::*,
+    world::*,
+};
+
+// these all assume chunk sizes of (32 x 256 x 32)
+fn test_get_block_chunk_id(pos_x: i32, pos_z: i32, answer: (i32, i32)) {
+    let world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+    let result: (i32, i32) = world.get_block_chunk_id(pos_x, pos_z);
+
+    assert_eq!(
+        result, answer,
+        "input Position ({}, {}) - Expected ({:?}) - Got ({:?})",
+        pos_x, pos_z, answer, result
+    );
+}
+
+#[test]
+fn test_get_block_chunk_id_1() {
+    test_get_block_chunk_id(0, 0, (0, 0));
+}
+
+#[test]
+fn test_get_block_chunk_id_2() {
+    test_get_block_chunk_id(31, 32, (0, 1));
+}
+
+#[test]
+fn test_get_block_chunk_id_3() {
+    test_get_block_chunk_id(-1, -32, (-1, -1));
+}
+
+#[test]
+fn test_get_block_chunk_id_4() {
+    test_get_block_chunk_id(-100, 68, (-4, 2));
+}
+
+
+#[test]
+fn test_is_in_world_height() {
+    let world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+
+    assert!(world.is_in_world_height(0));
+    assert!(world.is_in_world_height(-128));
+    assert!(world.is_in_world_height(127));
+    assert!(!world.is_in_world_height(128));
+    assert!(!world.is_in_world_height(-129));
+}
+
+
+// with no chunks loaded every get and set should fail
+#[test]
+fn test_get_block_chunk_not_loaded() {
+    let world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+
+    assert_eq!(world.get_block(-5, 0, 40), Err(WorldError::ChunkNotLoaded((-1, 1))));
+}
+
+#[test]
+fn test_set_block_chunk_not_loaded() {
+    let mut world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+
+    assert_eq!(
+        world.set_block(70, 10, -3, BlockType::Stone),
+        Err(WorldError::ChunkNotLoaded((2, -1)))
+    );
+}
+
+#[test]
+fn test_get_set_block_out_of_bounds() {
+    let mut world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+
+    assert_eq!(world.get_block(0, 200, 0), Err(WorldError::OutOfBounds((0, 200, 0))));
+    assert_eq!(
+        world.set_block(0, -200, 0, BlockType::Stone),
+        Err(WorldError::OutOfBounds((0, -200, 0)))
+    );
+}
+
+#[test]
+fn test_set_block_void() {
+    let mut world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+
+    assert_eq!(
+        world.set_block(0, 0, 0, BlockType::Void),
+        Err(WorldError::InvalidBlockType(BlockType::Void))
+    );
+}
+
+#[test]
+fn test_break_block_chunk_not_loaded() {
+    let mut world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+
+    assert_eq!(world.break_block(0, 0, 0), Err(WorldError::ChunkNotLoaded((0, 0))));
+}
+
+#[test]
+fn test_place_block_air() {
+    let mut world: World = World::new("test_world".to_string(), 0, 3, (32, 256, 32));
+
+    assert_eq!(
+        world.place_block(0, 0, 0, BlockType::Air),
+        Err(WorldError::InvalidBlockType(BlockType::Air))
+    );
+}
+
+#[test]
+fn test_get_neighbour_positions() {
+    let neighbours: [(i32, i16, i32); 6] = World::get_neighbour_positions((-1, 5, 31));
+
+    assert_eq!(
+        neighbours,
+        [(-1, 6, 31), (-1, 4, 31), (0, 5, 31), (-2, 5, 31), (-1, 5, 32), (-1, 5, 30)]
+    );
+}